@@ -0,0 +1,224 @@
+//! The bench subcommand, which measures the throughput of algorithms over in-memory buffers,
+//! so that algorithms can be compared on the machine that will run them.
+//!
+//! Every measurement writes the whole buffer into the hasher and finalizes it, again and again,
+//! until the minimum time passed. Finalizing is included, since it dominates with small buffers.
+
+use std::{
+    collections::BTreeMap,
+    num::{NonZeroU64, NonZeroUsize},
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+
+use crate::{
+    hasher::{make_hasher, traits::DynHasher},
+    program_options::{HashAlgorithm, THashOptions},
+};
+
+/// Hashing is timed in batches of at least this many bytes, so that reading the clock doesn't slow down small buffers
+const MIN_BATCH_SIZE: usize = 64 * 1024;
+
+/// The part of the buffer that is hashed before measuring, to fill the caches of the CPU
+const WARM_UP_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub size: usize,
+    pub bytes_per_second: f64,
+    /// Only where the CPU has a cycle counter, see [`cycle_counter`]
+    pub cycles_per_byte: Option<f64>,
+}
+
+impl Measurement {
+    pub fn megabytes_per_second(&self) -> f64 {
+        self.bytes_per_second / 1_000_000.0
+    }
+}
+
+/// Runs the benchmarks with the settings of the command line, and prints the results
+pub fn run_bench(
+    args: &THashOptions,
+    algorithms: &[HashAlgorithm],
+    sizes: &[NonZeroUsize],
+    min_time: Duration,
+    json: bool,
+) -> anyhow::Result<()> {
+    let hashers = make_bench_hashers(algorithms, args.iters(), |algo| args.options_for(algo))?;
+
+    let largest_size = sizes.iter().map(|s| s.get()).max().unwrap_or(0);
+    // Not zeros, in case an algorithm has a shortcut for them
+    let data = (0..largest_size)
+        .map(|i| (i % 251) as u8)
+        .collect::<Vec<_>>();
+
+    let mut results = Vec::new();
+    for (algo, mut hasher) in hashers {
+        eprintln!("thash: measuring {algo}");
+        let measurements = sizes
+            .iter()
+            .map(|size| measure(&mut hasher, &data[..size.get()], min_time))
+            .collect::<Vec<_>>();
+        results.push((algo, measurements));
+    }
+
+    if json {
+        println!("{}", results_json(&results));
+    } else {
+        print!("{}", results_table(sizes, &results));
+    }
+
+    Ok(())
+}
+
+/// Makes the hashers of the algorithms. With no algorithms, all the algorithms are used,
+/// except for those that don't accept the given options, or that require options that weren't given.
+fn make_bench_hashers(
+    algorithms: &[HashAlgorithm],
+    iters: NonZeroU64,
+    options_for: impl Fn(HashAlgorithm) -> anyhow::Result<BTreeMap<String, String>>,
+) -> anyhow::Result<Vec<(HashAlgorithm, Box<dyn DynHasher>)>> {
+    if !algorithms.is_empty() {
+        let mut result = Vec::<(HashAlgorithm, Box<dyn DynHasher>)>::new();
+        for algo in algorithms {
+            if !result.iter().any(|(a, _)| a == algo) {
+                result.push((*algo, make_hasher(*algo, iters, options_for(*algo)?)?));
+            }
+        }
+        return Ok(result);
+    }
+
+    let mut result = Vec::new();
+    for algo in HashAlgorithm::value_variants() {
+        if let Ok(hasher) = make_hasher(*algo, iters, options_for(*algo)?) {
+            result.push((*algo, hasher));
+        }
+    }
+
+    if result.is_empty() {
+        return Err(anyhow::anyhow!("No algorithm accepts the given options"));
+    }
+
+    Ok(result)
+}
+
+/// Hashes the data again and again, for at least the given time, and returns the throughput
+pub fn measure(hasher: &mut impl DynHasher, data: &[u8], min_time: Duration) -> Measurement {
+    hasher.write(&data[..data.len().min(WARM_UP_SIZE)]);
+    std::hint::black_box(hasher.finalize_and_reset());
+
+    let rounds_per_batch = MIN_BATCH_SIZE.div_ceil(data.len().max(1));
+    let mut rounds = 0;
+
+    let start_cycles = cycle_counter();
+    let start = Instant::now();
+    let elapsed = loop {
+        for _ in 0..rounds_per_batch {
+            hasher.write(data);
+            std::hint::black_box(hasher.finalize_and_reset());
+        }
+        rounds += rounds_per_batch;
+
+        let elapsed = start.elapsed();
+        if elapsed >= min_time {
+            break elapsed;
+        }
+    };
+    let cycles = cycle_counter()
+        .zip(start_cycles)
+        .map(|(end, start)| end.wrapping_sub(start));
+
+    let bytes = (rounds * data.len()) as f64;
+    Measurement {
+        size: data.len(),
+        bytes_per_second: bytes / elapsed.as_secs_f64(),
+        cycles_per_byte: cycles.map(|c| c as f64 / bytes),
+    }
+}
+
+/// The time stamp counter, which counts cycles at the nominal frequency of the CPU. With frequency scaling,
+/// the actual cycles differ, like in other benchmarks that count cycles with it.
+#[cfg(target_arch = "x86_64")]
+fn cycle_counter() -> Option<u64> {
+    // SAFETY: Every x86-64 CPU has the time stamp counter
+    Some(unsafe { std::arch::x86_64::_rdtsc() })
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn cycle_counter() -> Option<u64> {
+    None
+}
+
+/// The size in the largest binary unit that divides it, like `4 KiB`
+pub fn size_label(size: usize) -> String {
+    [(1 << 30, "GiB"), (1 << 20, "MiB"), (1 << 10, "KiB")]
+        .into_iter()
+        .find(|(unit, _)| size.is_multiple_of(*unit))
+        .map(|(unit, name)| format!("{} {name}", size / unit))
+        .unwrap_or_else(|| format!("{size} B"))
+}
+
+fn results_table(
+    sizes: &[NonZeroUsize],
+    results: &[(HashAlgorithm, Vec<Measurement>)],
+) -> prettytable::Table {
+    let mut table = prettytable::Table::new();
+
+    let mut header = prettytable::Row::empty();
+    header.add_cell(prettytable::Cell::new("Algorithm"));
+    for size in sizes {
+        let label = size_label(size.get());
+        header.add_cell(prettytable::Cell::new(&format!("{label} MB/s")));
+        header.add_cell(prettytable::Cell::new(&format!("{label} cycles/B")));
+    }
+    table.add_row(header);
+
+    for (algo, measurements) in results {
+        let mut row = prettytable::Row::empty();
+        row.add_cell(prettytable::Cell::new(&algo.to_string()));
+        for measurement in measurements {
+            row.add_cell(prettytable::Cell::new(&format!(
+                "{:.1}",
+                measurement.megabytes_per_second()
+            )));
+            row.add_cell(prettytable::Cell::new(
+                &measurement
+                    .cycles_per_byte
+                    .map(|c| format!("{c:.2}"))
+                    .unwrap_or_else(|| "-".to_string()),
+            ));
+        }
+        table.add_row(row);
+    }
+
+    table
+}
+
+/// The results as a JSON object, with the version of thash, and a list of every measurement
+fn results_json(results: &[(HashAlgorithm, Vec<Measurement>)]) -> String {
+    let measurements = results
+        .iter()
+        .flat_map(|(algo, measurements)| {
+            measurements.iter().map(move |m| {
+                format!(
+                    r#"{{"algorithm":"{algo}","size":{},"megabytes_per_second":{:.3},"cycles_per_byte":{}}}"#,
+                    m.size,
+                    m.megabytes_per_second(),
+                    m.cycles_per_byte
+                        .map(|c| format!("{c:.3}"))
+                        .unwrap_or_else(|| "null".to_string())
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        r#"{{"version":"{}","results":[{}]}}"#,
+        env!("CARGO_PKG_VERSION"),
+        measurements.join(",")
+    )
+}
+
+#[cfg(test)]
+mod tests;
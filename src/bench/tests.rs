@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use crate::{hasher::make_hasher, program_options::HashAlgorithm};
+
+use super::{make_bench_hashers, measure, results_json, size_label, Measurement};
+
+#[test]
+fn size_labels() {
+    assert_eq!(size_label(64), "64 B");
+    assert_eq!(size_label(5000), "5000 B");
+    assert_eq!(size_label(4096), "4 KiB");
+    assert_eq!(size_label(3 << 20), "3 MiB");
+    assert_eq!(size_label(1 << 30), "1 GiB");
+}
+
+#[test]
+fn measurements_are_positive() {
+    let mut hasher = make_hasher(
+        HashAlgorithm::Sha256,
+        1.try_into().unwrap(),
+        Default::default(),
+    )
+    .unwrap();
+
+    for size in [1, 64, 100_000] {
+        let data = vec![1; size];
+        let measurement = measure(&mut hasher, &data, Duration::from_millis(5));
+        assert_eq!(measurement.size, size);
+        assert!(measurement.bytes_per_second.is_finite());
+        assert!(measurement.bytes_per_second > 0.0);
+        if let Some(cycles_per_byte) = measurement.cycles_per_byte {
+            assert!(cycles_per_byte > 0.0);
+        }
+    }
+
+    // The hasher is left reset
+    hasher.write(b"abc");
+    assert_eq!(
+        hex::encode(hasher.finalize_and_reset()),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+}
+
+#[test]
+fn algorithms_that_need_options_are_left_out() {
+    let iters = 1.try_into().unwrap();
+
+    let all = make_bench_hashers(&[], iters, |_| Ok(Default::default())).unwrap();
+    assert!(all.iter().any(|(algo, _)| *algo == HashAlgorithm::Sha256));
+    assert!(!all.iter().any(|(algo, _)| *algo == HashAlgorithm::Kmac128));
+
+    let chosen = make_bench_hashers(
+        &[
+            HashAlgorithm::Md5,
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Md5,
+        ],
+        iters,
+        |_| Ok(Default::default()),
+    )
+    .unwrap();
+    assert_eq!(
+        chosen.iter().map(|(algo, _)| *algo).collect::<Vec<_>>(),
+        [HashAlgorithm::Md5, HashAlgorithm::Sha256]
+    );
+
+    assert!(
+        make_bench_hashers(&[HashAlgorithm::Kmac128], iters, |_| Ok(Default::default())).is_err()
+    );
+}
+
+#[test]
+fn json_output() {
+    let results = [(
+        HashAlgorithm::Sha3_256,
+        vec![
+            Measurement {
+                size: 64,
+                bytes_per_second: 123_456_789.0,
+                cycles_per_byte: Some(12.5),
+            },
+            Measurement {
+                size: 4096,
+                bytes_per_second: 2_000_000.0,
+                cycles_per_byte: None,
+            },
+        ],
+    )];
+
+    assert_eq!(
+        results_json(&results),
+        format!(
+            r#"{{"version":"{}","results":[{{"algorithm":"sha3-256","size":64,"megabytes_per_second":123.457,"cycles_per_byte":12.500}},{{"algorithm":"sha3-256","size":4096,"megabytes_per_second":2.000,"cycles_per_byte":null}}]}}"#,
+            env!("CARGO_PKG_VERSION")
+        )
+    );
+}
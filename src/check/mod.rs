@@ -0,0 +1,356 @@
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufReader},
+    path::PathBuf,
+};
+
+use clap::ValueEnum;
+
+use crate::{
+    hasher::{make_hasher, traits::DynHasher},
+    input::{hash_input, open_file, InputSource},
+    output::{escape_file_name, string_to_digest, unescape_file_name},
+    program_options::{HashAlgorithm, OutputFormat, THashOptions},
+    worker_pool::run_in_order,
+};
+
+/// A single entry parsed from a checksum list
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ChecksumEntry {
+    /// The algorithm named in the line. Only the BSD layout carries this.
+    algo: Option<HashAlgorithm>,
+    digest: String,
+    path: String,
+}
+
+#[derive(Debug, Default)]
+struct CheckSummary {
+    properly_formatted: usize,
+    improperly_formatted: usize,
+    mismatched: usize,
+    unreadable: usize,
+    unsupported: usize,
+    verified: usize,
+}
+
+/// What was found in a single line of a checksum list
+enum LineOutcome {
+    /// Empty lines and comments
+    Skipped,
+    /// The line is neither in the BSD nor in the GNU layout, or its digest cannot be one of the algorithm
+    ImproperlyFormatted,
+    /// No hasher of the algorithm can be made with the given options
+    Unsupported {
+        error: anyhow::Error,
+    },
+    /// The file doesn't exist, and missing files are ignored
+    Missing,
+    Hashed {
+        printed_name: String,
+        matched: bool,
+    },
+    Unreadable {
+        printed_name: String,
+        error: anyhow::Error,
+    },
+}
+
+/// Verifies all the checksum lists given as inputs. Returns whether all the checks passed.
+/// The files of every list are hashed by `--jobs` workers, and reported in the order of the list.
+pub fn run_check(args: &THashOptions) -> anyhow::Result<bool> {
+    if matches!(args.output_format, OutputFormat::Binary) {
+        return Err(anyhow::anyhow!(
+            "Binary output format cannot be used when checking, since checksum lists are text"
+        ));
+    }
+
+    let default_algo = args.hash_algo()?;
+    // Every worker makes its own hashers, but the options are checked before reading any list
+    make_hasher(default_algo, args.iters(), args.options_for(default_algo)?)?;
+
+    let mut all_passed = true;
+
+    for list in args.inputs() {
+        let mut summary = CheckSummary::default();
+
+        let lines = match read_lines(&list) {
+            Ok(lines) => lines,
+            Err(e) => {
+                eprintln!("thash: {}: {:#}", list.display_name(), e);
+                all_passed = false;
+                continue;
+            }
+        };
+
+        run_in_order(
+            args.jobs(),
+            &lines.iter().enumerate().collect::<Vec<_>>(),
+            || Ok(BTreeMap::<HashAlgorithm, Box<dyn DynHasher>>::new()),
+            |hashers, (_, line)| check_line(args, line, default_algo, hashers),
+            |(line_number, _), outcome| {
+                report_line(args, &list, line_number + 1, outcome?, &mut summary);
+                Ok(())
+            },
+        )?;
+
+        all_passed &= report_summary(args, &list, &summary);
+    }
+
+    Ok(all_passed)
+}
+
+fn read_lines(list: &InputSource) -> anyhow::Result<Vec<String>> {
+    let lines = match list {
+        InputSource::Stdin => std::io::stdin().lock().lines().collect::<Result<_, _>>()?,
+        InputSource::File(p) => BufReader::new(open_file(p)?)
+            .lines()
+            .collect::<Result<_, _>>()?,
+    };
+
+    Ok(lines)
+}
+
+/// Parses the line, and hashes the file in it. The hasher of every algorithm is made the first time it's needed.
+fn check_line(
+    args: &THashOptions,
+    line: &str,
+    default_algo: HashAlgorithm,
+    hashers: &mut BTreeMap<HashAlgorithm, Box<dyn DynHasher>>,
+) -> anyhow::Result<LineOutcome> {
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(LineOutcome::Skipped);
+    }
+
+    let entry = match parse_checksum_line(line) {
+        Some(entry) => entry,
+        None => return Ok(LineOutcome::ImproperlyFormatted),
+    };
+
+    let algo = entry.algo.unwrap_or(default_algo);
+    let hasher = match hashers.get_mut(&algo) {
+        Some(hasher) => hasher,
+        None => {
+            let hasher = match args
+                .options_for(algo)
+                .and_then(|options| make_hasher(algo, args.iters(), options))
+            {
+                Ok(hasher) => hasher,
+                Err(error) => return Ok(LineOutcome::Unsupported { error }),
+            };
+            hashers.entry(algo).or_insert(hasher)
+        }
+    };
+
+    Ok(check_entry(args, &entry, hasher))
+}
+
+fn check_entry(
+    args: &THashOptions,
+    entry: &ChecksumEntry,
+    hasher: &mut Box<dyn DynHasher>,
+) -> LineOutcome {
+    let expected = match string_to_digest(&entry.digest, args.output_format) {
+        Some(d) if d.len() == hasher.output_size().get() => d,
+        _ => return LineOutcome::ImproperlyFormatted,
+    };
+
+    let input = InputSource::from_path(PathBuf::from(&entry.path));
+    if let InputSource::File(p) = &input {
+        if args.ignore_missing && !p.exists() {
+            return LineOutcome::Missing;
+        }
+    }
+
+    let printed_name = escape_file_name(&entry.path)
+        .map(|escaped| format!("\\{escaped}"))
+        .unwrap_or_else(|| entry.path.clone());
+
+    match hash_input(hasher, &input, args.recursive, args.input_reader()) {
+        Ok(actual) => LineOutcome::Hashed {
+            printed_name,
+            matched: actual == expected,
+        },
+        Err(error) => LineOutcome::Unreadable {
+            printed_name,
+            error,
+        },
+    }
+}
+
+/// Prints the result of a single line, and counts it in the summary
+fn report_line(
+    args: &THashOptions,
+    list: &InputSource,
+    line_number: usize,
+    outcome: LineOutcome,
+    summary: &mut CheckSummary,
+) {
+    match outcome {
+        LineOutcome::Skipped => (),
+        LineOutcome::ImproperlyFormatted => {
+            summary.improperly_formatted += 1;
+            if args.warn && !args.status {
+                eprintln!(
+                    "thash: {}: {}: improperly formatted checksum line",
+                    list.display_name(),
+                    line_number
+                );
+            }
+        }
+        LineOutcome::Unsupported { error } => {
+            summary.properly_formatted += 1;
+            summary.unsupported += 1;
+            if !args.status {
+                eprintln!(
+                    "thash: {}: {}: {:#}",
+                    list.display_name(),
+                    line_number,
+                    error
+                );
+            }
+        }
+        LineOutcome::Missing => summary.properly_formatted += 1,
+        LineOutcome::Hashed {
+            printed_name,
+            matched,
+        } => {
+            summary.properly_formatted += 1;
+            summary.verified += 1;
+            if matched {
+                if !args.quiet && !args.status {
+                    println!("{printed_name}: OK");
+                }
+            } else {
+                summary.mismatched += 1;
+                if !args.status {
+                    println!("{printed_name}: FAILED");
+                }
+            }
+        }
+        LineOutcome::Unreadable {
+            printed_name,
+            error,
+        } => {
+            summary.properly_formatted += 1;
+            summary.unreadable += 1;
+            if !args.status {
+                eprintln!("thash: {printed_name}: {error:#}");
+                println!("{printed_name}: FAILED open or read");
+            }
+        }
+    }
+}
+
+/// Prints the warnings for a single checksum list. Returns whether the list passed the check.
+fn report_summary(args: &THashOptions, list: &InputSource, summary: &CheckSummary) -> bool {
+    if summary.properly_formatted == 0 {
+        eprintln!(
+            "thash: {}: no properly formatted checksum lines found",
+            list.display_name()
+        );
+        return false;
+    }
+
+    if !args.status {
+        if summary.improperly_formatted > 0 {
+            eprintln!(
+                "thash: WARNING: {} {} improperly formatted",
+                summary.improperly_formatted,
+                plural(summary.improperly_formatted, "line is", "lines are")
+            );
+        }
+        if summary.unreadable > 0 {
+            eprintln!(
+                "thash: WARNING: {} listed {} could not be read",
+                summary.unreadable,
+                plural(summary.unreadable, "file", "files")
+            );
+        }
+        if summary.unsupported > 0 {
+            eprintln!(
+                "thash: WARNING: {} {} could not be checked with the given options",
+                summary.unsupported,
+                plural(summary.unsupported, "line", "lines")
+            );
+        }
+        if summary.mismatched > 0 {
+            eprintln!(
+                "thash: WARNING: {} computed {} did NOT match",
+                summary.mismatched,
+                plural(summary.mismatched, "checksum", "checksums")
+            );
+        }
+    }
+
+    if args.ignore_missing && summary.verified == 0 {
+        if !args.status {
+            eprintln!("thash: {}: no file was verified", list.display_name());
+        }
+        return false;
+    }
+
+    summary.mismatched == 0
+        && summary.unreadable == 0
+        && summary.unsupported == 0
+        && !(args.strict && summary.improperly_formatted > 0)
+}
+
+fn plural<'a>(count: usize, singular: &'a str, plural: &'a str) -> &'a str {
+    if count == 1 {
+        singular
+    } else {
+        plural
+    }
+}
+
+/// Parses a line in either the BSD layout, `ALGO (path) = digest`, or the GNU layout, `<digest>  <path>`.
+/// Like GNU, a line that starts with a backslash has an escaped path.
+fn parse_checksum_line(line: &str) -> Option<ChecksumEntry> {
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+
+    let mut entry = parse_bsd_line(line).or_else(|| parse_gnu_line(line))?;
+
+    if escaped {
+        entry.path = unescape_file_name(&entry.path)?;
+    }
+
+    Some(entry)
+}
+
+fn parse_bsd_line(line: &str) -> Option<ChecksumEntry> {
+    let (tag, rest) = line.split_once(" (")?;
+    let (path, digest) = rest.rsplit_once(") = ")?;
+
+    let algo = HashAlgorithm::from_str(tag, true).ok()?;
+
+    if path.is_empty() || digest.is_empty() {
+        return None;
+    }
+
+    Some(ChecksumEntry {
+        algo: Some(algo),
+        digest: digest.to_string(),
+        path: path.to_string(),
+    })
+}
+
+fn parse_gnu_line(line: &str) -> Option<ChecksumEntry> {
+    let (digest, rest) = line.split_once(' ')?;
+    let path = rest.strip_prefix([' ', '*'])?;
+
+    if digest.is_empty() || path.is_empty() {
+        return None;
+    }
+
+    Some(ChecksumEntry {
+        algo: None,
+        digest: digest.to_string(),
+        path: path.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,61 @@
+use crate::program_options::HashAlgorithm;
+
+use super::{parse_checksum_line, ChecksumEntry};
+
+#[test]
+fn gnu_lines() {
+    assert_eq!(
+        parse_checksum_line("abcd  some file.txt"),
+        Some(ChecksumEntry {
+            algo: None,
+            digest: "abcd".to_string(),
+            path: "some file.txt".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_checksum_line("abcd *file.bin"),
+        Some(ChecksumEntry {
+            algo: None,
+            digest: "abcd".to_string(),
+            path: "file.bin".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_checksum_line("\\abcd  a\\\\b\\nc"),
+        Some(ChecksumEntry {
+            algo: None,
+            digest: "abcd".to_string(),
+            path: "a\\b\nc".to_string(),
+        })
+    );
+    assert_eq!(parse_checksum_line("abcd"), None);
+    assert_eq!(parse_checksum_line("abcd  "), None);
+    assert_eq!(parse_checksum_line("abcd -file"), None);
+    assert_eq!(parse_checksum_line("\\abcd  a\\qb"), None);
+}
+
+#[test]
+fn bsd_lines() {
+    assert_eq!(
+        parse_checksum_line("SHA256 (file (1).txt) = abcd"),
+        Some(ChecksumEntry {
+            algo: Some(HashAlgorithm::Sha256),
+            digest: "abcd".to_string(),
+            path: "file (1).txt".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_checksum_line("SHA3-512 (file) = abcd"),
+        Some(ChecksumEntry {
+            algo: Some(HashAlgorithm::Sha3_512),
+            digest: "abcd".to_string(),
+            path: "file".to_string(),
+        })
+    );
+    assert_eq!(
+        parse_checksum_line("BLAKE2b (file) = abcd").unwrap().algo,
+        Some(HashAlgorithm::Blake2b)
+    );
+    // Unknown algorithms are not BSD lines, and are not valid GNU lines either
+    assert_eq!(parse_checksum_line("NOTANALGO (file) = abcd"), None);
+}
@@ -1,8 +1,15 @@
-use std::num::{NonZeroU64, NonZeroUsize};
+use std::{
+    num::{NonZeroU64, NonZeroUsize},
+    path::Path,
+};
 
-use k12::digest::typenum::Unsigned;
+use sha3::digest::typenum::Unsigned;
 
-use crate::hashing_lib::{sized_hasher::SizedHasher, unsized_hasher::UnsizedHasher};
+use crate::hashing_lib::{
+    hmac::Hmac,
+    sized_hasher::{BlockSizedHasher, SizedHasher},
+    unsized_hasher::UnsizedHasher,
+};
 
 pub struct GenericSizedHasher<H: SizedHasher> {
     hasher: H,
@@ -11,10 +18,12 @@ pub struct GenericSizedHasher<H: SizedHasher> {
 
 impl<H: SizedHasher> GenericSizedHasher<H> {
     pub fn new(iters: NonZeroU64) -> Self {
-        Self {
-            hasher: H::new(),
-            iters,
-        }
+        Self::from_hasher(H::new(), iters)
+    }
+
+    /// Uses a hasher that was already constructed, for hashers that need more than the defaults
+    pub fn from_hasher(hasher: H, iters: NonZeroU64) -> Self {
+        Self { hasher, iters }
     }
 
     pub fn write(&mut self, data: &[u8]) -> &mut Self {
@@ -45,11 +54,8 @@ pub struct GenericUnsizedHasher<H: UnsizedHasher> {
 }
 
 impl<H: UnsizedHasher> GenericUnsizedHasher<H> {
-    pub fn new(output_size: NonZeroUsize, iters: NonZeroU64) -> Self {
-        Self {
-            hasher: H::new(output_size),
-            iters,
-        }
+    pub fn from_hasher(hasher: H, iters: NonZeroU64) -> Self {
+        Self { hasher, iters }
     }
 
     pub fn write(&mut self, data: &[u8]) -> &mut Self {
@@ -70,4 +76,55 @@ impl<H: UnsizedHasher> GenericUnsizedHasher<H> {
     pub fn output_size(&self) -> NonZeroUsize {
         self.hasher.output_size()
     }
+
+    pub fn write_file(&mut self, path: &Path) -> anyhow::Result<bool> {
+        self.hasher.write_file(path)
+    }
+
+    pub fn begin_input(&mut self, len: u64) {
+        self.hasher.begin_input(len)
+    }
+
+    pub fn end_input(&mut self) -> anyhow::Result<()> {
+        self.hasher.end_input()
+    }
+
+    pub fn end_tuple_element(&mut self) -> anyhow::Result<()> {
+        self.hasher.end_tuple_element()
+    }
+}
+
+pub struct GenericHmacHasher<H: BlockSizedHasher> {
+    hmac: Hmac<H>,
+    iters: NonZeroU64,
+}
+
+impl<H: BlockSizedHasher> GenericHmacHasher<H> {
+    pub fn new(key: &[u8], iters: NonZeroU64) -> Self {
+        Self {
+            hmac: Hmac::new(key),
+            iters,
+        }
+    }
+
+    pub fn write(&mut self, data: &[u8]) -> &mut Self {
+        self.hmac.write(data);
+        self
+    }
+
+    pub fn finalize_and_reset(&mut self) -> Vec<u8> {
+        let mut hash = self.hmac.finalize_and_reset();
+        // We authenticated the data once, let's do the remaining iterations with the same key
+        for _ in 0..self.iters.get() - 1 {
+            self.hmac.write(hash);
+            hash = self.hmac.finalize_and_reset();
+        }
+        hash.to_vec()
+    }
+
+    pub fn output_size(&self) -> NonZeroUsize {
+        <H::OutputSize as Unsigned>::USIZE
+            .try_into()
+            .expect("Sized cannot be zero or less")
+    }
 }
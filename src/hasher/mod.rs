@@ -5,14 +5,41 @@ pub mod traits;
 use std::{collections::BTreeMap, num::NonZeroU64};
 
 use crate::hashing_lib::{
+    checksum::{Adler32, Cksum, Crc32, Crc32c, Crc64, Crc64Ecma, Crc64Nvme, CustomCrc},
+    fast_hash::{
+        SeededHasher, SipHash13, SipHash24, SipHashHasher, Wyhash, Xxh32, Xxh3Hasher, Xxh3_128,
+        Xxh3_64, Xxh64,
+    },
+    kangaroo_twelve::{KangarooTwelve, Kt128, Kt256, TurboShake, TurboShakeVariant},
     sized_hasher::{
-        Blake2b, Blake2s, Blake3, Md5, Sha1, Sha224, Sha256, Sha384, Sha3_224, Sha3_256, Sha3_384,
-        Sha3_512, Sha512,
+        AsconHash256, Blake224, Blake256, Blake2b512, Blake2s256, Blake384, Blake512,
+        BlockSizedHasher, Gost94, Gost94CryptoPro, Groestl224, Groestl256, Groestl384, Groestl512,
+        Jh224, Jh256, Jh384, Jh512, Keccak224, Keccak256, Keccak384, Keccak512, Md5, Ripemd160,
+        Sha1, Sha224, Sha256, Sha384, Sha3_224, Sha3_256, Sha3_384, Sha3_512, Sha512, Sha512_224,
+        Sha512_256, SizedHasher, Sm3, Streebog256, Streebog512, Tiger, Tiger2, Whirlpool,
+    },
+    skein::{Skein1024, Skein256, Skein512},
+    sp800_185::{Kmac, ParallelHash, Sp800185Variant, Sp800185_128, Sp800185_256, TupleHash},
+    unsized_hasher::{
+        AsconXof128, Blake2Hasher, Blake2b, Blake2s, Blake3, CShake128, CShake256, CShakeHasher,
+        Shake128, Shake256, ShakeHasher, UnsizedHasher, VariableOutputHasher,
     },
-    unsized_hasher::K12,
 };
-use generic::{GenericSizedHasher, GenericUnsizedHasher};
-use options::{k12_options::K12Options, traits::HashingOptions};
+use generic::{GenericHmacHasher, GenericSizedHasher, GenericUnsizedHasher};
+use options::{
+    blake2_options::Blake2Options,
+    blake3_options::{Blake3Mode, Blake3Options},
+    checksum_options::{ChecksumOptions, CrcOptions},
+    fast_hash_options::{SeedOptions, SipHashOptions, Xxh3Mode, Xxh3Options},
+    fixed_options::FixedOptions,
+    hmac_options::HmacOptions,
+    k12_options::K12Options,
+    output_size_options::OutputSizeOptions,
+    shake_options::{CShakeOptions, ShakeOptions},
+    sp800_185_options::{KmacOptions, ParallelHashOptions, TupleHashOptions},
+    traits::HashingOptions,
+    turbo_shake_options::TurboShakeOptions,
+};
 use traits::DynHasher;
 
 use crate::program_options::HashAlgorithm;
@@ -23,160 +50,389 @@ pub fn make_hasher(
     options: BTreeMap<String, String>,
 ) -> anyhow::Result<Box<dyn DynHasher>> {
     let f: Box<dyn DynHasher> = match algo {
-        HashAlgorithm::Blake2b => Box::new(make_blake2b_hasher(options, iters)?),
-        HashAlgorithm::Blake2s => Box::new(make_blake2s_hasher(options, iters)?),
+        HashAlgorithm::Adler32 => Box::new(make_checksum_hasher::<Adler32>(options, iters)?),
+        // Ascon-Hash256 takes no key. Its block is the 8-byte rate of the sponge, and HMAC needs blocks
+        // that are at least as large as the digest.
+        HashAlgorithm::AsconHash256 => Box::new(make_fixed_hasher::<AsconHash256>(options, iters)?),
+        HashAlgorithm::AsconXof128 => {
+            Box::new(make_variable_output_hasher::<AsconXof128>(options, iters)?)
+        }
+        HashAlgorithm::Blake2b => make_blake2b_hasher(options, iters)?,
+        HashAlgorithm::Blake2s => make_blake2s_hasher(options, iters)?,
         HashAlgorithm::Blake3 => Box::new(make_blake3_hasher(options, iters)?),
-        HashAlgorithm::K12 => Box::new(make_k12_hasher(options, iters)?),
-        HashAlgorithm::Md5 => Box::new(make_md5_hasher(options, iters)?),
-        HashAlgorithm::Sha1 => Box::new(make_sha1_hasher(options, iters)?),
-        HashAlgorithm::Sha224 => Box::new(make_sha224_hasher(options, iters)?),
-        HashAlgorithm::Sha256 => Box::new(make_sha256_hasher(options, iters)?),
-        HashAlgorithm::Sha384 => Box::new(make_sha384_hasher(options, iters)?),
-        HashAlgorithm::Sha512 => Box::new(make_sha512_hasher(options, iters)?),
-        HashAlgorithm::Sha3_224 => Box::new(make_sha3_224_hasher(options, iters)?),
-        HashAlgorithm::Sha3_256 => Box::new(make_sha3_256_hasher(options, iters)?),
-        HashAlgorithm::Sha3_384 => Box::new(make_sha3_384_hasher(options, iters)?),
-        HashAlgorithm::Sha3_512 => Box::new(make_sha3_512_hasher(options, iters)?),
+        HashAlgorithm::Cksum => Box::new(make_checksum_hasher::<Cksum>(options, iters)?),
+        HashAlgorithm::Crc => Box::new(make_crc_hasher(options, iters)?),
+        HashAlgorithm::Crc32 => Box::new(make_checksum_hasher::<Crc32>(options, iters)?),
+        HashAlgorithm::Crc32c => Box::new(make_checksum_hasher::<Crc32c>(options, iters)?),
+        HashAlgorithm::Crc64 => Box::new(make_checksum_hasher::<Crc64>(options, iters)?),
+        HashAlgorithm::Crc64Ecma => Box::new(make_checksum_hasher::<Crc64Ecma>(options, iters)?),
+        HashAlgorithm::Crc64Nvme => Box::new(make_checksum_hasher::<Crc64Nvme>(options, iters)?),
+        HashAlgorithm::Cshake128 => Box::new(make_cshake_hasher::<CShake128>(options, iters)?),
+        HashAlgorithm::Cshake256 => Box::new(make_cshake_hasher::<CShake256>(options, iters)?),
+        HashAlgorithm::K12 => Box::new(make_k12_hasher::<Kt128>(options, iters)?),
+        HashAlgorithm::Kmac128 => Box::new(make_kmac_hasher::<Sp800185_128>(options, iters)?),
+        HashAlgorithm::Kmac256 => Box::new(make_kmac_hasher::<Sp800185_256>(options, iters)?),
+        HashAlgorithm::Kt256 => Box::new(make_k12_hasher::<Kt256>(options, iters)?),
+        HashAlgorithm::Parallelhash128 => {
+            Box::new(make_parallel_hash_hasher::<Sp800185_128>(options, iters)?)
+        }
+        HashAlgorithm::Parallelhash256 => {
+            Box::new(make_parallel_hash_hasher::<Sp800185_256>(options, iters)?)
+        }
+        HashAlgorithm::Shake128 => Box::new(make_shake_hasher::<Shake128>(options, iters)?),
+        HashAlgorithm::Shake256 => Box::new(make_shake_hasher::<Shake256>(options, iters)?),
+        HashAlgorithm::Siphash13 => Box::new(make_siphash_hasher::<SipHash13>(options, iters)?),
+        HashAlgorithm::Siphash24 => Box::new(make_siphash_hasher::<SipHash24>(options, iters)?),
+        HashAlgorithm::Skein256 => {
+            Box::new(make_variable_output_hasher::<Skein256>(options, iters)?)
+        }
+        HashAlgorithm::Skein512 => {
+            Box::new(make_variable_output_hasher::<Skein512>(options, iters)?)
+        }
+        HashAlgorithm::Skein1024 => {
+            Box::new(make_variable_output_hasher::<Skein1024>(options, iters)?)
+        }
+        HashAlgorithm::Tuplehash128 => {
+            Box::new(make_tuple_hash_hasher::<Sp800185_128>(options, iters)?)
+        }
+        HashAlgorithm::Tuplehash256 => {
+            Box::new(make_tuple_hash_hasher::<Sp800185_256>(options, iters)?)
+        }
+        HashAlgorithm::Turboshake128 => Box::new(make_turbo_shake_hasher::<Kt128>(options, iters)?),
+        HashAlgorithm::Turboshake256 => Box::new(make_turbo_shake_hasher::<Kt256>(options, iters)?),
+        HashAlgorithm::Wyhash => Box::new(make_seeded_hasher::<Wyhash>(options, iters)?),
+        HashAlgorithm::Xxh32 => Box::new(make_seeded_hasher::<Xxh32>(options, iters)?),
+        HashAlgorithm::Xxh64 => Box::new(make_seeded_hasher::<Xxh64>(options, iters)?),
+        HashAlgorithm::Xxh3_64 => Box::new(make_xxh3_hasher::<Xxh3_64>(options, iters)?),
+        HashAlgorithm::Xxh3_128 => Box::new(make_xxh3_hasher::<Xxh3_128>(options, iters)?),
+        _ => visit_hmac_hasher(algo, HmacCapableHasherMaker { options, iters })
+            .expect("Every other algorithm can be used as HMAC")?,
     };
 
     Ok(f)
 }
 
-fn make_sha1_hasher(
+fn make_blake2b_hasher(
     options: BTreeMap<String, String>,
     iters: NonZeroU64,
-) -> anyhow::Result<GenericSizedHasher<Sha1>> {
-    ensure_empty_options(&options)?;
+) -> anyhow::Result<Box<dyn DynHasher>> {
+    make_blake2_hasher::<Blake2b, Blake2b512>(options, iters)
+}
 
-    Ok(GenericSizedHasher::new(iters))
+fn make_blake2s_hasher(
+    options: BTreeMap<String, String>,
+    iters: NonZeroU64,
+) -> anyhow::Result<Box<dyn DynHasher>> {
+    make_blake2_hasher::<Blake2s, Blake2s256>(options, iters)
 }
 
-fn make_sha224_hasher(
+/// Creates the BLAKE2 hasher with its parameters, or HMAC with `HmacH` if an HMAC key is provided
+fn make_blake2_hasher<H: Blake2Hasher + 'static, HmacH: BlockSizedHasher + 'static>(
     options: BTreeMap<String, String>,
     iters: NonZeroU64,
-) -> anyhow::Result<GenericSizedHasher<Sha224>> {
-    ensure_empty_options(&options)?;
+) -> anyhow::Result<Box<dyn DynHasher>> {
+    let parsed_options = Blake2Options::<H>::parse(options)?;
 
-    Ok(GenericSizedHasher::new(iters))
+    if let Some(key) = parsed_options.hmac_key {
+        return Ok(Box::new(GenericHmacHasher::<HmacH>::new(&key, iters)));
+    }
+
+    let hasher = H::with_parameters(
+        parsed_options.output_size,
+        &parsed_options.key,
+        &parsed_options.salt,
+        &parsed_options.personal,
+    );
+
+    Ok(Box::new(GenericUnsizedHasher::from_hasher(hasher, iters)))
 }
 
-fn make_sha256_hasher(
+fn make_blake3_hasher(
     options: BTreeMap<String, String>,
     iters: NonZeroU64,
-) -> anyhow::Result<GenericSizedHasher<Sha256>> {
-    ensure_empty_options(&options)?;
+) -> anyhow::Result<GenericUnsizedHasher<Blake3>> {
+    let parsed_options = Blake3Options::parse(options)?;
+
+    let hasher = match parsed_options.mode {
+        Blake3Mode::Hash => Blake3::new(parsed_options.output_size),
+        Blake3Mode::KeyedHash(key) => Blake3::new_keyed(&key, parsed_options.output_size),
+        Blake3Mode::DeriveKey(context) => {
+            Blake3::new_derive_key(&context, parsed_options.output_size)
+        }
+    }
+    .with_output_offset(parsed_options.output_offset)
+    .with_threads(parsed_options.threads)?;
 
-    Ok(GenericSizedHasher::new(iters))
+    Ok(GenericUnsizedHasher::from_hasher(hasher, iters))
 }
 
-fn make_sha384_hasher(
+fn make_k12_hasher<V: TurboShakeVariant>(
     options: BTreeMap<String, String>,
     iters: NonZeroU64,
-) -> anyhow::Result<GenericSizedHasher<Sha384>> {
-    ensure_empty_options(&options)?;
+) -> anyhow::Result<GenericUnsizedHasher<KangarooTwelve<V>>> {
+    let parsed_options = K12Options::<V>::parse(options)?;
 
-    Ok(GenericSizedHasher::new(iters))
+    let hasher = KangarooTwelve::with_customization(
+        &parsed_options.customization,
+        parsed_options.output_size,
+    );
+
+    Ok(GenericUnsizedHasher::from_hasher(hasher, iters))
 }
 
-fn make_sha512_hasher(
+fn make_turbo_shake_hasher<V: TurboShakeVariant>(
     options: BTreeMap<String, String>,
     iters: NonZeroU64,
-) -> anyhow::Result<GenericSizedHasher<Sha512>> {
-    ensure_empty_options(&options)?;
+) -> anyhow::Result<GenericUnsizedHasher<TurboShake<V>>> {
+    let parsed_options = TurboShakeOptions::<V>::parse(options)?;
 
-    Ok(GenericSizedHasher::new(iters))
+    let hasher = TurboShake::with_domain_separation(
+        parsed_options.domain_separation,
+        parsed_options.output_size,
+    );
+
+    Ok(GenericUnsizedHasher::from_hasher(hasher, iters))
 }
 
-fn make_sha3_224_hasher(
+fn make_shake_hasher<H: ShakeHasher>(
     options: BTreeMap<String, String>,
     iters: NonZeroU64,
-) -> anyhow::Result<GenericSizedHasher<Sha3_224>> {
-    ensure_empty_options(&options)?;
+) -> anyhow::Result<GenericUnsizedHasher<H>> {
+    let parsed_options = ShakeOptions::<H>::parse(options)?;
 
-    Ok(GenericSizedHasher::new(iters))
+    Ok(GenericUnsizedHasher::from_hasher(
+        H::new(parsed_options.output_size),
+        iters,
+    ))
 }
 
-fn make_sha3_256_hasher(
+fn make_variable_output_hasher<H: VariableOutputHasher>(
     options: BTreeMap<String, String>,
     iters: NonZeroU64,
-) -> anyhow::Result<GenericSizedHasher<Sha3_256>> {
-    ensure_empty_options(&options)?;
+) -> anyhow::Result<GenericUnsizedHasher<H>> {
+    let parsed_options = OutputSizeOptions::<H>::parse(options)?;
 
-    Ok(GenericSizedHasher::new(iters))
+    Ok(GenericUnsizedHasher::from_hasher(
+        H::new(parsed_options.output_size),
+        iters,
+    ))
 }
 
-fn make_sha3_384_hasher(
+fn make_cshake_hasher<H: CShakeHasher>(
     options: BTreeMap<String, String>,
     iters: NonZeroU64,
-) -> anyhow::Result<GenericSizedHasher<Sha3_384>> {
-    ensure_empty_options(&options)?;
+) -> anyhow::Result<GenericUnsizedHasher<H>> {
+    let parsed_options = CShakeOptions::<H>::parse(options)?;
 
-    Ok(GenericSizedHasher::new(iters))
+    let hasher = H::with_customization(
+        &parsed_options.function_name,
+        &parsed_options.customization,
+        parsed_options.output_size,
+    );
+
+    Ok(GenericUnsizedHasher::from_hasher(hasher, iters))
 }
 
-fn make_sha3_512_hasher(
+fn make_kmac_hasher<V: Sp800185Variant>(
     options: BTreeMap<String, String>,
     iters: NonZeroU64,
-) -> anyhow::Result<GenericSizedHasher<Sha3_512>> {
-    ensure_empty_options(&options)?;
+) -> anyhow::Result<GenericUnsizedHasher<Kmac<V>>> {
+    let parsed_options = KmacOptions::<V>::parse(options)?;
 
-    Ok(GenericSizedHasher::new(iters))
+    let hasher = Kmac::with_key(
+        &parsed_options.key,
+        &parsed_options.customization,
+        parsed_options.output_size,
+    );
+
+    Ok(GenericUnsizedHasher::from_hasher(hasher, iters))
 }
 
-fn make_blake2b_hasher(
+fn make_tuple_hash_hasher<V: Sp800185Variant>(
     options: BTreeMap<String, String>,
     iters: NonZeroU64,
-) -> anyhow::Result<GenericSizedHasher<Blake2b>> {
-    ensure_empty_options(&options)?;
+) -> anyhow::Result<GenericUnsizedHasher<TupleHash<V>>> {
+    let parsed_options = TupleHashOptions::<V>::parse(options)?;
 
-    Ok(GenericSizedHasher::new(iters))
+    let hasher =
+        TupleHash::with_customization(&parsed_options.customization, parsed_options.output_size);
+
+    Ok(GenericUnsizedHasher::from_hasher(hasher, iters))
 }
 
-fn make_blake2s_hasher(
+fn make_parallel_hash_hasher<V: Sp800185Variant>(
+    options: BTreeMap<String, String>,
+    iters: NonZeroU64,
+) -> anyhow::Result<GenericUnsizedHasher<ParallelHash<V>>> {
+    let parsed_options = ParallelHashOptions::<V>::parse(options)?;
+
+    let hasher = ParallelHash::with_parameters(
+        parsed_options.block_size,
+        &parsed_options.customization,
+        parsed_options.output_size,
+    )
+    .with_threads(parsed_options.threads)?;
+
+    Ok(GenericUnsizedHasher::from_hasher(hasher, iters))
+}
+
+fn make_checksum_hasher<H: SizedHasher + 'static>(
     options: BTreeMap<String, String>,
     iters: NonZeroU64,
-) -> anyhow::Result<GenericSizedHasher<Blake2s>> {
-    ensure_empty_options(&options)?;
+) -> anyhow::Result<GenericSizedHasher<H>> {
+    ChecksumOptions::<H>::parse(options)?;
 
     Ok(GenericSizedHasher::new(iters))
 }
 
-fn make_blake3_hasher(
+fn make_fixed_hasher<H: SizedHasher + 'static>(
     options: BTreeMap<String, String>,
     iters: NonZeroU64,
-) -> anyhow::Result<GenericSizedHasher<Blake3>> {
-    ensure_empty_options(&options)?;
+) -> anyhow::Result<GenericSizedHasher<H>> {
+    FixedOptions::<H>::parse(options)?;
 
     Ok(GenericSizedHasher::new(iters))
 }
 
-fn make_k12_hasher<'a>(
+fn make_crc_hasher(
     options: BTreeMap<String, String>,
     iters: NonZeroU64,
-) -> anyhow::Result<GenericUnsizedHasher<K12<'a>>> {
-    let parsed_options = K12Options::parse(options)?;
+) -> anyhow::Result<GenericUnsizedHasher<CustomCrc>> {
+    let parsed_options = CrcOptions::parse(options)?;
 
-    Ok(GenericUnsizedHasher::new(parsed_options.output_size, iters))
+    Ok(GenericUnsizedHasher::from_hasher(
+        CustomCrc::with_parameters(parsed_options.parameters),
+        iters,
+    ))
 }
 
-fn make_md5_hasher(
+fn make_seeded_hasher<H: SeededHasher>(
     options: BTreeMap<String, String>,
     iters: NonZeroU64,
-) -> anyhow::Result<GenericSizedHasher<Md5>> {
-    ensure_empty_options(&options)?;
+) -> anyhow::Result<GenericSizedHasher<H>> {
+    let parsed_options = SeedOptions::<H>::parse(options)?;
 
-    Ok(GenericSizedHasher::new(iters))
+    Ok(GenericSizedHasher::from_hasher(
+        H::with_seed(parsed_options.seed),
+        iters,
+    ))
 }
 
-fn ensure_empty_options(opts: &BTreeMap<String, String>) -> anyhow::Result<()> {
-    if !opts.is_empty() {
-        return Err(anyhow::anyhow!(
-            "The selected algorithm does not have any options"
-        ));
+fn make_xxh3_hasher<H: Xxh3Hasher>(
+    options: BTreeMap<String, String>,
+    iters: NonZeroU64,
+) -> anyhow::Result<GenericSizedHasher<H>> {
+    let parsed_options = Xxh3Options::<H>::parse(options)?;
+
+    let hasher = match parsed_options.mode {
+        Xxh3Mode::Seed(seed) => H::with_seed(seed),
+        Xxh3Mode::Secret(secret) => H::with_secret(&secret),
+    };
+
+    Ok(GenericSizedHasher::from_hasher(hasher, iters))
+}
+
+fn make_siphash_hasher<H: SipHashHasher>(
+    options: BTreeMap<String, String>,
+    iters: NonZeroU64,
+) -> anyhow::Result<GenericUnsizedHasher<H>> {
+    let parsed_options = SipHashOptions::<H>::parse(options)?;
+
+    let hasher = H::with_key(&parsed_options.key, parsed_options.output_size);
+
+    Ok(GenericUnsizedHasher::from_hasher(hasher, iters))
+}
+
+/// Something to do with the hasher of an algorithm that can be used as HMAC, like hashing with a key, or HKDF
+pub trait HmacHasherVisitor {
+    type Output;
+
+    fn visit<H: BlockSizedHasher + 'static>(self) -> Self::Output;
+}
+
+/// Calls the visitor with the hasher that HMAC uses for the algorithm. This is the only list of the algorithms
+/// that can be used as HMAC. Returns None for the other algorithms.
+pub fn visit_hmac_hasher<V: HmacHasherVisitor>(
+    algo: HashAlgorithm,
+    visitor: V,
+) -> Option<V::Output> {
+    let result = match algo {
+        HashAlgorithm::Md5 => visitor.visit::<Md5>(),
+        HashAlgorithm::Sha1 => visitor.visit::<Sha1>(),
+        HashAlgorithm::Sha224 => visitor.visit::<Sha224>(),
+        HashAlgorithm::Sha256 => visitor.visit::<Sha256>(),
+        HashAlgorithm::Sha384 => visitor.visit::<Sha384>(),
+        HashAlgorithm::Sha512 => visitor.visit::<Sha512>(),
+        HashAlgorithm::Sha512_224 => visitor.visit::<Sha512_224>(),
+        HashAlgorithm::Sha512_256 => visitor.visit::<Sha512_256>(),
+        HashAlgorithm::Sha3_224 => visitor.visit::<Sha3_224>(),
+        HashAlgorithm::Sha3_256 => visitor.visit::<Sha3_256>(),
+        HashAlgorithm::Sha3_384 => visitor.visit::<Sha3_384>(),
+        HashAlgorithm::Sha3_512 => visitor.visit::<Sha3_512>(),
+        HashAlgorithm::Keccak224 => visitor.visit::<Keccak224>(),
+        HashAlgorithm::Keccak256 => visitor.visit::<Keccak256>(),
+        HashAlgorithm::Keccak384 => visitor.visit::<Keccak384>(),
+        HashAlgorithm::Keccak512 => visitor.visit::<Keccak512>(),
+        HashAlgorithm::Ripemd160 => visitor.visit::<Ripemd160>(),
+        HashAlgorithm::Whirlpool => visitor.visit::<Whirlpool>(),
+        HashAlgorithm::Tiger => visitor.visit::<Tiger>(),
+        HashAlgorithm::Tiger2 => visitor.visit::<Tiger2>(),
+        HashAlgorithm::Sm3 => visitor.visit::<Sm3>(),
+        HashAlgorithm::Streebog256 => visitor.visit::<Streebog256>(),
+        HashAlgorithm::Streebog512 => visitor.visit::<Streebog512>(),
+        HashAlgorithm::Gost94 => visitor.visit::<Gost94>(),
+        HashAlgorithm::Gost94Cryptopro => visitor.visit::<Gost94CryptoPro>(),
+        HashAlgorithm::Blake2b => visitor.visit::<Blake2b512>(),
+        HashAlgorithm::Blake2s => visitor.visit::<Blake2s256>(),
+        HashAlgorithm::Blake224 => visitor.visit::<Blake224>(),
+        HashAlgorithm::Blake256 => visitor.visit::<Blake256>(),
+        HashAlgorithm::Blake384 => visitor.visit::<Blake384>(),
+        HashAlgorithm::Blake512 => visitor.visit::<Blake512>(),
+        HashAlgorithm::Groestl224 => visitor.visit::<Groestl224>(),
+        HashAlgorithm::Groestl256 => visitor.visit::<Groestl256>(),
+        HashAlgorithm::Groestl384 => visitor.visit::<Groestl384>(),
+        HashAlgorithm::Groestl512 => visitor.visit::<Groestl512>(),
+        HashAlgorithm::Jh224 => visitor.visit::<Jh224>(),
+        HashAlgorithm::Jh256 => visitor.visit::<Jh256>(),
+        HashAlgorithm::Jh384 => visitor.visit::<Jh384>(),
+        HashAlgorithm::Jh512 => visitor.visit::<Jh512>(),
+        _ => return None,
+    };
+
+    Some(result)
+}
+
+/// Makes the hasher of an algorithm whose only option is the key of HMAC.
+/// BLAKE2 has parameters of its own, so it's made with `make_blake2_hasher` instead.
+struct HmacCapableHasherMaker {
+    options: BTreeMap<String, String>,
+    iters: NonZeroU64,
+}
+
+impl HmacHasherVisitor for HmacCapableHasherMaker {
+    type Output = anyhow::Result<Box<dyn DynHasher>>;
+
+    fn visit<H: BlockSizedHasher + 'static>(self) -> Self::Output {
+        make_hmac_capable_hasher::<H>(self.options, self.iters)
     }
+}
+
+/// Creates the hasher, or HMAC with the hasher if a key is provided in the options
+fn make_hmac_capable_hasher<H: BlockSizedHasher + 'static>(
+    options: BTreeMap<String, String>,
+    iters: NonZeroU64,
+) -> anyhow::Result<Box<dyn DynHasher>> {
+    let parsed_options = HmacOptions::<H>::parse(options)?;
+
+    let result: Box<dyn DynHasher> = match parsed_options.key {
+        Some(key) => Box::new(GenericHmacHasher::<H>::new(&key, iters)),
+        None => Box::new(GenericSizedHasher::<H>::new(iters)),
+    };
 
-    Ok(())
+    Ok(result)
 }
 
 #[cfg(test)]
+// The tests convert option maps with `into()`, whatever type the options are passed as
+#[allow(clippy::useless_conversion)]
 mod tests;
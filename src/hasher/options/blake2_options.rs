@@ -0,0 +1,140 @@
+use std::{collections::BTreeMap, marker::PhantomData, num::NonZeroUsize};
+
+use crate::hashing_lib::unsized_hasher::Blake2Hasher;
+
+use super::{
+    common::{parse_bytes_option, parse_option, BytesEncoding, KEY_KEY, OUTPUT_SIZE_KEY, SALT_KEY},
+    traits::HashingOptions,
+};
+
+pub const PERSONAL_KEY: &str = "personal";
+pub const BLAKE2_KEY_KEY: &str = "blake2-key";
+
+/// Options of BLAKE2. `key` selects HMAC, like with the other algorithms, while `blake2-key`
+/// selects the keyed hashing of BLAKE2 itself. HMAC uses BLAKE2 without parameters.
+pub struct Blake2Options<H: Blake2Hasher> {
+    pub output_size: NonZeroUsize,
+    pub hmac_key: Option<Vec<u8>>,
+    pub key: Vec<u8>,
+    pub salt: Vec<u8>,
+    pub personal: Vec<u8>,
+    _phantom: PhantomData<H>,
+}
+
+/// Parses a bytes option, and rejects it if it's longer than `max_size`
+fn parse_limited_bytes_option(
+    options: &BTreeMap<String, String>,
+    option_key: &str,
+    algo_name: &str,
+    max_size: usize,
+) -> anyhow::Result<Vec<u8>> {
+    let value = parse_bytes_option(options, option_key, BytesEncoding::Hex)?.unwrap_or_default();
+
+    if value.len() > max_size {
+        return Err(anyhow::anyhow!(
+            "Option `{option_key}` for {algo_name} can be at most {max_size} bytes, but {} bytes were provided",
+            value.len()
+        ));
+    }
+
+    Ok(value)
+}
+
+impl<H: Blake2Hasher> TryFrom<BTreeMap<String, String>> for Blake2Options<H> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let default_output_size =
+            NonZeroUsize::new(H::MAX_OUTPUT_SIZE).expect("Blake2 output size is never zero");
+        let output_size =
+            parse_option::<NonZeroUsize>(&options, OUTPUT_SIZE_KEY, default_output_size)?;
+
+        if output_size.get() > H::MAX_OUTPUT_SIZE {
+            return Err(anyhow::anyhow!(
+                "Option `{OUTPUT_SIZE_KEY}` for {} must be between 1 and {}, but {output_size} was provided",
+                H::algorithm_name(),
+                H::MAX_OUTPUT_SIZE
+            ));
+        }
+
+        let hmac_key = parse_bytes_option(&options, KEY_KEY, BytesEncoding::Hex)?;
+        if hmac_key.is_some() {
+            if let Some(other_key) = [OUTPUT_SIZE_KEY, BLAKE2_KEY_KEY, SALT_KEY, PERSONAL_KEY]
+                .into_iter()
+                .find(|k| options.contains_key(*k))
+            {
+                return Err(anyhow::anyhow!(
+                    "Option `{KEY_KEY}` (HMAC) cannot be combined with `{other_key}` for {}",
+                    H::algorithm_name()
+                ));
+            }
+        }
+
+        let key = parse_limited_bytes_option(
+            &options,
+            BLAKE2_KEY_KEY,
+            H::algorithm_name(),
+            H::MAX_KEY_SIZE,
+        )?;
+        let salt =
+            parse_limited_bytes_option(&options, SALT_KEY, H::algorithm_name(), H::MAX_SALT_SIZE)?;
+        let personal = parse_limited_bytes_option(
+            &options,
+            PERSONAL_KEY,
+            H::algorithm_name(),
+            H::MAX_PERSONAL_SIZE,
+        )?;
+
+        Ok(Self {
+            output_size,
+            hmac_key,
+            key,
+            salt,
+            personal,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<H: Blake2Hasher> HashingOptions for Blake2Options<H> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        let encodings = "The value is hex, or prefixed with base64:, text: or file:";
+        [
+            (
+                OUTPUT_SIZE_KEY.to_string(),
+                format!(
+                    "The size of the output, from 1 to {} bytes",
+                    H::MAX_OUTPUT_SIZE
+                ),
+            ),
+            (
+                KEY_KEY.to_string(),
+                format!("Use HMAC with this key. It cannot be combined with the other options. {encodings}"),
+            ),
+            (
+                BLAKE2_KEY_KEY.to_string(),
+                format!(
+                    "Use the keyed hashing of BLAKE2 with a key of up to {} bytes. {encodings}",
+                    H::MAX_KEY_SIZE
+                ),
+            ),
+            (
+                SALT_KEY.to_string(),
+                format!("A salt of up to {} bytes. {encodings}", H::MAX_SALT_SIZE),
+            ),
+            (
+                PERSONAL_KEY.to_string(),
+                format!(
+                    "A personalization string of up to {} bytes. {encodings}",
+                    H::MAX_PERSONAL_SIZE
+                ),
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        H::algorithm_name()
+    }
+}
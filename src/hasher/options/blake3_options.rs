@@ -0,0 +1,106 @@
+use std::{collections::BTreeMap, num::NonZeroUsize};
+
+use super::{
+    common::{
+        parse_bytes_option, parse_option, BytesEncoding, KEY_KEY, OUTPUT_SIZE_KEY, THREADS_KEY,
+    },
+    traits::HashingOptions,
+};
+
+pub const DERIVE_KEY_CONTEXT_KEY: &str = "derive-key-context";
+pub const SEEK_KEY: &str = "seek";
+
+pub const DEFAULT_OUTPUT_SIZE: NonZeroUsize = match NonZeroUsize::new(32) {
+    Some(v) => v,
+    None => unreachable!(),
+};
+
+pub const BLAKE3_KEY_SIZE: usize = 32;
+
+pub enum Blake3Mode {
+    Hash,
+    KeyedHash([u8; BLAKE3_KEY_SIZE]),
+    DeriveKey(String),
+}
+
+pub struct Blake3Options {
+    pub mode: Blake3Mode,
+    pub output_size: NonZeroUsize,
+    pub output_offset: u64,
+    pub threads: NonZeroUsize,
+}
+
+impl TryFrom<BTreeMap<String, String>> for Blake3Options {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let key = parse_bytes_option(&options, KEY_KEY, BytesEncoding::Hex)?;
+        let context = options.get(DERIVE_KEY_CONTEXT_KEY);
+
+        let mode = match (key, context) {
+            (None, None) => Blake3Mode::Hash,
+            (Some(key), None) => {
+                let key: [u8; BLAKE3_KEY_SIZE] = key.try_into().map_err(|k: Vec<u8>| {
+                    anyhow::anyhow!(
+                        "The key for Blake3 must be {BLAKE3_KEY_SIZE} bytes, but {} bytes were provided",
+                        k.len()
+                    )
+                })?;
+                Blake3Mode::KeyedHash(key)
+            }
+            (None, Some(context)) => Blake3Mode::DeriveKey(context.clone()),
+            (Some(_), Some(_)) => {
+                return Err(anyhow::anyhow!(
+                    "Options `{KEY_KEY}` and `{DERIVE_KEY_CONTEXT_KEY}` cannot be used together"
+                ))
+            }
+        };
+
+        let output_size =
+            parse_option::<NonZeroUsize>(&options, OUTPUT_SIZE_KEY, DEFAULT_OUTPUT_SIZE)?;
+        let output_offset = parse_option::<u64>(&options, SEEK_KEY, 0)?;
+        let threads = parse_option::<NonZeroUsize>(&options, THREADS_KEY, NonZeroUsize::MIN)?;
+
+        Ok(Self {
+            mode,
+            output_size,
+            output_offset,
+            threads,
+        })
+    }
+}
+
+impl HashingOptions for Blake3Options {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [
+            (
+                KEY_KEY.to_string(),
+                format!("Use keyed hashing with this {BLAKE3_KEY_SIZE} bytes key. The key is hex, or prefixed with base64:, text: or file:"),
+            ),
+            (
+                DERIVE_KEY_CONTEXT_KEY.to_string(),
+                "Use key derivation with this context string. The input is the key material"
+                    .to_string(),
+            ),
+            (
+                OUTPUT_SIZE_KEY.to_string(),
+                "The size of the output as a positive integer".to_string(),
+            ),
+            (
+                SEEK_KEY.to_string(),
+                "The position in the output stream to start the output from, in bytes".to_string(),
+            ),
+            (
+                THREADS_KEY.to_string(),
+                "The number of threads that hash large inputs. Files are memory-mapped when more than one thread is used. Default is 1"
+                    .to_string(),
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        "Blake3"
+    }
+}
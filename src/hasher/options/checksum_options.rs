@@ -0,0 +1,139 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use crate::hashing_lib::{
+    checksum::{CrcParameters, CustomCrc, MAX_CRC_WIDTH},
+    sized_hasher::SizedHasher,
+    unsized_hasher::UnsizedHasher,
+};
+
+use super::{
+    common::{parse_integer_option, parse_option},
+    traits::HashingOptions,
+};
+
+pub const WIDTH_KEY: &str = "width";
+pub const POLYNOMIAL_KEY: &str = "polynomial";
+pub const INIT_KEY: &str = "init";
+pub const XOROUT_KEY: &str = "xorout";
+pub const REFLECT_KEY: &str = "reflect";
+pub const REFLECT_OUT_KEY: &str = "reflect-out";
+
+pub const DEFAULT_CRC_WIDTH: u32 = 32;
+
+/// Checksums with fixed parameters take no options
+pub struct ChecksumOptions<H: SizedHasher> {
+    _phantom: PhantomData<H>,
+}
+
+impl<H: SizedHasher> TryFrom<BTreeMap<String, String>> for ChecksumOptions<H> {
+    type Error = anyhow::Error;
+
+    fn try_from(_options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<H: SizedHasher> HashingOptions for ChecksumOptions<H> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        BTreeMap::new()
+    }
+
+    fn algo_name() -> &'static str {
+        H::algorithm_name()
+    }
+}
+
+/// The parameters of a CRC, in the Rocksoft model that catalogues of CRCs use
+pub struct CrcOptions {
+    pub parameters: CrcParameters,
+}
+
+impl TryFrom<BTreeMap<String, String>> for CrcOptions {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let width = parse_option(&options, WIDTH_KEY, DEFAULT_CRC_WIDTH)?;
+        if !(1..=MAX_CRC_WIDTH).contains(&width) {
+            return Err(anyhow::anyhow!(
+                "Option `{WIDTH_KEY}` must be between 1 and {MAX_CRC_WIDTH}, but {width} was provided"
+            ));
+        }
+
+        let max_value = u64::MAX >> (MAX_CRC_WIDTH - width);
+        let parse_parameter = |option_key: &str, default_value: u64| {
+            let value = parse_integer_option(&options, option_key)?.unwrap_or(default_value);
+            if value > max_value {
+                return Err(anyhow::anyhow!(
+                    "Option `{option_key}` must fit in {width} bits, but {value:#X} was provided"
+                ));
+            }
+            Ok(value)
+        };
+
+        if !options.contains_key(POLYNOMIAL_KEY) {
+            return Err(anyhow::anyhow!(
+                "Option `{POLYNOMIAL_KEY}` is required for a custom CRC"
+            ));
+        }
+        let polynomial = parse_parameter(POLYNOMIAL_KEY, 0)?;
+        let init = parse_parameter(INIT_KEY, 0)?;
+        let xorout = parse_parameter(XOROUT_KEY, 0)?;
+
+        let reflect_in = parse_option(&options, REFLECT_KEY, false)?;
+        let reflect_out = parse_option(&options, REFLECT_OUT_KEY, reflect_in)?;
+
+        Ok(Self {
+            parameters: CrcParameters {
+                width,
+                polynomial,
+                init,
+                reflect_in,
+                reflect_out,
+                xorout,
+            },
+        })
+    }
+}
+
+impl HashingOptions for CrcOptions {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [
+            (
+                WIDTH_KEY.to_string(),
+                format!("The width of the CRC in bits, from 1 to {MAX_CRC_WIDTH}. Default is {DEFAULT_CRC_WIDTH}"),
+            ),
+            (
+                POLYNOMIAL_KEY.to_string(),
+                "The generator polynomial without its top bit, in decimal or in hex with the prefix 0x. Required"
+                    .to_string(),
+            ),
+            (
+                INIT_KEY.to_string(),
+                "The initial value of the register, in decimal or in hex with the prefix 0x. Default is 0"
+                    .to_string(),
+            ),
+            (
+                XOROUT_KEY.to_string(),
+                "The value XORed with the final register, in decimal or in hex with the prefix 0x. Default is 0"
+                    .to_string(),
+            ),
+            (
+                REFLECT_KEY.to_string(),
+                "Whether the bits of the input bytes are reflected, true or false. Default is false"
+                    .to_string(),
+            ),
+            (
+                REFLECT_OUT_KEY.to_string(),
+                format!("Whether the final register is reflected, true or false. Default is the value of `{REFLECT_KEY}`"),
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        CustomCrc::algorithm_name()
+    }
+}
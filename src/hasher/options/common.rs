@@ -3,6 +3,10 @@ use std::{collections::BTreeMap, str::FromStr};
 use anyhow::Context;
 
 pub const OUTPUT_SIZE_KEY: &str = "output-size";
+pub const KEY_KEY: &str = "key";
+pub const CUSTOMIZATION_KEY: &str = "customization";
+pub const THREADS_KEY: &str = "threads";
+pub const SALT_KEY: &str = "salt";
 
 pub fn parse_option<T: FromStr>(
     options: &BTreeMap<String, String>,
@@ -21,3 +25,78 @@ where
 
     Ok(parsed)
 }
+
+/// Parses an option that holds an integer, written in decimal, or in hex with the prefix `0x`.
+/// The integer must fit in `T`.
+pub fn parse_integer_option<T: TryFrom<u64>>(
+    options: &BTreeMap<String, String>,
+    option_key: impl AsRef<str>,
+) -> anyhow::Result<Option<T>> {
+    let value = match options.get(option_key.as_ref()) {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+
+    let result = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex_value) => u64::from_str_radix(hex_value, 16),
+        None => value.parse::<u64>(),
+    }
+    .context(format!("While parsing option `{}`", option_key.as_ref()))?;
+
+    let result = T::try_from(result).map_err(|_| {
+        anyhow::anyhow!(
+            "Option `{}` must fit in {} bits, but {value} was provided",
+            option_key.as_ref(),
+            std::mem::size_of::<T>() * 8
+        )
+    })?;
+
+    Ok(Some(result))
+}
+
+/// How the value of a bytes option is decoded when it has no prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BytesEncoding {
+    Hex,
+    Text,
+}
+
+/// Parses an option that holds bytes. The value can be prefixed with `hex:`, `base64:` or `text:`
+/// to choose how it is decoded, or with `file:` to read the bytes of a file.
+/// Without a prefix, the value is decoded with `default_encoding`.
+pub fn parse_bytes_option(
+    options: &BTreeMap<String, String>,
+    option_key: impl AsRef<str>,
+    default_encoding: BytesEncoding,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    use base64::prelude::*;
+
+    let value = match options.get(option_key.as_ref()) {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+
+    let context = || format!("While parsing option `{}`", option_key.as_ref());
+
+    let result = if let Some(hex_value) = value.strip_prefix("hex:") {
+        hex::decode(hex_value).with_context(context)?
+    } else if let Some(base64_value) = value.strip_prefix("base64:") {
+        BASE64_STANDARD.decode(base64_value).with_context(context)?
+    } else if let Some(text_value) = value.strip_prefix("text:") {
+        text_value.as_bytes().to_vec()
+    } else if let Some(path) = value.strip_prefix("file:") {
+        std::fs::read(path)
+            .context(format!("Reading file failed: {path}"))
+            .with_context(context)?
+    } else {
+        match default_encoding {
+            BytesEncoding::Hex => hex::decode(value).with_context(context)?,
+            BytesEncoding::Text => value.as_bytes().to_vec(),
+        }
+    };
+
+    Ok(Some(result))
+}
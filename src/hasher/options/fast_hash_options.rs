@@ -0,0 +1,182 @@
+use std::{collections::BTreeMap, marker::PhantomData, num::NonZeroUsize};
+
+use crate::hashing_lib::fast_hash::{
+    is_valid_siphash_output_size, SeededHasher, SipHashHasher, Xxh3Hasher, SIPHASH_KEY_SIZE,
+    XXH3_SECRET_SIZE_MIN,
+};
+
+use super::{
+    common::{
+        parse_bytes_option, parse_integer_option, parse_option, BytesEncoding, KEY_KEY,
+        OUTPUT_SIZE_KEY,
+    },
+    traits::HashingOptions,
+};
+
+pub const SEED_KEY: &str = "seed";
+pub const SECRET_KEY: &str = "secret";
+
+pub const DEFAULT_SIPHASH_OUTPUT_SIZE: NonZeroUsize = match NonZeroUsize::new(8) {
+    Some(v) => v,
+    None => unreachable!(),
+};
+
+const SEED_DESCRIPTION: &str = "The seed, in decimal or in hex with the prefix 0x. Default is 0";
+
+fn parse_seed<H: SeededHasher>(options: &BTreeMap<String, String>) -> anyhow::Result<H::Seed> {
+    match parse_integer_option::<u64>(options, SEED_KEY)? {
+        Some(seed) => H::Seed::try_from(seed).map_err(|_| {
+            anyhow::anyhow!(
+                "Option `{SEED_KEY}` must fit in {} bits, but {seed:#X} was provided",
+                std::mem::size_of::<H::Seed>() * 8
+            )
+        }),
+        None => Ok(H::Seed::default()),
+    }
+}
+
+/// Options of hashers that only take a seed
+pub struct SeedOptions<H: SeededHasher> {
+    pub seed: H::Seed,
+    _phantom: PhantomData<H>,
+}
+
+impl<H: SeededHasher> TryFrom<BTreeMap<String, String>> for SeedOptions<H> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            seed: parse_seed::<H>(&options)?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<H: SeededHasher> HashingOptions for SeedOptions<H> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [(SEED_KEY.to_string(), SEED_DESCRIPTION.to_string())]
+            .into_iter()
+            .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        H::algorithm_name()
+    }
+}
+
+pub enum Xxh3Mode {
+    Seed(u64),
+    Secret(Vec<u8>),
+}
+
+pub struct Xxh3Options<H: Xxh3Hasher> {
+    pub mode: Xxh3Mode,
+    _phantom: PhantomData<H>,
+}
+
+impl<H: Xxh3Hasher> TryFrom<BTreeMap<String, String>> for Xxh3Options<H> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let secret = parse_bytes_option(&options, SECRET_KEY, BytesEncoding::Hex)?;
+
+        let mode = match secret {
+            Some(_) if options.contains_key(SEED_KEY) => {
+                return Err(anyhow::anyhow!(
+                    "Options `{SEED_KEY}` and `{SECRET_KEY}` cannot be used together"
+                ))
+            }
+            Some(secret) if secret.len() < XXH3_SECRET_SIZE_MIN => {
+                return Err(anyhow::anyhow!(
+                    "The secret for {} must be at least {XXH3_SECRET_SIZE_MIN} bytes, but {} bytes were provided",
+                    H::algorithm_name(),
+                    secret.len()
+                ))
+            }
+            Some(secret) => Xxh3Mode::Secret(secret),
+            None => Xxh3Mode::Seed(parse_seed::<H>(&options)?),
+        };
+
+        Ok(Self {
+            mode,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<H: Xxh3Hasher> HashingOptions for Xxh3Options<H> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [
+            (SEED_KEY.to_string(), SEED_DESCRIPTION.to_string()),
+            (
+                SECRET_KEY.to_string(),
+                format!("Use this secret of at least {XXH3_SECRET_SIZE_MIN} bytes instead of a seed. The secret is hex, or prefixed with base64:, text: or file:"),
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        H::algorithm_name()
+    }
+}
+
+pub struct SipHashOptions<H: SipHashHasher> {
+    pub key: [u8; SIPHASH_KEY_SIZE],
+    pub output_size: NonZeroUsize,
+    _phantom: PhantomData<H>,
+}
+
+impl<H: SipHashHasher> TryFrom<BTreeMap<String, String>> for SipHashOptions<H> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let key = match parse_bytes_option(&options, KEY_KEY, BytesEncoding::Hex)? {
+            Some(key) => key.try_into().map_err(|k: Vec<u8>| {
+                anyhow::anyhow!(
+                    "The key for {} must be {SIPHASH_KEY_SIZE} bytes, but {} bytes were provided",
+                    H::algorithm_name(),
+                    k.len()
+                )
+            })?,
+            None => [0; SIPHASH_KEY_SIZE],
+        };
+
+        let output_size =
+            parse_option::<NonZeroUsize>(&options, OUTPUT_SIZE_KEY, DEFAULT_SIPHASH_OUTPUT_SIZE)?;
+        if !is_valid_siphash_output_size(output_size) {
+            return Err(anyhow::anyhow!(
+                "The output size of {} must be 8 or 16 bytes, but {output_size} bytes were requested",
+                H::algorithm_name()
+            ));
+        }
+
+        Ok(Self {
+            key,
+            output_size,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<H: SipHashHasher> HashingOptions for SipHashOptions<H> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [
+            (
+                KEY_KEY.to_string(),
+                format!("The {SIPHASH_KEY_SIZE} bytes key. Default is zeros. The key is hex, or prefixed with base64:, text: or file:"),
+            ),
+            (
+                OUTPUT_SIZE_KEY.to_string(),
+                format!("The size of the output, 8 or 16 bytes. Default is {DEFAULT_SIPHASH_OUTPUT_SIZE}"),
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        H::algorithm_name()
+    }
+}
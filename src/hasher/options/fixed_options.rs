@@ -0,0 +1,30 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use crate::hashing_lib::sized_hasher::SizedHasher;
+
+use super::traits::HashingOptions;
+
+/// Options of hashers that have no parameters, so they take no options
+pub struct FixedOptions<H: SizedHasher> {
+    _phantom: PhantomData<H>,
+}
+
+impl<H: SizedHasher> TryFrom<BTreeMap<String, String>> for FixedOptions<H> {
+    type Error = anyhow::Error;
+
+    fn try_from(_options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<H: SizedHasher> HashingOptions for FixedOptions<H> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        BTreeMap::new()
+    }
+
+    fn algo_name() -> &'static str {
+        H::algorithm_name()
+    }
+}
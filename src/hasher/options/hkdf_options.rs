@@ -0,0 +1,76 @@
+use std::{collections::BTreeMap, marker::PhantomData, num::NonZeroUsize};
+
+use sha3::digest::typenum::Unsigned;
+
+use crate::hashing_lib::{hkdf::max_expand_output_size, sized_hasher::BlockSizedHasher};
+
+use super::{
+    common::{parse_bytes_option, parse_option, BytesEncoding, KEY_KEY, OUTPUT_SIZE_KEY, SALT_KEY},
+    traits::HashingOptions,
+};
+
+pub const INFO_KEY: &str = "info";
+
+pub struct HkdfOptions<H: BlockSizedHasher> {
+    /// The input keying material, or the pseudorandom key when only expanding
+    pub key: Option<Vec<u8>>,
+    pub salt: Option<Vec<u8>>,
+    pub info: Option<Vec<u8>>,
+    pub output_size: Option<NonZeroUsize>,
+    _phantom: PhantomData<H>,
+}
+
+impl<H: BlockSizedHasher> TryFrom<BTreeMap<String, String>> for HkdfOptions<H> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let output_size = options
+            .contains_key(OUTPUT_SIZE_KEY)
+            .then(|| parse_option(&options, OUTPUT_SIZE_KEY, NonZeroUsize::MIN))
+            .transpose()?;
+
+        Ok(Self {
+            key: parse_bytes_option(&options, KEY_KEY, BytesEncoding::Hex)?,
+            salt: parse_bytes_option(&options, SALT_KEY, BytesEncoding::Hex)?,
+            info: parse_bytes_option(&options, INFO_KEY, BytesEncoding::Hex)?,
+            output_size,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<H: BlockSizedHasher> HashingOptions for HkdfOptions<H> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [
+            (
+                KEY_KEY.to_string(),
+                "The input keying material, or the pseudorandom key for expand. If not given, it's read from the input. The key is hex, or prefixed with base64:, text: or file:"
+                    .to_string(),
+            ),
+            (
+                SALT_KEY.to_string(),
+                "The salt for extract. Default is zeros. The salt is hex, or prefixed with base64:, text: or file:"
+                    .to_string(),
+            ),
+            (
+                INFO_KEY.to_string(),
+                "The context information for expand. Default is empty. The info is hex, or prefixed with base64:, text: or file:"
+                    .to_string(),
+            ),
+            (
+                OUTPUT_SIZE_KEY.to_string(),
+                format!(
+                    "The size of the output of expand, up to {}. Default is {}",
+                    max_expand_output_size::<H>(),
+                    <H::OutputSize as Unsigned>::USIZE
+                ),
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        H::algorithm_name()
+    }
+}
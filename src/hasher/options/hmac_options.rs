@@ -0,0 +1,43 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use crate::hashing_lib::sized_hasher::BlockSizedHasher;
+
+use super::{
+    common::{parse_bytes_option, BytesEncoding, KEY_KEY},
+    traits::HashingOptions,
+};
+
+/// Options of hashers that can be used as HMAC. Without a key, the plain hasher is used.
+pub struct HmacOptions<H: BlockSizedHasher> {
+    pub key: Option<Vec<u8>>,
+    _phantom: PhantomData<H>,
+}
+
+impl<H: BlockSizedHasher> TryFrom<BTreeMap<String, String>> for HmacOptions<H> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let key = parse_bytes_option(&options, KEY_KEY, BytesEncoding::Hex)?;
+
+        Ok(Self {
+            key,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<H: BlockSizedHasher> HashingOptions for HmacOptions<H> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [(
+            KEY_KEY.to_string(),
+            "Use HMAC with this key. The key is hex, or prefixed with base64:, text: or file:"
+                .to_string(),
+        )]
+        .into_iter()
+        .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        H::algorithm_name()
+    }
+}
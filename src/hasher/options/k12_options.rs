@@ -1,41 +1,56 @@
-use std::{collections::BTreeMap, num::NonZeroUsize};
+use std::{collections::BTreeMap, marker::PhantomData, num::NonZeroUsize};
+
+use crate::hashing_lib::kangaroo_twelve::TurboShakeVariant;
 
 use super::{
-    common::{parse_option, OUTPUT_SIZE_KEY},
+    common::{parse_bytes_option, parse_option, BytesEncoding, CUSTOMIZATION_KEY, OUTPUT_SIZE_KEY},
     traits::HashingOptions,
 };
 
-pub const DEFAULT_OUTPUT_SIZE: NonZeroUsize = match NonZeroUsize::new(32) {
-    Some(v) => v,
-    None => unreachable!(),
-};
-
-pub struct K12Options {
+/// Options of KangarooTwelve, which are the same for K12 (KT128) and KT256
+pub struct K12Options<V: TurboShakeVariant> {
     pub output_size: NonZeroUsize,
+    pub customization: Vec<u8>,
+    _phantom: PhantomData<V>,
 }
 
-impl TryFrom<BTreeMap<String, String>> for K12Options {
+impl<V: TurboShakeVariant> TryFrom<BTreeMap<String, String>> for K12Options<V> {
     type Error = anyhow::Error;
 
     fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let default_output_size =
+            NonZeroUsize::new(V::DEFAULT_OUTPUT_SIZE).expect("Default output size is never zero");
         let output_size =
-            parse_option::<NonZeroUsize>(&options, OUTPUT_SIZE_KEY, DEFAULT_OUTPUT_SIZE)?;
-
-        Ok(Self { output_size })
+            parse_option::<NonZeroUsize>(&options, OUTPUT_SIZE_KEY, default_output_size)?;
+        let customization = parse_bytes_option(&options, CUSTOMIZATION_KEY, BytesEncoding::Text)?
+            .unwrap_or_default();
+
+        Ok(Self {
+            output_size,
+            customization,
+            _phantom: PhantomData,
+        })
     }
 }
 
-impl HashingOptions for K12Options {
+impl<V: TurboShakeVariant> HashingOptions for K12Options<V> {
     fn options_descriptions() -> BTreeMap<String, String> {
-        [(
-            OUTPUT_SIZE_KEY.to_string(),
-            "The size of the output as a positive integer".to_string(),
-        )]
+        [
+            (
+                OUTPUT_SIZE_KEY.to_string(),
+                "The size of the output as a positive integer".to_string(),
+            ),
+            (
+                CUSTOMIZATION_KEY.to_string(),
+                "The customization string. It is text, or prefixed with hex:, base64: or file:"
+                    .to_string(),
+            ),
+        ]
         .into_iter()
         .collect()
     }
 
     fn algo_name() -> &'static str {
-        "K12"
+        V::KANGAROO_TWELVE_NAME
     }
 }
@@ -1,3 +1,14 @@
+pub mod blake2_options;
+pub mod blake3_options;
+pub mod checksum_options;
 pub mod common;
+pub mod fast_hash_options;
+pub mod fixed_options;
+pub mod hkdf_options;
+pub mod hmac_options;
 pub mod k12_options;
+pub mod output_size_options;
+pub mod shake_options;
+pub mod sp800_185_options;
 pub mod traits;
+pub mod turbo_shake_options;
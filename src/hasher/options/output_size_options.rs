@@ -0,0 +1,46 @@
+use std::{collections::BTreeMap, marker::PhantomData, num::NonZeroUsize};
+
+use crate::hashing_lib::unsized_hasher::VariableOutputHasher;
+
+use super::{
+    common::{parse_option, OUTPUT_SIZE_KEY},
+    traits::HashingOptions,
+};
+
+/// Options of hashers whose only option is the size of their output
+pub struct OutputSizeOptions<H: VariableOutputHasher> {
+    pub output_size: NonZeroUsize,
+    _phantom: PhantomData<H>,
+}
+
+impl<H: VariableOutputHasher> TryFrom<BTreeMap<String, String>> for OutputSizeOptions<H> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let default_output_size =
+            NonZeroUsize::new(H::DEFAULT_OUTPUT_SIZE).expect("Default output size is never zero");
+
+        Ok(Self {
+            output_size: parse_option(&options, OUTPUT_SIZE_KEY, default_output_size)?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<H: VariableOutputHasher> HashingOptions for OutputSizeOptions<H> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [(
+            OUTPUT_SIZE_KEY.to_string(),
+            format!(
+                "The size of the output as a positive integer. Default is {}",
+                H::DEFAULT_OUTPUT_SIZE
+            ),
+        )]
+        .into_iter()
+        .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        H::algorithm_name()
+    }
+}
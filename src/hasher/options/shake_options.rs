@@ -0,0 +1,104 @@
+use std::{collections::BTreeMap, marker::PhantomData, num::NonZeroUsize};
+
+use crate::hashing_lib::unsized_hasher::{CShakeHasher, ShakeHasher};
+
+use super::{
+    common::{parse_bytes_option, parse_option, BytesEncoding, CUSTOMIZATION_KEY, OUTPUT_SIZE_KEY},
+    traits::HashingOptions,
+};
+
+pub const FUNCTION_NAME_KEY: &str = "function-name";
+
+fn parse_output_size<H: ShakeHasher>(
+    options: &BTreeMap<String, String>,
+) -> anyhow::Result<NonZeroUsize> {
+    let default_output_size =
+        NonZeroUsize::new(H::DEFAULT_OUTPUT_SIZE).expect("Default output size is never zero");
+
+    parse_option::<NonZeroUsize>(options, OUTPUT_SIZE_KEY, default_output_size)
+}
+
+fn output_size_description<H: ShakeHasher>() -> (String, String) {
+    (
+        OUTPUT_SIZE_KEY.to_string(),
+        format!(
+            "The size of the output as a positive integer. Default is {}",
+            H::DEFAULT_OUTPUT_SIZE
+        ),
+    )
+}
+
+pub struct ShakeOptions<H: ShakeHasher> {
+    pub output_size: NonZeroUsize,
+    _phantom: PhantomData<H>,
+}
+
+impl<H: ShakeHasher> TryFrom<BTreeMap<String, String>> for ShakeOptions<H> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            output_size: parse_output_size::<H>(&options)?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<H: ShakeHasher> HashingOptions for ShakeOptions<H> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [output_size_description::<H>()].into_iter().collect()
+    }
+
+    fn algo_name() -> &'static str {
+        H::algorithm_name()
+    }
+}
+
+pub struct CShakeOptions<H: CShakeHasher> {
+    pub output_size: NonZeroUsize,
+    pub function_name: Vec<u8>,
+    pub customization: Vec<u8>,
+    _phantom: PhantomData<H>,
+}
+
+impl<H: CShakeHasher> TryFrom<BTreeMap<String, String>> for CShakeOptions<H> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let function_name = parse_bytes_option(&options, FUNCTION_NAME_KEY, BytesEncoding::Text)?
+            .unwrap_or_default();
+        let customization = parse_bytes_option(&options, CUSTOMIZATION_KEY, BytesEncoding::Text)?
+            .unwrap_or_default();
+
+        Ok(Self {
+            output_size: parse_output_size::<H>(&options)?,
+            function_name,
+            customization,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<H: CShakeHasher> HashingOptions for CShakeOptions<H> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [
+            output_size_description::<H>(),
+            (
+                FUNCTION_NAME_KEY.to_string(),
+                "The function name, reserved for functions defined by NIST. It is text, or prefixed with hex:, base64: or file:"
+                    .to_string(),
+            ),
+            (
+                CUSTOMIZATION_KEY.to_string(),
+                "The customization string. It is text, or prefixed with hex:, base64: or file:"
+                    .to_string(),
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        H::algorithm_name()
+    }
+}
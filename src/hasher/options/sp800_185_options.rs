@@ -0,0 +1,162 @@
+use std::{collections::BTreeMap, marker::PhantomData, num::NonZeroUsize};
+
+use crate::hashing_lib::sp800_185::{Sp800185Variant, DEFAULT_PARALLEL_HASH_BLOCK_SIZE};
+
+use super::{
+    common::{
+        parse_bytes_option, parse_option, BytesEncoding, CUSTOMIZATION_KEY, KEY_KEY,
+        OUTPUT_SIZE_KEY, THREADS_KEY,
+    },
+    traits::HashingOptions,
+};
+
+pub const BLOCK_SIZE_KEY: &str = "block-size";
+
+fn parse_output_size<V: Sp800185Variant>(
+    options: &BTreeMap<String, String>,
+) -> anyhow::Result<NonZeroUsize> {
+    let default_output_size =
+        NonZeroUsize::new(V::DEFAULT_OUTPUT_SIZE).expect("Default output size is never zero");
+
+    parse_option::<NonZeroUsize>(options, OUTPUT_SIZE_KEY, default_output_size)
+}
+
+fn parse_customization(options: &BTreeMap<String, String>) -> anyhow::Result<Vec<u8>> {
+    Ok(parse_bytes_option(options, CUSTOMIZATION_KEY, BytesEncoding::Text)?.unwrap_or_default())
+}
+
+fn common_descriptions<V: Sp800185Variant>() -> [(String, String); 2] {
+    [
+        (
+            OUTPUT_SIZE_KEY.to_string(),
+            format!(
+                "The size of the output as a positive integer. It changes the whole output, not just its length. Default is {}",
+                V::DEFAULT_OUTPUT_SIZE
+            ),
+        ),
+        (
+            CUSTOMIZATION_KEY.to_string(),
+            "The customization string. It is text, or prefixed with hex:, base64: or file:"
+                .to_string(),
+        ),
+    ]
+}
+
+pub struct KmacOptions<V: Sp800185Variant> {
+    pub key: Vec<u8>,
+    pub customization: Vec<u8>,
+    pub output_size: NonZeroUsize,
+    _phantom: PhantomData<V>,
+}
+
+impl<V: Sp800185Variant> TryFrom<BTreeMap<String, String>> for KmacOptions<V> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let key = parse_bytes_option(&options, KEY_KEY, BytesEncoding::Hex)?.ok_or_else(|| {
+            anyhow::anyhow!("{} requires a key, with option `{KEY_KEY}`", V::KMAC_NAME)
+        })?;
+
+        Ok(Self {
+            key,
+            customization: parse_customization(&options)?,
+            output_size: parse_output_size::<V>(&options)?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<V: Sp800185Variant> HashingOptions for KmacOptions<V> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        common_descriptions::<V>()
+            .into_iter()
+            .chain([(
+                KEY_KEY.to_string(),
+                "The key, which is required. The key is hex, or prefixed with base64:, text: or file:"
+                    .to_string(),
+            )])
+            .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        V::KMAC_NAME
+    }
+}
+
+pub struct TupleHashOptions<V: Sp800185Variant> {
+    pub customization: Vec<u8>,
+    pub output_size: NonZeroUsize,
+    _phantom: PhantomData<V>,
+}
+
+impl<V: Sp800185Variant> TryFrom<BTreeMap<String, String>> for TupleHashOptions<V> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            customization: parse_customization(&options)?,
+            output_size: parse_output_size::<V>(&options)?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<V: Sp800185Variant> HashingOptions for TupleHashOptions<V> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        common_descriptions::<V>().into_iter().collect()
+    }
+
+    fn algo_name() -> &'static str {
+        V::TUPLE_HASH_NAME
+    }
+}
+
+pub struct ParallelHashOptions<V: Sp800185Variant> {
+    pub block_size: NonZeroUsize,
+    pub customization: Vec<u8>,
+    pub output_size: NonZeroUsize,
+    pub threads: NonZeroUsize,
+    _phantom: PhantomData<V>,
+}
+
+impl<V: Sp800185Variant> TryFrom<BTreeMap<String, String>> for ParallelHashOptions<V> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let default_block_size = NonZeroUsize::new(DEFAULT_PARALLEL_HASH_BLOCK_SIZE)
+            .expect("Default block size is never zero");
+        let block_size =
+            parse_option::<NonZeroUsize>(&options, BLOCK_SIZE_KEY, default_block_size)?;
+
+        Ok(Self {
+            block_size,
+            customization: parse_customization(&options)?,
+            output_size: parse_output_size::<V>(&options)?,
+            threads: parse_option::<NonZeroUsize>(&options, THREADS_KEY, NonZeroUsize::MIN)?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<V: Sp800185Variant> HashingOptions for ParallelHashOptions<V> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        common_descriptions::<V>()
+            .into_iter()
+            .chain([
+                (
+                    BLOCK_SIZE_KEY.to_string(),
+                    format!("The size of the blocks that are hashed independently, in bytes. Default is {DEFAULT_PARALLEL_HASH_BLOCK_SIZE}"),
+                ),
+                (
+                    THREADS_KEY.to_string(),
+                    "The number of threads that hash the blocks of large inputs. Default is 1"
+                        .to_string(),
+                ),
+            ])
+            .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        V::PARALLEL_HASH_NAME
+    }
+}
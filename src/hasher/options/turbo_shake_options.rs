@@ -0,0 +1,74 @@
+use std::{collections::BTreeMap, marker::PhantomData, num::NonZeroUsize};
+
+use crate::hashing_lib::kangaroo_twelve::{
+    TurboShakeVariant, DEFAULT_TURBO_SHAKE_DOMAIN_SEPARATION,
+};
+
+use super::{
+    common::{parse_integer_option, parse_option, OUTPUT_SIZE_KEY},
+    traits::HashingOptions,
+};
+
+pub const DOMAIN_SEPARATION_KEY: &str = "domain-separation";
+
+pub struct TurboShakeOptions<V: TurboShakeVariant> {
+    pub output_size: NonZeroUsize,
+    pub domain_separation: u8,
+    _phantom: PhantomData<V>,
+}
+
+fn parse_domain_separation(options: &BTreeMap<String, String>) -> anyhow::Result<u8> {
+    let result = match parse_integer_option::<u8>(options, DOMAIN_SEPARATION_KEY)? {
+        Some(v) => v,
+        None => return Ok(DEFAULT_TURBO_SHAKE_DOMAIN_SEPARATION),
+    };
+
+    if !(0x01..=0x7F).contains(&result) {
+        return Err(anyhow::anyhow!(
+            "Option `{DOMAIN_SEPARATION_KEY}` must be between 0x01 and 0x7F, but {result:#04X} was provided"
+        ));
+    }
+
+    Ok(result)
+}
+
+impl<V: TurboShakeVariant> TryFrom<BTreeMap<String, String>> for TurboShakeOptions<V> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let default_output_size =
+            NonZeroUsize::new(V::DEFAULT_OUTPUT_SIZE).expect("Default output size is never zero");
+        let output_size =
+            parse_option::<NonZeroUsize>(&options, OUTPUT_SIZE_KEY, default_output_size)?;
+
+        Ok(Self {
+            output_size,
+            domain_separation: parse_domain_separation(&options)?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<V: TurboShakeVariant> HashingOptions for TurboShakeOptions<V> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [
+            (
+                OUTPUT_SIZE_KEY.to_string(),
+                format!(
+                    "The size of the output as a positive integer. Default is {}",
+                    V::DEFAULT_OUTPUT_SIZE
+                ),
+            ),
+            (
+                DOMAIN_SEPARATION_KEY.to_string(),
+                format!("The domain separation byte, from 0x01 to 0x7F, in decimal or in hex with the prefix 0x. Default is {DEFAULT_TURBO_SHAKE_DOMAIN_SEPARATION:#04X}"),
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        V::TURBO_SHAKE_NAME
+    }
+}
@@ -1,8 +1,16 @@
 use std::collections::BTreeMap;
 
-use crate::{hasher::make_hasher, program_options::HashAlgorithm};
+use crate::{
+    hasher::make_hasher,
+    hashing_lib::kangaroo_twelve::{Kt128, TurboShakeVariant},
+    program_options::HashAlgorithm,
+};
 
-use super::options::{common::OUTPUT_SIZE_KEY, k12_options};
+use super::options::{
+    blake2_options, blake3_options,
+    common::{CUSTOMIZATION_KEY, KEY_KEY, OUTPUT_SIZE_KEY, SALT_KEY, THREADS_KEY},
+    shake_options, sp800_185_options, turbo_shake_options,
+};
 
 #[test]
 fn sha1() {
@@ -330,7 +338,7 @@ fn k12_with_manual_default_size() {
     ];
     let opts: BTreeMap<String, String> = [(
         OUTPUT_SIZE_KEY.to_string(),
-        k12_options::DEFAULT_OUTPUT_SIZE.to_string(),
+        Kt128::DEFAULT_OUTPUT_SIZE.to_string(),
     )]
     .into_iter()
     .collect();
@@ -387,3 +395,1834 @@ fn k12_with_invalid_option() {
             .contains("is not a valid option")
     );
 }
+
+#[test]
+fn k12_with_customization() {
+    // From the official KangarooTwelve test vectors, where ptn(n) is n bytes of the sequence 0, 1, 2, ..., 250, 0, 1, ...
+    let ptn = |n: usize| (0..n).map(|i| (i % 251) as u8).collect::<Vec<u8>>();
+    let expected = [
+        (
+            vec![],
+            ptn(1),
+            "fab658db63e94a246188bf7af69a133045f46ee984c56e3c3328caaf1aa1a583",
+        ),
+        (
+            vec![0xff],
+            ptn(41),
+            "d848c5068ced736f4462159b9867fd4c20b808acc3d5bc48e0b06ba0a3762ec4",
+        ),
+    ];
+
+    for (data, customization, el) in expected {
+        let opts: BTreeMap<String, String> = [(
+            CUSTOMIZATION_KEY.to_string(),
+            format!("hex:{}", hex::encode(customization)),
+        )]
+        .into_iter()
+        .collect();
+        let mut hasher = make_hasher(HashAlgorithm::K12, 1.try_into().unwrap(), opts).unwrap();
+        hasher.write(&data);
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+
+    // Without a prefix, the customization is text, and it's kept for all iterations
+    let opts: BTreeMap<String, String> = [(CUSTOMIZATION_KEY.to_string(), "my-app".to_string())]
+        .into_iter()
+        .collect();
+    let mut hasher = make_hasher(HashAlgorithm::K12, 2.try_into().unwrap(), opts).unwrap();
+    hasher.write(b"abc");
+    assert_eq!(
+        hex::encode(hasher.finalize_and_reset()),
+        "1770d1b10bb826dca8c26f34c03fae662cec0212656e5ad26de486ca1e60ec4f"
+    );
+}
+
+#[test]
+fn hmac_rfc4231_sha256() {
+    let cases = [
+        (
+            "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+            b"Hi There".as_slice(),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+        ),
+        (
+            "4a656665",
+            b"what do ya want for nothing?".as_slice(),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+        ),
+        (
+            "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            b"Test Using Larger Than Block-Size Key - Hash Key First".as_slice(),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+        ),
+    ];
+
+    for (key, data, expected) in cases {
+        let opts: BTreeMap<String, String> = [(KEY_KEY.to_string(), key.to_string())]
+            .into_iter()
+            .collect();
+        let mut hasher = make_hasher(HashAlgorithm::Sha256, 1.try_into().unwrap(), opts).unwrap();
+        hasher.write(data);
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), expected);
+    }
+}
+
+#[test]
+fn hmac_all_algorithms() {
+    // To generate, in Python: hmac.new(b"key", data, hashlib.sha256).digest(), repeated with the result as data
+    let expected = [
+        (
+            HashAlgorithm::Md5,
+            [
+                "d2fe98063f876b03193afb49b4979591",
+                "331e8b4c0ddcd07cce5770efa318873d",
+                "785aff4823a9418160eec32c5635e635",
+                "50ab2d3052791e25e3f43537952ccf61",
+                "d0189d905c50f3692ec8d6e7607555bb",
+            ],
+        ),
+        (
+            HashAlgorithm::Sha1,
+            [
+                "4fd0b215276ef12f2b3e4c8ecac2811498b656fc",
+                "8bd9314235902c952cab4d9faf741f7a5be72509",
+                "1b0999a8783941fd2c25d47aad63a60f93ed8a26",
+                "d0217fe7434dc3175d0879a1d13c00e030fa4de4",
+                "700371a7ce1af9858a8f2b1ebb6fca188915f59e",
+            ],
+        ),
+        (
+            HashAlgorithm::Sha224,
+            [
+                "f524670b7e34f31467de0aa96593861cf65117d414fb2d86158d760e",
+                "2275c685d49c6bad7e9bc53af717576944aaa5d9bfd069c5386a808c",
+                "3e441aae62164c3ae35c38fefb2feca5c2ce975611b116c8ba79bdd6",
+                "e4988ad59f13155d904906023e259693dbbde4d0d8a6d28926554c64",
+                "609f448103ae01236249c988e1ad1adfb3938b931755df42a8fbf481",
+            ],
+        ),
+        (
+            HashAlgorithm::Sha256,
+            [
+                "9c196e32dc0175f86f4b1cb89289d6619de6bee699e4c378e68309ed97a1a6ab",
+                "de97fcd2df118793f131631aa92d39c0576e1542c16a03a3210238b245b5d595",
+                "3950e733290d5d83935d401a58f2df3ef21c929ae261845b64fea58b5adfd345",
+                "1b477f451f4e6cb74d7d6039091bbfb35cfc7921cc416f8939f33fc9d00eb136",
+                "218bdd42dd690df057bb033ae76a7f4abcfb27b36c4e6a74e9e8f63eab023ec8",
+            ],
+        ),
+        (
+            HashAlgorithm::Sha384,
+            [
+                "30ddb9c8f347cffbfb44e519d814f074cf4047a55d6f563324f1c6a33920e5edfb2a34bac60bdc96cd33a95623d7d638",
+                "51a94d39c316471aff235a9490497181b14d8b1991b0c60100bc479bdbfc61605e100548139abcbed4713295447439fa",
+                "6b8e646514099d3ce71f5ba6b280d4fdc89528121e86608c8639035c9c7632c9094b2313118b134da624beb69810e5f1",
+                "bddd8a015c9b06b6f237a0d7f22a0a7aa3724f199bae660a771b88a58c9e1e8e4ebaca075bea9f266533716c7e933c76",
+                "b62a6fff1e454733cd9bda8b931625f19537a3a8078a65d6133f0e9ff5225eddd30e3d2997a30a9d43f7dd73a5754a26",
+            ],
+        ),
+        (
+            HashAlgorithm::Sha512,
+            [
+                "3926a207c8c42b0c41792cbd3e1a1aaaf5f7a25704f62dfc939c4987dd7ce060009c5bb1c2447355b3216f10b537e9afa7b64a4e5391b0d631172d07939e087a",
+                "fac66ec192b490eae7ba83075d3640b500569d37c5ef890d1b24ce082f24d59840ef4ab509d886b8f40d364289e4cf2d38e7fdecda4e6f1591b776a17b2361fd",
+                "f2c54a5616a0f3eb7aa9bd9a356cd4a6c1950b513e423ce8fba150c9144cb1df4c24f4c5920b755810d02a3bdb45d9763e86b5debad6c9390b104e3c4e90604f",
+                "6c26d5f38c0a590d47baf1d3cdbb041a90d5e2f54c6b3407063ff940420deae5fca8a3caaa9e0273c9bde8f77b411356af6101c9ea607ec30a30d1f9abac9a3c",
+                "12237be894e19dd525d8613ea68c6a28ba01c6d387ebbede7d54ba9da9dfc42c6608c9b86509049ce4a8fa4bef28d09bf0167875225f4bba64fc3ad2a09b6b07",
+            ],
+        ),
+        (
+            HashAlgorithm::Sha3_224,
+            [
+                "09d9d74e7d761c8f27f07d8d35f95b9d160b6b4d8175329db68eea6d",
+                "b5e4a29d294718c07b0808be146e768c386e105cd3ff240d4206093a",
+                "676bf43839d434128a95589efada49b5069f4d92603a937ddec301d3",
+                "7f591805b510fb851f8fe04773cccb6648615567299bdd4e757faf41",
+                "f52fc619f526e77201bc48bc4e9a38c4da277705d4e4200a83bc8654",
+            ],
+        ),
+        (
+            HashAlgorithm::Sha3_256,
+            [
+                "09b6dbab8d11795ca7c8d82f1cf91682013c7cb980abbb25473be4ae7f7b5683",
+                "7ce9dfab045c429c662bc0c655e7597eb7cc6e9866d79155223e8fecd2d1125a",
+                "ec9bb1121671827f7de56c23a3b2dfda9f80edcb580115cfdaec49e5c230e3bc",
+                "3517fa34ec1afcbaad0e3d1a5b452fb2ebaf2e4dc27ae7d4d13887346eeda2c9",
+                "30720993ef7dc643cf48608855c88af43071c5577f58a1f290627704d9cf40de",
+            ],
+        ),
+        (
+            HashAlgorithm::Sha3_384,
+            [
+                "94f2aa7ae7c4b7b8fa4c612fdb422b3343811b13c8888257904f543995cdbcba5e49f10f8ed6f7b9ddc1b30b3828815c",
+                "d3117f0b31ef156969b2f47a133d41d36a59cfff7a055ea8060b45dc2c56ca46f5ef58f0526ebe7a56e3617ffc84dc15",
+                "efba526854d7c9f315b48224549e20ac6373f42a717e5926d422cfa510c4feac19846a8afc23b653ed2b8befb76c69fa",
+                "0c79409864ea844279e8090f95db44a9fbdb3ad59193ec86512be17a888596618d63c2de029f99dea377685086809c63",
+                "b552dca1cbea32e87e19d7927f8d8a94295e0ad367e9895c2ea12c9c072a98a4535477c528ec9581dce11f68171588ae",
+            ],
+        ),
+        (
+            HashAlgorithm::Sha3_512,
+            [
+                "085e4e83503f40b82fef38438bc4905a55dbaa8c8878097a899db0b57ce7da57a368251c34474f60b3ebacb39b2edaca4b290456411c76ec7ab61944cfe2288e",
+                "513238813084cc8405710dffc2948804e29cdfb634463e9f1bb342f3987067dad22fb092ea9c960c7787bee175bfb362ce93912c98be7582bfcd0d333f11f049",
+                "7e146904e9c3249fd8ac1f339bea5f8d608d536870534e90cc5d4f6dedb6dfe8ac67e3bf9c1b42b6116dede5fc19b59f547ff8580d9ca28e045cda6cef55d9ae",
+                "de6f5316792503ac98de8f7b2918296c5ec9fd7c0f49470901c9ce3cce0e2297ccdd0c5d8dfa8e84c5f2121da2eee6bb30aba26104012faa4c8bc19c98ef9c0f",
+                "0f24a491a132e1c91a4e538916332e8ac9a96fc2d41df9fa55457fc4723c68ee45274158579cfa9040979e764ee80d72cba7d30761ee08629aaeeaad012d0f22",
+            ],
+        ),
+        (
+            HashAlgorithm::Blake2b,
+            [
+                "05cc4815438d5cfe68fff446b8df57828cc96189de4b4e928e3f06d815d64e5bc15124a02ffd39859b3e2476da03bc0235ca86df623af2a5631779809e9fd04a",
+                "a5b666c4579c219ce7fa3b414285e3482c39b976a87be2df5cfbf09f3160ef9c4a1ead8b418f5f0adeeb9c1718df5fa848b0b671ea8045729daeb2219d962fdf",
+                "0038eab7b0b639bca294b557fdd13742043be7a46b942a25aa079c0f79df981a24232b269c6721f76a395961b08234145c97b4fb4f366957a68c6f64ebd5eab3",
+                "f747beea13b6c2dad0c27805d3fecbbe6625927c7cf5efba67e2a6d85ce79db8bfef670ac7b84f8d9ebe9c38436970a47449c9a9a1143f6dee377fa6774fa081",
+                "a92964374f3b5f6b3b41b941dfd3df1e276ad6d7472c9b000ef0e3ea3412201372e496088e00f746e0f025358f34999f06ef34aee373b6b98f77105175d0442f",
+            ],
+        ),
+        (
+            HashAlgorithm::Blake2s,
+            [
+                "0018bfddc0c878b3e270c6e59b35af01e164a42d89499c9447a90b58a4d60eb3",
+                "4c95620c233d6046249c800112d9e15b71399ce5f2a65c241802c3a04643f01c",
+                "c21c36fa2f9ae4c77b65548705c5c145bf556457bc06dd54a28e29765e29b821",
+                "d24479047bad3b22bc71e820ad455fccdfc85e8dbf1e7325c67460d8feb009ce",
+                "2733966ef20ad122a47cabe41c133e0635b9129fb29febab9683b027ea57f765",
+            ],
+        ),
+    ];
+    let opts: BTreeMap<String, String> = [(KEY_KEY.to_string(), "text:key".to_string())]
+        .into_iter()
+        .collect();
+
+    for (algo, expected) in expected {
+        for (i, el) in expected.into_iter().enumerate() {
+            let mut hasher =
+                make_hasher(algo, (i as u64 + 1).try_into().unwrap(), opts.clone()).unwrap();
+            hasher.write(b"abc");
+            assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+        }
+    }
+}
+
+#[test]
+fn hmac_keys_longer_than_block_size() {
+    // 200 bytes is longer than the block size of all algorithms, so the key is hashed first
+    let expected = [
+        (
+            HashAlgorithm::Md5,
+            "8df98dca2397c5c8399d42a5bcc6e477",
+        ),
+        (
+            HashAlgorithm::Sha1,
+            "f91271dc2fd036f54d74d134ff739fefb59aac86",
+        ),
+        (
+            HashAlgorithm::Sha224,
+            "0277b8037023d846ee7872438a7ebf7c5e29da67713594f5004a2280",
+        ),
+        (
+            HashAlgorithm::Sha256,
+            "f84c159648a99f6ace4dc6e293ebc50e9ec6936ebd7022091d9ae0f5cd6693ba",
+        ),
+        (
+            HashAlgorithm::Sha384,
+            "ec629fe0dc1fab504fc1c89572d6573cf15c3a4b5b69d53f0c13849561a6c13e153af48d2538ce056a3fe10d69da16c3",
+        ),
+        (
+            HashAlgorithm::Sha512,
+            "9dc6330f4c966b62b735d565343cb77413deccdf42a92d9ef5e4e2ae33f6c924bbc8e34c47111bc069482d4dbcfee148419a6547f2d01500e8160b39cc2e4ae8",
+        ),
+        (
+            HashAlgorithm::Sha3_224,
+            "5e73d57bd011f0f92fef3c3b92ea4bcb4821c6d83c37db34f29e0760",
+        ),
+        (
+            HashAlgorithm::Sha3_256,
+            "49ad92b02124fdac9627ae45e008a696182ab6bfb8470457777c744aeb9df06f",
+        ),
+        (
+            HashAlgorithm::Sha3_384,
+            "3e7b62d091d75f484892bc2ed26d7b0ed37c9529f0227197cc8522971eb6f7215dd4e0cc6ea306987e0cbfe914f3a916",
+        ),
+        (
+            HashAlgorithm::Sha3_512,
+            "fafc7b7fe3332ce153966b27f6586fa5b49ec5d8dff3d7fd26a011451ca4c9de437913879159d9c5181a9a6f377ef18b48399756decea695b04fe90a9d3b93d1",
+        ),
+        (
+            HashAlgorithm::Blake2b,
+            "2f5f2d35b23f886565d4ef590d7226d15750f0971a39346f2859f38e87f0b09b871ff0176dfd41c30f2d99e53309e215601088c0299f83f8acd804b32ccea0ae",
+        ),
+        (
+            HashAlgorithm::Blake2s,
+            "e407ee3ae12bd7275d6e0c9c22a4671abae5deabaedf52d6a8af6c12e674249a",
+        ),
+    ];
+    let opts: BTreeMap<String, String> = [(KEY_KEY.to_string(), "aa".repeat(200))]
+        .into_iter()
+        .collect();
+
+    for (algo, el) in expected {
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), opts.clone()).unwrap();
+        hasher.write(b"Test Using Larger Than Block-Size Key - Hash Key First");
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+}
+
+#[test]
+fn hmac_key_encodings() {
+    let expected = "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+
+    for key in ["4a656665", "hex:4a656665", "base64:SmVmZQ==", "text:Jefe"] {
+        let opts: BTreeMap<String, String> = [(KEY_KEY.to_string(), key.to_string())]
+            .into_iter()
+            .collect();
+        let mut hasher = make_hasher(HashAlgorithm::Sha256, 1.try_into().unwrap(), opts).unwrap();
+        hasher.write(b"what do ya want for nothing?");
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), expected);
+    }
+
+    let opts: BTreeMap<String, String> = [(KEY_KEY.to_string(), "Jefe".to_string())]
+        .into_iter()
+        .collect();
+    assert!(make_hasher(HashAlgorithm::Sha256, 1.try_into().unwrap(), opts).is_err());
+}
+
+// From the official BLAKE3 test vectors, where the input is bytes of the sequence 0, 1, 2, ..., 250, 0, 1, ...
+const BLAKE3_TEST_VECTORS_KEY: &str = "whats the Elvish word for friend";
+const BLAKE3_TEST_VECTORS_CONTEXT: &str = "BLAKE3 2019-12-27 16:29:52 test vectors context";
+
+#[test]
+fn blake3_keyed() {
+    let expected = [
+        (
+            0,
+            "92b2b75604ed3c761f9d6f62392c8a9227ad0ea3f09573e783f1498a4ed60d26",
+        ),
+        (
+            1,
+            "6d7878dfff2f485635d39013278ae14f1454b8c0a3a2d34bc1ab38228a80c95b",
+        ),
+    ];
+    let opts: BTreeMap<String, String> =
+        [(KEY_KEY.to_string(), hex::encode(BLAKE3_TEST_VECTORS_KEY))]
+            .into_iter()
+            .collect();
+
+    for (input_len, el) in expected {
+        let input = (0..input_len).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        let mut hasher =
+            make_hasher(HashAlgorithm::Blake3, 1.try_into().unwrap(), opts.clone()).unwrap();
+        hasher.write(&input);
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+}
+
+#[test]
+fn blake3_derive_key() {
+    let expected = [
+        (
+            0,
+            "2cc39783c223154fea8dfb7c1b1660f2ac2dcbd1c1de8277b0b0dd39b7e50d7d",
+        ),
+        (
+            1,
+            "b3e2e340a117a499c6cf2398a19ee0d29cca2bb7404c73063382693bf66cb06c",
+        ),
+    ];
+    let opts: BTreeMap<String, String> = [(
+        blake3_options::DERIVE_KEY_CONTEXT_KEY.to_string(),
+        BLAKE3_TEST_VECTORS_CONTEXT.to_string(),
+    )]
+    .into_iter()
+    .collect();
+
+    for (input_len, el) in expected {
+        let input = (0..input_len).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        let mut hasher =
+            make_hasher(HashAlgorithm::Blake3, 1.try_into().unwrap(), opts.clone()).unwrap();
+        hasher.write(&input);
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+}
+
+#[test]
+fn blake3_invalid_options() {
+    let opts: BTreeMap<String, String> = [(KEY_KEY.to_string(), "00".repeat(31))]
+        .into_iter()
+        .collect();
+    assert!(
+        make_hasher(HashAlgorithm::Blake3, 1.try_into().unwrap(), opts)
+            .unwrap_err()
+            .to_string()
+            .contains("must be 32 bytes")
+    );
+
+    let opts: BTreeMap<String, String> = [
+        (KEY_KEY.to_string(), "00".repeat(32)),
+        (
+            blake3_options::DERIVE_KEY_CONTEXT_KEY.to_string(),
+            "context".to_string(),
+        ),
+    ]
+    .into_iter()
+    .collect();
+    assert!(make_hasher(HashAlgorithm::Blake3, 1.try_into().unwrap(), opts).is_err());
+}
+
+#[test]
+fn blake3_extended_output() {
+    // From the official BLAKE3 test vectors, with an empty input
+    let expected = "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262e00f03e7b69af26b7faaf09fcd333050338ddfe085b8cc869ca98b206c08243a26f5487789e8f660afe6c99ef9e0c52b92e7393024a80459cf91f476f9ffdbda7001c22e159b402631f277ca96f2defdf1078282314e763699a31c5363165421cce14d";
+
+    let opts: BTreeMap<String, String> = [(OUTPUT_SIZE_KEY.to_string(), "131".to_string())]
+        .into_iter()
+        .collect();
+    let mut hasher = make_hasher(HashAlgorithm::Blake3, 1.try_into().unwrap(), opts).unwrap();
+    assert_eq!(hex::encode(hasher.finalize_and_reset()), expected);
+
+    // Seeking gives the rest of the output stream
+    let opts: BTreeMap<String, String> = [
+        (OUTPUT_SIZE_KEY.to_string(), "99".to_string()),
+        (blake3_options::SEEK_KEY.to_string(), "32".to_string()),
+    ]
+    .into_iter()
+    .collect();
+    let mut hasher = make_hasher(HashAlgorithm::Blake3, 1.try_into().unwrap(), opts).unwrap();
+    assert_eq!(hex::encode(hasher.finalize_and_reset()), expected[64..]);
+}
+
+#[test]
+fn blake3_with_non_default_size() {
+    // To generate, you can cut parts from the command below
+    // echo -n "abc" | ./thash -a blake3 -o output-size=64 -F binary | ./thash -a blake3 -o output-size=64 -F binary | ./thash -a blake3 -o output-size=64
+    let expected = [
+        "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d851fb250ae7393f5d02813b65d521a0d492d9ba09cf7ce7f4cffd900f23374bf0b",
+        "93c4a5a70bd258a3a856c4ca68df7b475954057b06b9dcadd815545a555f076d29532d870cbda8fa69d3d1250ca6593935b437853c2411d868dd7c7e282b4758",
+        "09e706d3603940d92e8fb2bb92db5dff660d43ddb50714e8135924f464a44752fd2977b75ad39033b1d6aa4657d18390cc2af21ac54dcfdf29367de20c3ee155",
+    ];
+    let opts: BTreeMap<String, String> = [(OUTPUT_SIZE_KEY.to_string(), "64".to_string())]
+        .into_iter()
+        .collect();
+
+    for (i, el) in expected.into_iter().enumerate() {
+        let mut hasher = make_hasher(
+            HashAlgorithm::Blake3,
+            (i as u64 + 1).try_into().unwrap(),
+            opts.clone(),
+        )
+        .unwrap();
+        hasher.write(b"abc");
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+}
+
+#[test]
+fn blake2_keyed() {
+    // To generate, in Python: hashlib.blake2b(data, key=b"key").digest(), repeated with the result as data
+    let expected = [
+        "5c6a9a4ae911c02fb7e71a991eb9aea371ae993d4842d206e6020d46f5e41358c6d5c277c110ef86c959ed63e6ecaaaceaaff38019a43264ae06acf73b9550b1",
+        "c69bb9d1e5c3a5f396a5e83a23c2b9b3f722c08a40804271ed3d8796887608dc3d2a746c93b6cc3ddec40ae2ab865a1ea9bc37242701465b798b465f906ab5c0",
+        "3f31eca47c63cc6ca1d345107d346aed82f3c45d7272a70aa69510fb7ec3a55f72d33352bbad7aecd159d7dfbee6279442120e3eb0b70d752cfb453f269040cd",
+    ];
+    let opts: BTreeMap<String, String> = [(
+        blake2_options::BLAKE2_KEY_KEY.to_string(),
+        "text:key".to_string(),
+    )]
+    .into_iter()
+    .collect();
+
+    for (i, el) in expected.into_iter().enumerate() {
+        let mut hasher = make_hasher(
+            HashAlgorithm::Blake2b,
+            (i as u64 + 1).try_into().unwrap(),
+            opts.clone(),
+        )
+        .unwrap();
+        hasher.write(b"abc");
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+}
+
+#[test]
+fn blake2_all_parameters() {
+    // hashlib.blake2s(data, digest_size=16, key=b"key", salt=b"salt", person=b"me").digest(), repeated
+    let expected = [
+        "6ce549b6209a8994c7272010531992c5",
+        "8fde26fdf75de78c4205944c5128d237",
+        "d14447a87ac029b7353be4a420a8119a",
+    ];
+    let opts: BTreeMap<String, String> = [
+        (OUTPUT_SIZE_KEY.to_string(), "16".to_string()),
+        (
+            blake2_options::BLAKE2_KEY_KEY.to_string(),
+            "text:key".to_string(),
+        ),
+        (SALT_KEY.to_string(), "text:salt".to_string()),
+        (blake2_options::PERSONAL_KEY.to_string(), "6d65".to_string()),
+    ]
+    .into_iter()
+    .collect();
+
+    for (i, el) in expected.into_iter().enumerate() {
+        let mut hasher = make_hasher(
+            HashAlgorithm::Blake2s,
+            (i as u64 + 1).try_into().unwrap(),
+            opts.clone(),
+        )
+        .unwrap();
+        hasher.write(b"abc");
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+}
+
+#[test]
+fn blake2_with_non_default_size() {
+    // hashlib.blake2b(data, digest_size=20).digest(), repeated with the result as data
+    let expected = [
+        "384264f676f39536840523f284921cdc68b6846b",
+        "3318b6e0717bd9cf71f526b62096bcb1cc601125",
+        "f859ca252c00da4e040a1fff893903e42b0f0c67",
+    ];
+    let opts: BTreeMap<String, String> = [(OUTPUT_SIZE_KEY.to_string(), "20".to_string())]
+        .into_iter()
+        .collect();
+
+    for (i, el) in expected.into_iter().enumerate() {
+        let mut hasher = make_hasher(
+            HashAlgorithm::Blake2b,
+            (i as u64 + 1).try_into().unwrap(),
+            opts.clone(),
+        )
+        .unwrap();
+        hasher.write(b"abc");
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+}
+
+#[test]
+fn blake2_invalid_options() {
+    let invalid = [
+        (HashAlgorithm::Blake2b, OUTPUT_SIZE_KEY, "65"),
+        (HashAlgorithm::Blake2s, OUTPUT_SIZE_KEY, "33"),
+        (HashAlgorithm::Blake2s, OUTPUT_SIZE_KEY, "0"),
+        (
+            HashAlgorithm::Blake2b,
+            blake2_options::BLAKE2_KEY_KEY,
+            &"00".repeat(65),
+        ),
+        (
+            HashAlgorithm::Blake2s,
+            blake2_options::BLAKE2_KEY_KEY,
+            &"00".repeat(33),
+        ),
+        (HashAlgorithm::Blake2b, SALT_KEY, &"00".repeat(17)),
+        (HashAlgorithm::Blake2s, SALT_KEY, &"00".repeat(9)),
+        (
+            HashAlgorithm::Blake2b,
+            blake2_options::PERSONAL_KEY,
+            &"00".repeat(17),
+        ),
+        (
+            HashAlgorithm::Blake2s,
+            blake2_options::PERSONAL_KEY,
+            &"00".repeat(9),
+        ),
+    ];
+
+    for (algo, key, value) in invalid {
+        let opts: BTreeMap<String, String> =
+            [(key.to_string(), value.to_string())].into_iter().collect();
+        assert!(make_hasher(algo, 1.try_into().unwrap(), opts).is_err());
+    }
+}
+
+#[test]
+fn blake2_hmac_cannot_be_combined_with_parameters() {
+    for other_key in [
+        OUTPUT_SIZE_KEY,
+        blake2_options::BLAKE2_KEY_KEY,
+        SALT_KEY,
+        blake2_options::PERSONAL_KEY,
+    ] {
+        let opts: BTreeMap<String, String> = [
+            (KEY_KEY.to_string(), "00".to_string()),
+            (other_key.to_string(), "10".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        assert!(make_hasher(HashAlgorithm::Blake2b, 1.try_into().unwrap(), opts).is_err());
+    }
+}
+
+#[test]
+fn shake() {
+    // To generate, in Python: hashlib.shake_128(data).digest(32), repeated with the result as data
+    let expected = [
+        (
+            HashAlgorithm::Shake128,
+            [
+                "5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc8",
+                "457a90873d5ee97dc44f57ee9d47babc0347832199f74ea8e0beebcd1a71eaa2",
+                "4fc03992a03553f53984637e8e0240fead06ab227354de3db865480f905a91b7",
+            ],
+        ),
+        (
+            HashAlgorithm::Shake256,
+            [
+                "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4",
+                "b59f8d64897e6572cad787574676c95d265f3020ec3837e7393e25a42aecb3e58e4b58f0bc4c56732aac8c111af2912e5f5f5c54a4479b32dce2b3f9b705607a",
+                "f4399034c05dd158456cd8609c0adc7277aee17f1a334630449c6b8473991f5e979b7868a2cee5a14dc4464a3d12eaa2305336652f1f4f7d0ee56d5e6ee1ede0",
+            ],
+        ),
+    ];
+
+    for (algo, expected) in expected {
+        for (i, el) in expected.into_iter().enumerate() {
+            let mut hasher =
+                make_hasher(algo, (i as u64 + 1).try_into().unwrap(), BTreeMap::new()).unwrap();
+            hasher.write(b"abc");
+            assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+        }
+    }
+}
+
+#[test]
+fn shake_with_non_default_size() {
+    let opts: BTreeMap<String, String> = [(OUTPUT_SIZE_KEY.to_string(), "100".to_string())]
+        .into_iter()
+        .collect();
+    let mut hasher = make_hasher(HashAlgorithm::Shake128, 1.try_into().unwrap(), opts).unwrap();
+    hasher.write(b"abc");
+    assert_eq!(
+        hex::encode(hasher.finalize_and_reset()),
+        "5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc844c50af32acd3f2cdd066568706f509bc1bdde58295dae3f891a9a0fca5783789a41f8611214ce612394df286a62d1a2252aa94db9c538956c717dc2bed4f232a0294c85"
+    );
+}
+
+#[test]
+fn cshake() {
+    // From the NIST SP 800-185 cSHAKE samples
+    let expected = [
+        (
+            HashAlgorithm::Cshake128,
+            "c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5",
+        ),
+        (
+            HashAlgorithm::Cshake256,
+            "d008828e2b80ac9d2218ffee1d070c48b8e4c87bff32c9699d5b6896eee0edd164020e2be0560858d9c00c037e34a96937c561a74c412bb4c746469527281c8c",
+        ),
+    ];
+    let opts: BTreeMap<String, String> =
+        [(CUSTOMIZATION_KEY.to_string(), "Email Signature".to_string())]
+            .into_iter()
+            .collect();
+
+    for (algo, el) in expected {
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), opts.clone()).unwrap();
+        hasher.write(&[0, 1, 2, 3]);
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+
+    // Without a function name and a customization string, cSHAKE is SHAKE
+    let mut hasher = make_hasher(
+        HashAlgorithm::Cshake256,
+        1.try_into().unwrap(),
+        BTreeMap::new(),
+    )
+    .unwrap();
+    hasher.write(b"abc");
+    assert_eq!(hex::encode(hasher.finalize_and_reset()), "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4");
+
+    // The function name and customization string are kept for all iterations
+    let opts: BTreeMap<String, String> = [
+        (
+            shake_options::FUNCTION_NAME_KEY.to_string(),
+            "fn".to_string(),
+        ),
+        (CUSTOMIZATION_KEY.to_string(), "hex:63757374".to_string()),
+        (OUTPUT_SIZE_KEY.to_string(), "20".to_string()),
+    ]
+    .into_iter()
+    .collect();
+    let mut hasher = make_hasher(HashAlgorithm::Cshake128, 2.try_into().unwrap(), opts).unwrap();
+    hasher.write(b"abc");
+    assert_eq!(
+        hex::encode(hasher.finalize_and_reset()),
+        "5bec2996f8170649d32867742baac785d8365337"
+    );
+}
+
+// The key of the NIST SP 800-185 KMAC samples
+const KMAC_SAMPLES_KEY: &str = "404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5f";
+
+#[test]
+fn kmac() {
+    // From the NIST SP 800-185 KMAC samples
+    let expected = [
+        (
+            HashAlgorithm::Kmac128,
+            "",
+            vec![0, 1, 2, 3],
+            "e5780b0d3ea6f7d3a429c5706aa43a00fadbd7d49628839e3187243f456ee14e",
+        ),
+        (
+            HashAlgorithm::Kmac128,
+            "My Tagged Application",
+            vec![0, 1, 2, 3],
+            "3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5",
+        ),
+        (
+            HashAlgorithm::Kmac256,
+            "My Tagged Application",
+            (0..200).collect(),
+            "b58618f71f92e1d56c1b8c55ddd7cd188b97b4ca4d99831eb2699a837da2e4d970fbacfde50033aea585f1a2708510c32d07880801bd182898fe476876fc8965",
+        ),
+    ];
+
+    for (algo, customization, data, el) in expected {
+        let opts: BTreeMap<String, String> = [
+            (KEY_KEY.to_string(), KMAC_SAMPLES_KEY.to_string()),
+            (CUSTOMIZATION_KEY.to_string(), customization.to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), opts).unwrap();
+        hasher.write(&data);
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+
+    // The key is kept for all iterations
+    let opts: BTreeMap<String, String> = [(KEY_KEY.to_string(), "text:key".to_string())]
+        .into_iter()
+        .collect();
+    let mut hasher = make_hasher(HashAlgorithm::Kmac128, 2.try_into().unwrap(), opts).unwrap();
+    hasher.write(b"abc");
+    assert_eq!(
+        hex::encode(hasher.finalize_and_reset()),
+        "97a43186052bb0e4022b2d6f6f93b2994ce02b93af133f2f4b6c5f744fe7d4f4"
+    );
+
+    // A key is required
+    assert!(make_hasher(
+        HashAlgorithm::Kmac256,
+        1.try_into().unwrap(),
+        BTreeMap::new()
+    )
+    .is_err());
+}
+
+#[test]
+fn tuple_hash() {
+    // The first is from the NIST SP 800-185 TupleHash samples. The second isn't a sample, it was generated
+    // with this implementation, with the elements of the NIST samples and an empty customization.
+    let expected = [
+        (
+            HashAlgorithm::Tuplehash128,
+            "My Tuple App",
+            vec![vec![0, 1, 2], vec![0x10, 0x11, 0x12, 0x13, 0x14, 0x15]],
+            "75cdb20ff4db1154e841d758e24160c54bae86eb8c13e7f5f40eb35588e96dfb",
+        ),
+        (
+            HashAlgorithm::Tuplehash256,
+            "",
+            vec![
+                vec![0, 1, 2],
+                vec![0x10, 0x11, 0x12, 0x13, 0x14, 0x15],
+                (0x20..0x29).collect(),
+            ],
+            "351d5ee026e44ce15b309187aa100263eebddf11b9ab130709a18d538b92518513ec7e9bc10c524cf408668b7ee7feaefb5927c7f59b82d249551c2182da860e",
+        ),
+    ];
+
+    for (algo, customization, elements, el) in expected {
+        let opts: BTreeMap<String, String> =
+            [(CUSTOMIZATION_KEY.to_string(), customization.to_string())]
+                .into_iter()
+                .collect();
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), opts).unwrap();
+        for element in elements {
+            // Elements can be written in parts
+            for part in element.chunks(2) {
+                hasher.write(part);
+            }
+            hasher.end_tuple_element().unwrap();
+        }
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+
+    // Without ending elements, the whole input is a single element.
+    // To generate, TupleHash128 of a tuple with one empty element
+    let expected_single_empty = "549330469327c593eb95b1d467c48e5781939e135e10632c804ef8a69c73281c";
+    let mut hasher = make_hasher(
+        HashAlgorithm::Tuplehash128,
+        1.try_into().unwrap(),
+        BTreeMap::new(),
+    )
+    .unwrap();
+    assert_eq!(
+        hex::encode(hasher.finalize_and_reset()),
+        expected_single_empty
+    );
+    hasher.end_tuple_element().unwrap();
+    assert_eq!(
+        hex::encode(hasher.finalize_and_reset()),
+        expected_single_empty
+    );
+
+    // Empty elements count
+    hasher.write(b"abc");
+    hasher.end_tuple_element().unwrap();
+    hasher.end_tuple_element().unwrap();
+    assert_eq!(
+        hex::encode(hasher.finalize_and_reset()),
+        "db3e1dbb703f54ccf1143b124a6dc29dd58b1013179c04d60b4a039fcae31691"
+    );
+
+    // Other algorithms don't hash tuples
+    let mut hasher = make_hasher(
+        HashAlgorithm::Sha256,
+        1.try_into().unwrap(),
+        BTreeMap::new(),
+    )
+    .unwrap();
+    assert!(hasher.end_tuple_element().is_err());
+}
+
+#[test]
+fn tuple_hash_with_announced_lengths() {
+    let elements: [&[u8]; 3] = [b"abc", b"", &[7; 1000]];
+
+    let mut hasher = make_hasher(
+        HashAlgorithm::Tuplehash256,
+        1.try_into().unwrap(),
+        BTreeMap::new(),
+    )
+    .unwrap();
+    for element in elements {
+        hasher.write(element);
+        hasher.end_tuple_element().unwrap();
+    }
+    let expected = hasher.finalize_and_reset();
+
+    // Elements with announced lengths aren't kept in memory, but give the same digest
+    for element in elements {
+        hasher.begin_input(element.len() as u64);
+        for part in element.chunks(300) {
+            hasher.write(part);
+        }
+        hasher.end_input().unwrap();
+        hasher.end_tuple_element().unwrap();
+    }
+    assert_eq!(hasher.finalize_and_reset(), expected);
+
+    // Also for a single element, without ending it
+    hasher.write(b"abc");
+    let expected = hasher.finalize_and_reset();
+    hasher.begin_input(3);
+    hasher.write(b"abc");
+    hasher.end_input().unwrap();
+    assert_eq!(hasher.finalize_and_reset(), expected);
+
+    // An input that isn't as long as announced is an error
+    hasher.begin_input(4);
+    hasher.write(b"abc");
+    assert!(hasher.end_input().is_err());
+    assert!(hasher.end_tuple_element().is_err());
+    hasher.finalize_and_reset();
+    hasher.write(b"abc");
+    assert_eq!(hasher.finalize_and_reset(), expected);
+}
+
+#[test]
+fn parallel_hash() {
+    // From the NIST SP 800-185 ParallelHash samples
+    let data = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16,
+        0x17, 0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27,
+    ];
+    let expected = [
+        (
+            HashAlgorithm::Parallelhash128,
+            "Parallel Data",
+            "fc484dcb3f84dceedc353438151bee58157d6efed0445a81f165e495795b7206",
+        ),
+        (
+            HashAlgorithm::Parallelhash256,
+            "",
+            "bc1ef124da34495e948ead207dd9842235da432d2bbc54b4c110e64c451105531b7f2a3e0ce055c02805e7c2de1fb746af97a1dd01f43b824e31b87612410429",
+        ),
+    ];
+
+    for (algo, customization, el) in expected {
+        let opts: BTreeMap<String, String> = [
+            (
+                sp800_185_options::BLOCK_SIZE_KEY.to_string(),
+                "8".to_string(),
+            ),
+            (CUSTOMIZATION_KEY.to_string(), customization.to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), opts).unwrap();
+        // Writes that don't align with the blocks
+        for part in data.chunks(5) {
+            hasher.write(part);
+        }
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+
+    // The parameters are kept for all iterations
+    let opts: BTreeMap<String, String> = [
+        (
+            sp800_185_options::BLOCK_SIZE_KEY.to_string(),
+            "2".to_string(),
+        ),
+        (CUSTOMIZATION_KEY.to_string(), "c".to_string()),
+        (OUTPUT_SIZE_KEY.to_string(), "20".to_string()),
+    ]
+    .into_iter()
+    .collect();
+    let mut hasher =
+        make_hasher(HashAlgorithm::Parallelhash256, 2.try_into().unwrap(), opts).unwrap();
+    hasher.write(b"abc");
+    assert_eq!(
+        hex::encode(hasher.finalize_and_reset()),
+        "25a2992b38d8a937e02a5b09df6e9955d14291a9"
+    );
+}
+
+#[test]
+fn parallel_hash_blocks_in_parallel() {
+    // With threads, many blocks in a single write are hashed in parallel, which must give the same digest
+    // as blocks that are completed one byte at a time
+    let data = (0..300_000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+    for block_size in ["8", "1000", "8192"] {
+        let opts: BTreeMap<String, String> = [(
+            sp800_185_options::BLOCK_SIZE_KEY.to_string(),
+            block_size.to_string(),
+        )]
+        .into_iter()
+        .collect();
+        let mut single_threaded = make_hasher(
+            HashAlgorithm::Parallelhash128,
+            1.try_into().unwrap(),
+            opts.clone(),
+        )
+        .unwrap();
+        for byte in &data {
+            single_threaded.write(std::slice::from_ref(byte));
+        }
+        let expected = single_threaded.finalize_and_reset();
+
+        single_threaded.write(&data);
+        assert_eq!(
+            single_threaded.finalize_and_reset(),
+            expected,
+            "{block_size}"
+        );
+
+        let mut opts = opts;
+        opts.insert(THREADS_KEY.to_string(), "4".to_string());
+        let mut hasher =
+            make_hasher(HashAlgorithm::Parallelhash128, 1.try_into().unwrap(), opts).unwrap();
+
+        hasher.write(&data);
+        assert_eq!(hasher.finalize_and_reset(), expected, "{block_size}");
+
+        for part in data.chunks(70_001) {
+            hasher.write(part);
+        }
+        assert_eq!(hasher.finalize_and_reset(), expected, "{block_size}");
+    }
+}
+
+// The pattern of the RFC 9861 test vectors, n bytes of the sequence 0, 1, 2, ..., 250, 0, 1, ...
+fn rfc9861_pattern(n: usize) -> Vec<u8> {
+    (0..n).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn turbo_shake_rfc9861() {
+    let expected = [
+        (
+            HashAlgorithm::Turboshake128,
+            vec![],
+            "0x1F",
+            32,
+            "1e415f1c5983aff2169217277d17bb538cd945a397ddec541f1ce41af2c1b74c",
+        ),
+        (
+            HashAlgorithm::Turboshake128,
+            vec![],
+            "31",
+            64,
+            "1e415f1c5983aff2169217277d17bb538cd945a397ddec541f1ce41af2c1b74c3e8ccae2a4dae56c84a04c2385c03c15e8193bdf58737363321691c05462c8df",
+        ),
+        (
+            HashAlgorithm::Turboshake128,
+            vec![0xff, 0xff, 0xff],
+            "0x01",
+            32,
+            "bf323f940494e88ee1c540fe660be8a0c93f43d15ec006998462fa994eed5dab",
+        ),
+        (
+            HashAlgorithm::Turboshake256,
+            vec![],
+            "0x1F",
+            64,
+            "367a329dafea871c7802ec67f905ae13c57695dc2c6663c61035f59a18f8e7db11edc0e12e91ea60eb6b32df06dd7f002fbafabb6e13ec1cc20d995547600db0",
+        ),
+        (
+            HashAlgorithm::Turboshake256,
+            vec![0xff, 0xff, 0xff],
+            "0x01",
+            64,
+            "d21c6fbbf587fa2282f29aea620175fb0257413af78a0b1b2a87419ce031d933ae7a4d383327a8a17641a34f8a1d1003ad7da6b72dba84bb62fef28f62f12424",
+        ),
+    ];
+
+    for (algo, data, domain_separation, output_size, el) in expected {
+        let opts: BTreeMap<String, String> = [
+            (
+                turbo_shake_options::DOMAIN_SEPARATION_KEY.to_string(),
+                domain_separation.to_string(),
+            ),
+            (OUTPUT_SIZE_KEY.to_string(), output_size.to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), opts).unwrap();
+        hasher.write(&data);
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+
+    // The last 32 bytes of 10032 bytes of output
+    let opts: BTreeMap<String, String> = [(OUTPUT_SIZE_KEY.to_string(), "10032".to_string())]
+        .into_iter()
+        .collect();
+    let mut hasher =
+        make_hasher(HashAlgorithm::Turboshake256, 1.try_into().unwrap(), opts).unwrap();
+    assert_eq!(
+        hex::encode(&hasher.finalize_and_reset()[10000..]),
+        "abefa11630c661269249742685ec082f207265dccf2f43534e9c61ba0c9d1d75"
+    );
+
+    for invalid in ["0", "0x80", "256", "abc"] {
+        let opts: BTreeMap<String, String> = [(
+            turbo_shake_options::DOMAIN_SEPARATION_KEY.to_string(),
+            invalid.to_string(),
+        )]
+        .into_iter()
+        .collect();
+        assert!(make_hasher(HashAlgorithm::Turboshake128, 1.try_into().unwrap(), opts).is_err());
+    }
+}
+
+#[test]
+fn kangaroo_twelve_rfc9861() {
+    // Messages and customization strings around the size of a chunk, which is 8192 bytes
+    let expected = [
+        (
+            HashAlgorithm::K12,
+            rfc9861_pattern(17 * 17),
+            vec![],
+            "0c315ebcdedbf61426de7dcf8fb725d1e74675d7f5327a5067f367b108ecb67c",
+        ),
+        (
+            HashAlgorithm::K12,
+            rfc9861_pattern(17 * 17 * 17 * 17),
+            vec![],
+            "8701045e22205345ff4dda05555cbb5c3af1a771c2b89baef37db43d9998b9fe",
+        ),
+        (
+            HashAlgorithm::K12,
+            rfc9861_pattern(8191),
+            vec![],
+            "1b577636f723643e990cc7d6a659837436fd6a103626600eb8301cd1dbe553d6",
+        ),
+        (
+            HashAlgorithm::K12,
+            rfc9861_pattern(8192),
+            vec![],
+            "48f256f6772f9edfb6a8b661ec92dc93b95ebd05a08a17b39ae3490870c926c3",
+        ),
+        (
+            HashAlgorithm::K12,
+            rfc9861_pattern(8192),
+            rfc9861_pattern(8189),
+            "3ed12f70fb05ddb58689510ab3e4d23c6c6033849aa01e1d8c220a297fedcd0b",
+        ),
+        (
+            HashAlgorithm::K12,
+            rfc9861_pattern(8192),
+            rfc9861_pattern(8190),
+            "6a7c1b6a5cd0d8c9ca943a4a216cc64604559a2ea45f78570a15253d67ba00ae",
+        ),
+        (
+            HashAlgorithm::Kt256,
+            vec![],
+            vec![],
+            "b23d2e9cea9f4904e02bec06817fc10ce38ce8e93ef4c89e6537076af8646404e3e8b68107b8833a5d30490aa33482353fd4adc7148ecb782855003aaebde4a9",
+        ),
+        (
+            HashAlgorithm::Kt256,
+            rfc9861_pattern(17),
+            vec![],
+            "1ba3c02b1fc514474f06c8979978a9056c8483f4a1b63d0dccefe3a28a2f323e1cdcca40ebf006ac76ef0397152346837b1277d3e7faa9c9653b19075098527b",
+        ),
+        (
+            HashAlgorithm::Kt256,
+            rfc9861_pattern(17 * 17 * 17 * 17),
+            vec![],
+            "b06275d284cd1cf205bcbe57dccd3ec1ff6686e3ed15776383e1f2fa3c6ac8f08bf8a162829db1a44b2a43ff83dd89c3cf1ceb61ede659766d5ccf817a62ba8d",
+        ),
+        (
+            HashAlgorithm::Kt256,
+            vec![],
+            rfc9861_pattern(1),
+            "9280f5cc39b54a5a594ec63de0bb99371e4609d44bf845c2f5b8c316d72b159811f748f23e3fabbe5c3226ec96c62186df2d33e9df74c5069ceecbb4dd10eff6",
+        ),
+        (
+            HashAlgorithm::Kt256,
+            vec![0xff],
+            rfc9861_pattern(41),
+            "47ef96dd616f200937aa7847e34ec2feae8087e3761dc0f8c1a154f51dc9ccf845d7adbce57ff64b639722c6a1672e3bf5372d87e00aff89be97240756998853",
+        ),
+        (
+            HashAlgorithm::Kt256,
+            vec![0xff, 0xff, 0xff],
+            rfc9861_pattern(41 * 41),
+            "3b48667a5051c5966c53c5d42b95de451e05584e7806e2fb765eda959074172cb438a9e91dde337c98e9c41bed94c4e0aef431d0b64ef2324f7932caa6f54969",
+        ),
+        (
+            HashAlgorithm::Kt256,
+            rfc9861_pattern(8192),
+            rfc9861_pattern(8190),
+            "f4b5908b929ffe01e0f79ec2f21243d41a396b2e7303a6af1d6399cd6c7a0a2dd7c4f607e8277f9c9b1cb4ab9ddc59d4b92d1fc7558441f1832c3279a4241b8b",
+        ),
+    ];
+
+    for (algo, data, customization, el) in expected {
+        let opts: BTreeMap<String, String> = [(
+            CUSTOMIZATION_KEY.to_string(),
+            format!("hex:{}", hex::encode(customization)),
+        )]
+        .into_iter()
+        .collect();
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), opts).unwrap();
+        // Writes that don't align with the chunks
+        for part in data.chunks(1000) {
+            hasher.write(part);
+        }
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+
+        // The hasher is reset properly, also after hashing more than one chunk
+        hasher.write(&data);
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+}
+
+#[test]
+fn checksums() {
+    // The check values of the catalogues of CRCs, which are the checksums of "123456789"
+    let expected = [
+        (HashAlgorithm::Crc32, "cbf43926"),
+        (HashAlgorithm::Crc32c, "e3069283"),
+        (HashAlgorithm::Crc64, "995dc9bbdf1939fa"),
+        (HashAlgorithm::Crc64Ecma, "6c40df5f0b497347"),
+        (HashAlgorithm::Crc64Nvme, "ae8b14860a799888"),
+        (HashAlgorithm::Adler32, "091e01de"),
+        // printf 123456789 | cksum, in hex
+        (HashAlgorithm::Cksum, "377a6011"),
+    ];
+
+    for (algo, el) in expected {
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), BTreeMap::new()).unwrap();
+        hasher.write(b"12345");
+        hasher.write(b"6789");
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el, "{algo}");
+        // The hasher is reset, so the length that cksum appends starts over too
+        hasher.write(b"123456789");
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el, "{algo}");
+    }
+
+    // printf '' | cksum
+    let mut hasher =
+        make_hasher(HashAlgorithm::Cksum, 1.try_into().unwrap(), BTreeMap::new()).unwrap();
+    assert_eq!(hex::encode(hasher.finalize_and_reset()), "ffffffff");
+
+    // Adler-32 must reduce its sums before they overflow
+    let mut hasher = make_hasher(
+        HashAlgorithm::Adler32,
+        1.try_into().unwrap(),
+        BTreeMap::new(),
+    )
+    .unwrap();
+    hasher.write(&[0xFF; 100_000]);
+    // python3 -c "import zlib; print('%08x' % zlib.adler32(b'\xff' * 100000))"
+    assert_eq!(hex::encode(hasher.finalize_and_reset()), "149a302c");
+}
+
+#[test]
+fn custom_crc() {
+    // Entries of the catalogue of parametrised CRC algorithms, with their check values
+    let expected = [
+        // CRC-32/ISO-HDLC
+        (
+            vec![
+                ("polynomial", "0x04C11DB7"),
+                ("init", "0xFFFFFFFF"),
+                ("xorout", "0xFFFFFFFF"),
+                ("reflect", "true"),
+            ],
+            "cbf43926",
+        ),
+        // CRC-16/IBM-3740
+        (
+            vec![
+                ("width", "16"),
+                ("polynomial", "0x1021"),
+                ("init", "0xFFFF"),
+            ],
+            "29b1",
+        ),
+        // CRC-5/G-704
+        (
+            vec![("width", "5"), ("polynomial", "0x15"), ("reflect", "true")],
+            "07",
+        ),
+        // CRC-3/ROHC
+        (
+            vec![
+                ("width", "3"),
+                ("polynomial", "3"),
+                ("init", "7"),
+                ("reflect", "true"),
+            ],
+            "06",
+        ),
+        // CRC-12/UMTS, which reflects the output but not the input
+        (
+            vec![
+                ("width", "12"),
+                ("polynomial", "0x80F"),
+                ("reflect-out", "true"),
+            ],
+            "0daf",
+        ),
+        // CRC-64/ECMA-182
+        (
+            vec![("width", "64"), ("polynomial", "0x42F0E1EBA9EA3693")],
+            "6c40df5f0b497347",
+        ),
+    ];
+
+    for (options, el) in expected {
+        let opts: BTreeMap<String, String> = options
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let mut hasher = make_hasher(HashAlgorithm::Crc, 1.try_into().unwrap(), opts).unwrap();
+        hasher.write(b"123456789");
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+}
+
+#[test]
+fn checksum_invalid_options() {
+    let invalid_options = [
+        (HashAlgorithm::Crc32, vec![("polynomial", "0x1021")]),
+        (HashAlgorithm::Cksum, vec![("output-size", "8")]),
+        (HashAlgorithm::Crc, vec![]),
+        (HashAlgorithm::Crc, vec![("polynomial", "xyz")]),
+        (
+            HashAlgorithm::Crc,
+            vec![("width", "0"), ("polynomial", "1")],
+        ),
+        (
+            HashAlgorithm::Crc,
+            vec![("width", "65"), ("polynomial", "1")],
+        ),
+        (
+            HashAlgorithm::Crc,
+            vec![("width", "8"), ("polynomial", "0x107")],
+        ),
+        (
+            HashAlgorithm::Crc,
+            vec![("width", "8"), ("polynomial", "7"), ("init", "0x100")],
+        ),
+        (
+            HashAlgorithm::Crc,
+            vec![("polynomial", "7"), ("reflect", "yes")],
+        ),
+    ];
+
+    for (algo, options) in invalid_options {
+        let opts: BTreeMap<String, String> = options
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert!(make_hasher(algo, 1.try_into().unwrap(), opts).is_err());
+    }
+}
+
+#[test]
+fn fast_hashes() {
+    // Printed in the canonical form of xxhsum, and as the hex of the integer for the others
+    let expected = [
+        (HashAlgorithm::Xxh32, "02cc5d05", "32d153ff"),
+        (HashAlgorithm::Xxh64, "ef46db3751d8e999", "44bc2cf5ad770999"),
+        (
+            HashAlgorithm::Xxh3_64,
+            "2d06800538d394c2",
+            "78af5f94892f3950",
+        ),
+        (
+            HashAlgorithm::Xxh3_128,
+            "99aa06d3014798d86001c324468d497f",
+            "06b05ab6733a618578af5f94892f3950",
+        ),
+        (
+            HashAlgorithm::Wyhash,
+            "0409638ee2bde459",
+            "02a4f1d7cb516c72",
+        ),
+    ];
+
+    for (algo, empty, abc) in expected {
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), BTreeMap::new()).unwrap();
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), empty, "{algo}");
+        hasher.write(b"abc");
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), abc, "{algo}");
+    }
+}
+
+#[test]
+fn fast_hashes_with_seed() {
+    let expected = [
+        // From the test vectors of wyhash final4
+        (HashAlgorithm::Wyhash, "2", "32dd92e4b2915153"),
+        (HashAlgorithm::Xxh3_64, "0", "78af5f94892f3950"),
+    ];
+
+    for (algo, seed, el) in expected {
+        let opts = [("seed".to_string(), seed.to_string())].into();
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), opts).unwrap();
+        hasher.write(b"abc");
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el, "{algo}");
+    }
+
+    // A seed changes the result, and the hasher keeps its seed after being reset
+    for algo in [
+        HashAlgorithm::Xxh32,
+        HashAlgorithm::Xxh64,
+        HashAlgorithm::Xxh3_64,
+        HashAlgorithm::Xxh3_128,
+        HashAlgorithm::Wyhash,
+    ] {
+        let mut unseeded = make_hasher(algo, 1.try_into().unwrap(), BTreeMap::new()).unwrap();
+        let opts = [("seed".to_string(), "0x1234".to_string())].into();
+        let mut seeded = make_hasher(algo, 1.try_into().unwrap(), opts).unwrap();
+
+        unseeded.write(b"abc");
+        seeded.write(b"abc");
+        let seeded_result = seeded.finalize_and_reset();
+        assert_ne!(unseeded.finalize_and_reset(), seeded_result, "{algo}");
+        seeded.write(b"abc");
+        assert_eq!(seeded.finalize_and_reset(), seeded_result, "{algo}");
+    }
+}
+
+#[test]
+fn xxh3_with_secret() {
+    // Secrets of the minimum size, of the default size, and of sizes between and above them,
+    // which change the number of stripes in a block
+    for secret_size in [136, 150, 192, 256] {
+        let secret: Vec<u8> = (0..secret_size).map(|i| (i * 7 + 3) as u8).collect();
+        let opts: BTreeMap<String, String> = [("secret".to_string(), hex::encode(&secret))].into();
+
+        // Short, medium and long inputs are hashed in different ways, and long inputs
+        // end on the boundaries of stripes and blocks, or between them
+        for size in [0, 3, 100, 240, 241, 1000, 1024, 1025, 5000, 65536, 100_003] {
+            let data: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+
+            let mut hasher =
+                make_hasher(HashAlgorithm::Xxh3_64, 1.try_into().unwrap(), opts.clone()).unwrap();
+            for chunk in data.chunks(777) {
+                hasher.write(chunk);
+            }
+            assert_eq!(
+                hasher.finalize_and_reset(),
+                xxhash_rust::xxh3::xxh3_64_with_secret(&data, &secret).to_be_bytes(),
+                "{secret_size} {size}"
+            );
+
+            let mut hasher =
+                make_hasher(HashAlgorithm::Xxh3_128, 1.try_into().unwrap(), opts.clone()).unwrap();
+            hasher.write(&data);
+            assert_eq!(
+                hasher.finalize_and_reset(),
+                xxhash_rust::xxh3::xxh3_128_with_secret(&data, &secret).to_be_bytes(),
+                "{secret_size} {size}"
+            );
+        }
+    }
+}
+
+#[test]
+fn siphash() {
+    // The test vectors of the reference implementation list the bytes of the integer in little-endian order
+    let key = "000102030405060708090a0b0c0d0e0f";
+    let message: Vec<u8> = (0..15).collect();
+    let expected = [
+        (HashAlgorithm::Siphash24, "8", 0, "726fdb47dd0e0e31"),
+        (HashAlgorithm::Siphash24, "8", 15, "a129ca6149be45e5"),
+        (
+            HashAlgorithm::Siphash24,
+            "16",
+            0,
+            "930255c71472f66de6a825ba047f81a3",
+        ),
+        // The reference implementation has no vectors of SipHash-1-3, so these are those of
+        // the tests of Rust's standard library, which hashes with SipHash-1-3
+        (HashAlgorithm::Siphash13, "8", 0, "abac0158050fc4dc"),
+        (HashAlgorithm::Siphash13, "8", 15, "d320d86d2a519956"),
+        (
+            HashAlgorithm::Siphash13,
+            "16",
+            0,
+            "013030dd6adb62fdbea58827b2bc7ee7",
+        ),
+    ];
+
+    for (algo, output_size, message_size, el) in expected {
+        let opts = [
+            ("key".to_string(), key.to_string()),
+            ("output-size".to_string(), output_size.to_string()),
+        ]
+        .into();
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), opts).unwrap();
+        hasher.write(&message[..message_size]);
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el);
+    }
+
+    // The default key is zeros, like the hashers of Rust's hash maps without random keys
+    for algo in [HashAlgorithm::Siphash13, HashAlgorithm::Siphash24] {
+        let mut default_key = make_hasher(algo, 1.try_into().unwrap(), BTreeMap::new()).unwrap();
+        let opts = [("key".to_string(), "00".repeat(16))].into();
+        let mut zero_key = make_hasher(algo, 1.try_into().unwrap(), opts).unwrap();
+        default_key.write(b"abc");
+        zero_key.write(b"abc");
+        assert_eq!(
+            default_key.finalize_and_reset(),
+            zero_key.finalize_and_reset()
+        );
+    }
+}
+
+#[test]
+fn fast_hashes_invalid_options() {
+    let too_short_secret = "00".repeat(135);
+    let invalid_options = [
+        (HashAlgorithm::Xxh32, vec![("seed", "0x100000000")]),
+        (HashAlgorithm::Xxh64, vec![("seed", "-1")]),
+        (HashAlgorithm::Xxh64, vec![("secret", "00")]),
+        (HashAlgorithm::Wyhash, vec![("key", "00")]),
+        (HashAlgorithm::Xxh3_64, vec![("secret", "00")]),
+        (
+            HashAlgorithm::Xxh3_64,
+            vec![("secret", too_short_secret.as_str())],
+        ),
+        (
+            HashAlgorithm::Xxh3_128,
+            vec![("seed", "1"), ("secret", "00")],
+        ),
+        (HashAlgorithm::Siphash24, vec![("key", "0001")]),
+        (HashAlgorithm::Siphash13, vec![("output-size", "4")]),
+        (HashAlgorithm::Siphash13, vec![("seed", "1")]),
+    ];
+
+    for (algo, options) in invalid_options {
+        let opts: BTreeMap<String, String> = options
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert!(make_hasher(algo, 1.try_into().unwrap(), opts).is_err());
+    }
+}
+
+#[test]
+fn legacy_and_regional_hashes() {
+    // The examples of the standards and of the reference implementations
+    let expected = [
+        (
+            HashAlgorithm::Ripemd160,
+            "abc",
+            "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc",
+        ),
+        (
+            HashAlgorithm::Whirlpool,
+            "abc",
+            "4e2448a4c6f486bb16b6562c73b4020bf3043e3a731bce721ae1b303d97e6d4c7181eebdb6c57e277d0e34957114cbd6c797fc9d95d8b582d225292076d4eef5",
+        ),
+        (
+            HashAlgorithm::Tiger,
+            "abc",
+            "2aab1484e8c158f2bfb8c5ff41b57a525129131c957b5f93",
+        ),
+        (
+            HashAlgorithm::Tiger2,
+            "abc",
+            "f68d7bc5af4b43a06e048d7829560d4a9415658bb0b1f3bf",
+        ),
+        (
+            HashAlgorithm::Sm3,
+            "abc",
+            "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0",
+        ),
+        (
+            HashAlgorithm::Streebog256,
+            "012345678901234567890123456789012345678901234567890123456789012",
+            "9d151eefd8590b89daa6ba6cb74af9275dd051026bb149a452fd84e5e57b5500",
+        ),
+        (
+            HashAlgorithm::Streebog512,
+            "012345678901234567890123456789012345678901234567890123456789012",
+            "1b54d01a4af5b9d5cc3d86d68d285462b19abc2475222f35c085122be4ba1ffa00ad30f8767b3a82384c6574f024c311e2a481332b08ef7f41797891c1646f48",
+        ),
+        (
+            HashAlgorithm::Gost94,
+            "abc",
+            "f3134348c44fb1b2a277729e2285ebb5cb5e0f29c975bc753b70497c06a4d51d",
+        ),
+        (
+            HashAlgorithm::Gost94Cryptopro,
+            "abc",
+            "b285056dbf18d7392d7677369524dd14747459ed8143997e163b2986f92fd42c",
+        ),
+    ];
+
+    for (algo, message, el) in expected {
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), BTreeMap::new()).unwrap();
+        hasher.write(message.as_bytes());
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el, "{algo}");
+    }
+}
+
+#[test]
+fn hmac_ripemd160() {
+    // RFC 2286, test case 2
+    let opts = [("key".to_string(), "text:Jefe".to_string())].into();
+    let mut hasher = make_hasher(HashAlgorithm::Ripemd160, 1.try_into().unwrap(), opts).unwrap();
+    hasher.write(b"what do ya want for nothing?");
+    assert_eq!(
+        hex::encode(hasher.finalize_and_reset()),
+        "dda6c0213a485a9e24f4742064a7f033b43c4069"
+    );
+}
+
+#[test]
+fn sha512_truncated() {
+    // FIPS 180-4 examples
+    let expected = [
+        (
+            HashAlgorithm::Sha512_224,
+            "4634270f707b6a54daae7530460842e20e37ed265ceee9a43e8924aa",
+        ),
+        (
+            HashAlgorithm::Sha512_256,
+            "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23",
+        ),
+    ];
+
+    for (algo, el) in expected {
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), BTreeMap::new()).unwrap();
+        hasher.write(b"abc");
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el, "{algo}");
+    }
+}
+
+#[test]
+fn keccak_is_not_sha3() {
+    // Keccak and SHA-3 share the permutation, but SHA-3 appends the domain bits 01 before the padding,
+    // so the same input gives unrelated digests. Ethereum uses Keccak, and calls it "sha3" in Solidity.
+    let expected = [
+        (
+            "",
+            HashAlgorithm::Keccak256,
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            HashAlgorithm::Sha3_256,
+            "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+        ),
+        (
+            "abc",
+            HashAlgorithm::Keccak224,
+            "c30411768506ebe1c2871b1ee2e87d38df342317300a9b97a95ec6a8",
+            HashAlgorithm::Sha3_224,
+            "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf",
+        ),
+        (
+            "abc",
+            HashAlgorithm::Keccak256,
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+            HashAlgorithm::Sha3_256,
+            "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+        ),
+        (
+            "abc",
+            HashAlgorithm::Keccak384,
+            "f7df1165f033337be098e7d288ad6a2f74409d7a60b49c36642218de161b1f99f8c681e4afaf31a34db29fb763e3c28e",
+            HashAlgorithm::Sha3_384,
+            "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25",
+        ),
+        (
+            "abc",
+            HashAlgorithm::Keccak512,
+            "18587dc2ea106b9a1563e32b3312421ca164c7f1f07bc922a9c83d77cea3a1e5d0c69910739025372dc14ac9642629379540c17e2a65b19d77aa511a9d00bb96",
+            HashAlgorithm::Sha3_512,
+            "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+        ),
+    ];
+
+    for (message, keccak_algo, keccak_digest, sha3_algo, sha3_digest) in expected {
+        let mut keccak = make_hasher(keccak_algo, 1.try_into().unwrap(), BTreeMap::new()).unwrap();
+        keccak.write(message.as_bytes());
+        let mut sha3 = make_hasher(sha3_algo, 1.try_into().unwrap(), BTreeMap::new()).unwrap();
+        sha3.write(message.as_bytes());
+
+        assert_eq!(hex::encode(keccak.finalize_and_reset()), keccak_digest);
+        assert_eq!(hex::encode(sha3.finalize_and_reset()), sha3_digest);
+    }
+
+    // The selector of a Solidity function is the first 4 bytes of the Keccak-256 of its signature
+    let mut hasher = make_hasher(
+        HashAlgorithm::Keccak256,
+        1.try_into().unwrap(),
+        BTreeMap::new(),
+    )
+    .unwrap();
+    hasher.write(b"transfer(address,uint256)");
+    assert_eq!(hex::encode(&hasher.finalize_and_reset()[..4]), "a9059cbb");
+}
+
+#[test]
+fn sha3_finalists_and_ascon() {
+    // The digests of the empty message, from the submissions to the SHA-3 competition
+    // and from the known answer tests of NIST SP 800-232
+    let expected = [
+        (
+            HashAlgorithm::Blake224,
+            "7dc5313b1c04512a174bd6503b89607aecbee0903d40a8a569c94eed",
+        ),
+        (
+            HashAlgorithm::Blake256,
+            "716f6e863f744b9ac22c97ec7b76ea5f5908bc5b2f67c61510bfc4751384ea7a",
+        ),
+        (
+            HashAlgorithm::Blake384,
+            "c6cbd89c926ab525c242e6621f2f5fa73aa4afe3d9e24aed727faaadd6af38b620bdb623dd2b4788b1c8086984af8706",
+        ),
+        (
+            HashAlgorithm::Blake512,
+            "a8cfbbd73726062df0c6864dda65defe58ef0cc52a5625090fa17601e1eecd1b628e94f396ae402a00acc9eab77b4d4c2e852aaaa25a636d80af3fc7913ef5b8",
+        ),
+        (
+            HashAlgorithm::Groestl224,
+            "f2e180fb5947be964cd584e22e496242c6a329c577fc4ce8c36d34c3",
+        ),
+        (
+            HashAlgorithm::Groestl256,
+            "1a52d11d550039be16107f9c58db9ebcc417f16f736adb2502567119f0083467",
+        ),
+        (
+            HashAlgorithm::Groestl512,
+            "6d3ad29d279110eef3adbd66de2a0345a77baede1557f5d099fce0c03d6dc2ba8e6d4a6633dfbd66053c20faa87d1a11f39a7fbe4a6c2f009801370308fc4ad8",
+        ),
+        (
+            HashAlgorithm::Jh256,
+            "46e64619c18bb0a92a5e87185a47eef83ca747b8fcc8e1412921357e326df434",
+        ),
+        (
+            HashAlgorithm::Jh512,
+            "90ecf2f76f9d2c8017d979ad5ab96b87d58fc8fc4b83060f3f900774faa2c8fabe69c5f4ff1ec2b61d6b316941cedee117fb04b1f4c5bc1b919ae841c50eec4f",
+        ),
+        (
+            HashAlgorithm::Skein256,
+            "c8877087da56e072870daa843f176e9453115929094c3a40c463a196c29bf7ba",
+        ),
+        (
+            HashAlgorithm::Skein512,
+            "bc5b4c50925519c290cc634277ae3d6257212395cba733bbad37a4af0fa06af41fca7903d06564fea7a2d3730dbdb80c1f85562dfcc070334ea4d1d9e72cba7a",
+        ),
+        (
+            HashAlgorithm::Skein1024,
+            "0fff9563bb3279289227ac77d319b6fff8d7e9f09da1247b72a0a265cd6d2a62645ad547ed8193db48cff847c06494a03f55666d3b47eb4c20456c9373c86297d630d5578ebd34cb40991578f9f52b18003efa35d3da6553ff35db91b81ab890bec1b189b7f52cb2a783ebb7d823d725b0b4a71f6824e88f68f982eefc6d19c6",
+        ),
+        (
+            HashAlgorithm::AsconHash256,
+            "0b3be5850f2f6b98caf29f8fdea89b64a1fa70aa249b8f839bd53baa304d92b2",
+        ),
+        (
+            HashAlgorithm::AsconXof128,
+            "473d5e6164f58b39dfd84aacdb8ae42ec2d91fed33388ee0d960d9b3993295c6",
+        ),
+    ];
+
+    for (algo, el) in expected {
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), BTreeMap::new()).unwrap();
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el, "{algo}");
+    }
+}
+
+#[test]
+fn ascon_hash_cannot_be_keyed() {
+    let opts: BTreeMap<String, String> = [(KEY_KEY.to_string(), "00".to_string())].into();
+    assert!(make_hasher(HashAlgorithm::AsconHash256, 1.try_into().unwrap(), opts).is_err());
+}
+
+#[test]
+fn skein_output_size() {
+    // The output size is part of the configuration of Skein, so Skein-512-256 isn't a truncated Skein-512-512
+    let expected = [
+        (
+            HashAlgorithm::Skein256,
+            "c0fbd7d779b20f0a4614a66697f9e41859eaf382f14bf857e8cdb210adb9b3fe",
+        ),
+        (
+            HashAlgorithm::Skein512,
+            "b3250457e05d3060b1a4bbc1428bc75a3f525ca389aeab96cfa34638d96e492a",
+        ),
+    ];
+
+    for (algo, el) in expected {
+        let opts = [(OUTPUT_SIZE_KEY.to_string(), "32".to_string())].into();
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), opts).unwrap();
+        hasher.write(b"The quick brown fox jumps over the lazy dog");
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el, "{algo}");
+    }
+
+    let mut hasher = make_hasher(
+        HashAlgorithm::Skein512,
+        1.try_into().unwrap(),
+        BTreeMap::new(),
+    )
+    .unwrap();
+    hasher.write(b"The quick brown fox jumps over the lazy dog");
+    assert!(!hex::encode(hasher.finalize_and_reset())
+        .starts_with("b3250457e05d3060b1a4bbc1428bc75a3f525ca389aeab96cfa34638d96e492a"));
+}
+
+#[test]
+fn skein_multiple_blocks() {
+    // From Appendix C of the Skein 1.3 specification. The messages are the bytes ff, fe, fd and so on,
+    // two blocks of each state size, so the last block is only processed when finalizing.
+    let expected = [
+        (
+            HashAlgorithm::Skein256,
+            64,
+            "df28e916630d0b44c4a849dc9a02f07a07cb30f732318256b15d865ac4ae162f",
+        ),
+        (
+            HashAlgorithm::Skein512,
+            128,
+            "91cca510c263c4ddd010530a33073309628631f308747e1bcbaa90e451cab92e5188087af4188773a332303e6667a7a210856f742139000071f48e8ba2a5adb7",
+        ),
+        (
+            HashAlgorithm::Skein1024,
+            256,
+            "842a53c99c12b0cf80cf69491be5e2f7515de8733b6ea9422dfd676665b5fa42ffb3a9c48c217777950848cecdb48f640f81fb92bef6f88f7a85c1f7cd1446c9161c0afe8f25ae444f40d3680081c35aa43f640fd5fa3c3c030bcc06abac01d098bcc984ebd8322712921e00b1ba07d6d01f26907050255ef2c8e24f716c52a5",
+        ),
+    ];
+
+    for (algo, len, el) in expected {
+        let message = (0..len).map(|i| 0xff - i as u8).collect::<Vec<_>>();
+        let mut hasher = make_hasher(algo, 1.try_into().unwrap(), BTreeMap::new()).unwrap();
+        hasher.write(&message);
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el, "{algo}");
+
+        // Blocks that are split between writes
+        for chunk in message.chunks(7) {
+            hasher.write(chunk);
+        }
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el, "{algo}");
+    }
+}
+
+#[test]
+fn skein_output_longer_than_state() {
+    // Skein-256-512 from the known answer tests of the Skein submission, as the skein crate ships them.
+    // The output is made of two blocks of the state size, with counters 0 and 1.
+    let expected = [
+        (
+            "",
+            "357728de58a5f23315854840e0f2688d75376e7360030bba4dbd7da20306cd50cc75e66ddb6b0afd20bd0a7dacf88c8f421523f5315c0002388c39ec34eb4996",
+        ),
+        (
+            "cfec64f3e950c2bf80b13d3c18f7e7e660f6d0ec4a39b7422b8f7d7521736f80725fe176d8c3fbd544d9342b34471bc6a2318f73a177bc7791179262719d93c6",
+            "9e5b4d7ed28492278767e50d279df1fccb47e9df623a9dfb3fbbc94ff9c264f41d4ca9f424e9c65715cb5741b64ae768500f3c6d11d6527f5bce4e846cc98139",
+        ),
+    ];
+
+    let opts: BTreeMap<String, String> = [(OUTPUT_SIZE_KEY.to_string(), "64".to_string())].into();
+    let mut hasher = make_hasher(HashAlgorithm::Skein256, 1.try_into().unwrap(), opts).unwrap();
+    for (input, el) in expected {
+        hasher.write(&hex::decode(input).unwrap());
+        assert_eq!(hex::encode(hasher.finalize_and_reset()), el, "{input}");
+    }
+}
+
+#[test]
+fn ascon_xof_output_size() {
+    // Unlike Skein, the output of an XOF doesn't depend on its size, so shorter outputs are prefixes of longer ones
+    let opts = [(OUTPUT_SIZE_KEY.to_string(), "64".to_string())].into();
+    let mut hasher = make_hasher(HashAlgorithm::AsconXof128, 1.try_into().unwrap(), opts).unwrap();
+    let result = hex::encode(hasher.finalize_and_reset());
+    assert_eq!(result.len(), 128);
+    assert!(result.starts_with("473d5e6164f58b39dfd84aacdb8ae42ec2d91fed33388ee0d960d9b3993295c6"));
+}
+
+#[test]
+fn blake3_multithreaded() {
+    let opts: BTreeMap<String, String> = [(THREADS_KEY.to_string(), "4".to_string())]
+        .into_iter()
+        .collect();
+
+    // Around the smallest write that is split between threads, and much larger
+    for input_len in [1000, 128 * 1024 - 1, 128 * 1024, 3 * 1024 * 1024 + 17] {
+        let input = (0..input_len).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+        let mut single = make_hasher(
+            HashAlgorithm::Blake3,
+            1.try_into().unwrap(),
+            BTreeMap::new(),
+        )
+        .unwrap();
+        single.write(&input);
+        let mut multi =
+            make_hasher(HashAlgorithm::Blake3, 1.try_into().unwrap(), opts.clone()).unwrap();
+        multi.write(&input);
+
+        assert_eq!(multi.finalize_and_reset(), single.finalize_and_reset());
+    }
+
+    // Files are memory-mapped and hashed as a whole, and the hasher can be used again after that
+    let file = std::path::Path::new("README.md");
+    let mut single = make_hasher(
+        HashAlgorithm::Blake3,
+        1.try_into().unwrap(),
+        BTreeMap::new(),
+    )
+    .unwrap();
+    single.write(&std::fs::read(file).unwrap());
+    let expected = single.finalize_and_reset();
+
+    let mut multi = make_hasher(HashAlgorithm::Blake3, 1.try_into().unwrap(), opts).unwrap();
+    for _ in 0..2 {
+        assert!(multi.write_file(file).unwrap());
+        assert_eq!(multi.finalize_and_reset(), expected);
+    }
+    assert!(!single.write_file(file).unwrap());
+}
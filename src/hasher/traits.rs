@@ -2,11 +2,16 @@ use std::{
     fmt::Debug,
     num::NonZeroUsize,
     ops::{Deref, DerefMut},
+    path::Path,
 };
 
-use crate::hashing_lib::{sized_hasher::SizedHasher, unsized_hasher::UnsizedHasher};
+use crate::hashing_lib::{
+    hmac::Hmac,
+    sized_hasher::{BlockSizedHasher, SizedHasher},
+    unsized_hasher::UnsizedHasher,
+};
 
-use super::generic::{GenericSizedHasher, GenericUnsizedHasher};
+use super::generic::{GenericHmacHasher, GenericSizedHasher, GenericUnsizedHasher};
 
 impl<H: SizedHasher> DynHasher for GenericSizedHasher<H> {
     fn algorithm_name(&self) -> &'static str {
@@ -45,6 +50,22 @@ where
     fn output_size(&self) -> NonZeroUsize {
         self.deref().output_size()
     }
+
+    fn write_file(&mut self, path: &Path) -> anyhow::Result<bool> {
+        self.deref_mut().write_file(path)
+    }
+
+    fn begin_input(&mut self, len: u64) {
+        self.deref_mut().begin_input(len)
+    }
+
+    fn end_input(&mut self) -> anyhow::Result<()> {
+        self.deref_mut().end_input()
+    }
+
+    fn end_tuple_element(&mut self) -> anyhow::Result<()> {
+        self.deref_mut().end_tuple_element()
+    }
 }
 
 pub trait DynHasher {
@@ -55,6 +76,29 @@ pub trait DynHasher {
     fn finalize_and_reset(&mut self) -> Vec<u8>;
 
     fn output_size(&self) -> NonZeroUsize;
+
+    /// Hashes the whole file, for hashers that read files in their own way, like multithreaded BLAKE3.
+    /// Returns false if the hasher doesn't, and the file should be written into it instead.
+    fn write_file(&mut self, _path: &Path) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    /// Announces the length of the input that is written next, when it's known before reading it.
+    /// TupleHash uses it to hash files as they're read, instead of keeping them in memory.
+    fn begin_input(&mut self, _len: u64) {}
+
+    /// Checks that the input that was announced with `begin_input` had the announced length
+    fn end_input(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Ends the current element of a tuple. Only hashers of tuples, like TupleHash, support it.
+    fn end_tuple_element(&mut self) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!(
+            "Algorithm {} cannot hash tuples of inputs",
+            self.algorithm_name()
+        ))
+    }
 }
 
 impl Debug for dyn DynHasher {
@@ -79,4 +123,38 @@ impl<H: UnsizedHasher> DynHasher for GenericUnsizedHasher<H> {
     fn output_size(&self) -> NonZeroUsize {
         self.output_size()
     }
+
+    fn write_file(&mut self, path: &Path) -> anyhow::Result<bool> {
+        self.write_file(path)
+    }
+
+    fn begin_input(&mut self, len: u64) {
+        self.begin_input(len)
+    }
+
+    fn end_input(&mut self) -> anyhow::Result<()> {
+        self.end_input()
+    }
+
+    fn end_tuple_element(&mut self) -> anyhow::Result<()> {
+        self.end_tuple_element()
+    }
+}
+
+impl<H: BlockSizedHasher> DynHasher for GenericHmacHasher<H> {
+    fn algorithm_name(&self) -> &'static str {
+        Hmac::<H>::algorithm_name()
+    }
+
+    fn write(&mut self, data: &[u8]) {
+        self.write(data);
+    }
+
+    fn finalize_and_reset(&mut self) -> Vec<u8> {
+        self.finalize_and_reset()
+    }
+
+    fn output_size(&self) -> NonZeroUsize {
+        self.output_size()
+    }
 }
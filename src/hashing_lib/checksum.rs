@@ -0,0 +1,355 @@
+//! Non-cryptographic checksums: CRCs of any width up to 64 bits, Adler-32,
+//! and the CRC of the POSIX `cksum` program. They detect accidental corruption only.
+//! The checksums are written as big-endian integers, the way they're usually printed.
+
+use std::num::NonZeroUsize;
+
+use super::{
+    internal::{typenum, GenericArray},
+    sized_hasher::SizedHasher,
+    unsized_hasher::UnsizedHasher,
+};
+
+pub const MAX_CRC_WIDTH: u32 = 64;
+
+/// The parameters of a CRC, with the names of the Rocksoft model that catalogues of CRCs use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrcParameters {
+    pub width: u32,
+    pub polynomial: u64,
+    pub init: u64,
+    pub reflect_in: bool,
+    pub reflect_out: bool,
+    pub xorout: u64,
+}
+
+pub const CRC_32_ISO_HDLC: CrcParameters = CrcParameters {
+    width: 32,
+    polynomial: 0x04C11DB7,
+    init: 0xFFFFFFFF,
+    reflect_in: true,
+    reflect_out: true,
+    xorout: 0xFFFFFFFF,
+};
+
+pub const CRC_32_ISCSI: CrcParameters = CrcParameters {
+    width: 32,
+    polynomial: 0x1EDC6F41,
+    init: 0xFFFFFFFF,
+    reflect_in: true,
+    reflect_out: true,
+    xorout: 0xFFFFFFFF,
+};
+
+pub const CRC_32_CKSUM: CrcParameters = CrcParameters {
+    width: 32,
+    polynomial: 0x04C11DB7,
+    init: 0,
+    reflect_in: false,
+    reflect_out: false,
+    xorout: 0xFFFFFFFF,
+};
+
+pub const CRC_64_ECMA_182: CrcParameters = CrcParameters {
+    width: 64,
+    polynomial: 0x42F0E1EBA9EA3693,
+    init: 0,
+    reflect_in: false,
+    reflect_out: false,
+    xorout: 0,
+};
+
+pub const CRC_64_NVME: CrcParameters = CrcParameters {
+    width: 64,
+    polynomial: 0xAD93D23594C93659,
+    init: 0xFFFFFFFFFFFFFFFF,
+    reflect_in: true,
+    reflect_out: true,
+    xorout: 0xFFFFFFFFFFFFFFFF,
+};
+
+pub const CRC_64_XZ: CrcParameters = CrcParameters {
+    width: 64,
+    polynomial: 0x42F0E1EBA9EA3693,
+    init: 0xFFFFFFFFFFFFFFFF,
+    reflect_in: true,
+    reflect_out: true,
+    xorout: 0xFFFFFFFFFFFFFFFF,
+};
+
+impl CrcParameters {
+    fn mask(&self) -> u64 {
+        u64::MAX >> (MAX_CRC_WIDTH - self.width)
+    }
+
+    /// The size of the checksum in bytes
+    pub fn output_size(&self) -> NonZeroUsize {
+        NonZeroUsize::new(self.width.div_ceil(8) as usize).expect("The width is never zero")
+    }
+}
+
+/// Reverses the order of the lowest `width` bits of the value
+fn reflect(value: u64, width: u32) -> u64 {
+    value.reverse_bits() >> (MAX_CRC_WIDTH - width)
+}
+
+/// A CRC that is calculated a byte at a time with a table. If the input is reflected, the register
+/// holds the CRC in its lowest bits, reflected. Otherwise, the register holds the CRC in its highest bits,
+/// so that CRCs narrower than a byte work the same way as wider CRCs.
+#[derive(Clone)]
+pub struct Crc {
+    parameters: CrcParameters,
+    table: Box<[u64; 256]>,
+    register: u64,
+}
+
+impl Crc {
+    /// The width must be from 1 to 64, and the other parameters must fit in it
+    pub fn with_parameters(parameters: CrcParameters) -> Self {
+        assert!((1..=MAX_CRC_WIDTH).contains(&parameters.width));
+
+        let mut table = Box::new([0; 256]);
+        for (byte, entry) in table.iter_mut().enumerate() {
+            *entry = if parameters.reflect_in {
+                let polynomial = reflect(parameters.polynomial, parameters.width);
+                (0..8).fold(byte as u64, |crc, _| {
+                    if crc & 1 == 1 {
+                        (crc >> 1) ^ polynomial
+                    } else {
+                        crc >> 1
+                    }
+                })
+            } else {
+                let polynomial = parameters.polynomial << (MAX_CRC_WIDTH - parameters.width);
+                (0..8).fold((byte as u64) << 56, |crc, _| {
+                    if crc >> 63 == 1 {
+                        (crc << 1) ^ polynomial
+                    } else {
+                        crc << 1
+                    }
+                })
+            };
+        }
+
+        Self {
+            parameters,
+            table,
+            register: Self::initial_register(&parameters),
+        }
+    }
+
+    fn initial_register(parameters: &CrcParameters) -> u64 {
+        if parameters.reflect_in {
+            reflect(parameters.init, parameters.width)
+        } else {
+            parameters.init << (MAX_CRC_WIDTH - parameters.width)
+        }
+    }
+
+    pub fn write(&mut self, data: &[u8]) {
+        if self.parameters.reflect_in {
+            for byte in data {
+                let index = (self.register ^ *byte as u64) as u8;
+                self.register = self.table[index as usize] ^ (self.register >> 8);
+            }
+        } else {
+            for byte in data {
+                let index = ((self.register >> 56) ^ *byte as u64) as u8;
+                self.register = self.table[index as usize] ^ (self.register << 8);
+            }
+        }
+    }
+
+    /// The checksum as an integer
+    pub fn finalize_and_reset(&mut self) -> u64 {
+        let parameters = &self.parameters;
+
+        let mut result = if parameters.reflect_in {
+            self.register
+        } else {
+            self.register >> (MAX_CRC_WIDTH - parameters.width)
+        };
+        if parameters.reflect_in != parameters.reflect_out {
+            result = reflect(result, parameters.width);
+        }
+        result = (result ^ parameters.xorout) & parameters.mask();
+
+        self.register = Self::initial_register(parameters);
+        result
+    }
+
+    pub fn parameters(&self) -> &CrcParameters {
+        &self.parameters
+    }
+}
+
+/// The lowest `output_size` bytes of the value, in big-endian order
+fn big_endian_bytes(value: u64, output_size: usize) -> Vec<u8> {
+    value.to_be_bytes()[8 - output_size..].to_vec()
+}
+
+/// A CRC with parameters that are chosen at runtime
+#[derive(Clone)]
+pub struct CustomCrc(Crc);
+
+impl CustomCrc {
+    pub fn with_parameters(parameters: CrcParameters) -> Self {
+        Self(Crc::with_parameters(parameters))
+    }
+}
+
+impl UnsizedHasher for CustomCrc {
+    fn algorithm_name() -> &'static str {
+        "Crc"
+    }
+
+    /// A CRC-32 with the parameters of zlib, since a CRC has no output size of its own
+    fn new(_output_size: NonZeroUsize) -> Self {
+        Self::with_parameters(CRC_32_ISO_HDLC)
+    }
+
+    fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+        self.0.write(in_bytes.as_ref());
+        self
+    }
+
+    fn finalize_and_reset(&mut self) -> Box<[u8]> {
+        let output_size = self.output_size().get();
+        big_endian_bytes(self.0.finalize_and_reset(), output_size).into_boxed_slice()
+    }
+
+    fn output_size(&self) -> NonZeroUsize {
+        self.0.parameters().output_size()
+    }
+}
+
+macro_rules! impl_crc_hasher {
+    ($hasher:ident, $parameters:expr, $output_size:ty, $algo_name:literal) => {
+        #[derive(Clone)]
+        pub struct $hasher(Crc);
+
+        impl SizedHasher for $hasher {
+            type OutputSize = $output_size;
+
+            fn algorithm_name() -> &'static str {
+                $algo_name
+            }
+
+            fn new() -> Self {
+                Self(Crc::with_parameters($parameters))
+            }
+
+            fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+                self.0.write(in_bytes.as_ref());
+                self
+            }
+
+            fn reset(&mut self) {
+                self.0.finalize_and_reset();
+            }
+
+            fn finalize_and_reset(&mut self) -> GenericArray<u8, Self::OutputSize> {
+                let bytes = self.0.finalize_and_reset().to_be_bytes();
+                GenericArray::clone_from_slice(
+                    &bytes[bytes.len() - <$output_size as typenum::Unsigned>::USIZE..],
+                )
+            }
+        }
+    };
+}
+
+impl_crc_hasher!(Crc32, CRC_32_ISO_HDLC, typenum::U4, "Crc32");
+impl_crc_hasher!(Crc32c, CRC_32_ISCSI, typenum::U4, "Crc32c");
+impl_crc_hasher!(Crc64, CRC_64_XZ, typenum::U8, "Crc64");
+impl_crc_hasher!(Crc64Ecma, CRC_64_ECMA_182, typenum::U8, "Crc64Ecma");
+impl_crc_hasher!(Crc64Nvme, CRC_64_NVME, typenum::U8, "Crc64Nvme");
+
+/// The CRC of the POSIX `cksum` program: a CRC-32 of the data, followed by its length
+/// in as few bytes as possible, least significant byte first. Only the CRC is the same as in `cksum`,
+/// which prints it in decimal, followed by the length.
+#[derive(Clone)]
+pub struct Cksum {
+    crc: Crc,
+    length: u64,
+}
+
+impl SizedHasher for Cksum {
+    type OutputSize = typenum::U4;
+
+    fn algorithm_name() -> &'static str {
+        "Cksum"
+    }
+
+    fn new() -> Self {
+        Self {
+            crc: Crc::with_parameters(CRC_32_CKSUM),
+            length: 0,
+        }
+    }
+
+    fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+        let in_bytes = in_bytes.as_ref();
+        self.crc.write(in_bytes);
+        self.length += in_bytes.len() as u64;
+        self
+    }
+
+    fn reset(&mut self) {
+        self.crc.finalize_and_reset();
+        self.length = 0;
+    }
+
+    fn finalize_and_reset(&mut self) -> GenericArray<u8, Self::OutputSize> {
+        let length_bytes = self.length.to_le_bytes();
+        let length_size = length_bytes.len() - self.length.leading_zeros() as usize / 8;
+        self.crc.write(&length_bytes[..length_size]);
+
+        let result = (self.crc.finalize_and_reset() as u32).to_be_bytes();
+        self.length = 0;
+        result.into()
+    }
+}
+
+const ADLER_MODULUS: u32 = 65521;
+/// The most bytes that can be summed before the sums must be reduced, so they don't overflow
+const ADLER_MAX_BYTES_BEFORE_REDUCING: usize = 5552;
+
+#[derive(Clone)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl SizedHasher for Adler32 {
+    type OutputSize = typenum::U4;
+
+    fn algorithm_name() -> &'static str {
+        "Adler32"
+    }
+
+    fn new() -> Self {
+        Self { a: 1, b: 0 }
+    }
+
+    fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+        for chunk in in_bytes.as_ref().chunks(ADLER_MAX_BYTES_BEFORE_REDUCING) {
+            for byte in chunk {
+                self.a += *byte as u32;
+                self.b += self.a;
+            }
+            self.a %= ADLER_MODULUS;
+            self.b %= ADLER_MODULUS;
+        }
+        self
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn finalize_and_reset(&mut self) -> GenericArray<u8, Self::OutputSize> {
+        let result = ((self.b << 16) | self.a).to_be_bytes();
+        self.reset();
+        result.into()
+    }
+}
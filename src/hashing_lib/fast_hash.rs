@@ -0,0 +1,568 @@
+//! Fast non-cryptographic hashes, as used by hash maps and content caches: xxHash, SipHash and wyhash.
+//! The results are written as big-endian integers, the way `xxhsum` prints them,
+//! and the way they're printed in hex by programs that use them.
+
+use std::{hash::Hasher, num::NonZeroUsize};
+
+use siphasher::sip128::Hasher128;
+use wyhash_final4::{
+    generics::{StreamedWyHasher, WyHashVariant, WyHasher},
+    WyHash64,
+};
+use xxhash_rust::{xxh3, xxh32, xxh64};
+
+use super::{
+    internal::{typenum, GenericArray},
+    sized_hasher::SizedHasher,
+    unsized_hasher::UnsizedHasher,
+};
+
+/// The smallest custom secret of XXH3, the `XXH3_SECRET_SIZE_MIN` of the reference implementation
+pub const XXH3_SECRET_SIZE_MIN: usize = 136;
+pub const SIPHASH_KEY_SIZE: usize = 16;
+
+/// A hasher with a seed, which is zero by default
+pub trait SeededHasher: SizedHasher {
+    type Seed: TryFrom<u64> + Default;
+
+    fn with_seed(seed: Self::Seed) -> Self;
+}
+
+#[derive(Clone)]
+pub struct Xxh32 {
+    hasher: xxh32::Xxh32,
+    seed: u32,
+}
+
+impl SizedHasher for Xxh32 {
+    type OutputSize = typenum::U4;
+
+    fn algorithm_name() -> &'static str {
+        "Xxh32"
+    }
+
+    fn new() -> Self {
+        Self::with_seed(0)
+    }
+
+    fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+        self.hasher.update(in_bytes.as_ref());
+        self
+    }
+
+    fn reset(&mut self) {
+        self.hasher.reset(self.seed);
+    }
+
+    fn finalize_and_reset(&mut self) -> GenericArray<u8, Self::OutputSize> {
+        let result = self.hasher.digest().to_be_bytes();
+        self.reset();
+        result.into()
+    }
+}
+
+impl SeededHasher for Xxh32 {
+    type Seed = u32;
+
+    fn with_seed(seed: u32) -> Self {
+        Self {
+            hasher: xxh32::Xxh32::new(seed),
+            seed,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Xxh64 {
+    hasher: xxh64::Xxh64,
+    seed: u64,
+}
+
+impl SizedHasher for Xxh64 {
+    type OutputSize = typenum::U8;
+
+    fn algorithm_name() -> &'static str {
+        "Xxh64"
+    }
+
+    fn new() -> Self {
+        Self::with_seed(0)
+    }
+
+    fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+        self.hasher.update(in_bytes.as_ref());
+        self
+    }
+
+    fn reset(&mut self) {
+        self.hasher.reset(self.seed);
+    }
+
+    fn finalize_and_reset(&mut self) -> GenericArray<u8, Self::OutputSize> {
+        let result = self.hasher.digest().to_be_bytes();
+        self.reset();
+        result.into()
+    }
+}
+
+impl SeededHasher for Xxh64 {
+    type Seed = u64;
+
+    fn with_seed(seed: u64) -> Self {
+        Self {
+            hasher: xxh64::Xxh64::new(seed),
+            seed,
+        }
+    }
+}
+
+/// XXH3, which can use either a seed or a custom secret
+pub trait Xxh3Hasher: SeededHasher<Seed = u64> {
+    /// The secret must have at least `XXH3_SECRET_SIZE_MIN` bytes
+    fn with_secret(secret: &[u8]) -> Self;
+}
+
+const XXH3_STRIPE_SIZE: usize = 64;
+/// The secret is read from 8 bytes further for every stripe of a block
+const XXH3_SECRET_CONSUME_RATE: usize = 8;
+const XXH3_SECRET_MERGEACCS_START: usize = 11;
+const XXH3_SECRET_LASTACC_START: usize = 7;
+/// Longer inputs are hashed in stripes and blocks, shorter ones in other ways
+const XXH3_MID_SIZE_MAX: usize = 240;
+/// The input is buffered until this many bytes, which must be more than `XXH3_MID_SIZE_MAX`
+const XXH3_BUFFER_SIZE: usize = 16 * XXH3_STRIPE_SIZE;
+
+const XXH_PRIME32_1: u64 = 0x9E3779B1;
+const XXH_PRIME32_2: u64 = 0x85EBCA77;
+const XXH_PRIME32_3: u64 = 0xC2B2AE3D;
+const XXH_PRIME64_1: u64 = 0x9E3779B185EBCA87;
+const XXH_PRIME64_2: u64 = 0xC2B2AE3D27D4EB4F;
+const XXH_PRIME64_3: u64 = 0x165667B19E3779F9;
+const XXH_PRIME64_4: u64 = 0x85EBCA77C2B2AE63;
+const XXH_PRIME64_5: u64 = 0x27D4EB2F165667C5;
+
+const XXH3_INITIAL_ACCUMULATORS: [u64; 8] = [
+    XXH_PRIME32_3,
+    XXH_PRIME64_1,
+    XXH_PRIME64_2,
+    XXH_PRIME64_3,
+    XXH_PRIME64_4,
+    XXH_PRIME32_2,
+    XXH_PRIME64_5,
+    XXH_PRIME32_1,
+];
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().expect("The slice has 8 bytes"))
+}
+
+/// Streaming XXH3 with a custom secret of any size from `XXH3_SECRET_SIZE_MIN` bytes, since `xxhash_rust`
+/// streams only secrets of the size of the default secret. Inputs of up to `XXH3_MID_SIZE_MAX` bytes are
+/// hashed by the functions of `xxhash_rust`, and longer inputs by accumulating their stripes, like the
+/// reference implementation does.
+#[derive(Clone)]
+struct Xxh3WithSecret {
+    secret: Box<[u8]>,
+    accumulators: [u64; 8],
+    /// The stripes that were accumulated in the current block
+    block_stripes: usize,
+    /// The input that wasn't accumulated yet. The whole input stays here until it's longer than
+    /// `XXH3_BUFFER_SIZE`, and then at least the last stripe, which is accumulated differently.
+    buffer: Vec<u8>,
+    total_len: u64,
+}
+
+impl Xxh3WithSecret {
+    fn new(secret: &[u8]) -> Self {
+        assert!(secret.len() >= XXH3_SECRET_SIZE_MIN);
+
+        Self {
+            secret: secret.into(),
+            accumulators: XXH3_INITIAL_ACCUMULATORS,
+            block_stripes: 0,
+            buffer: Vec::with_capacity(XXH3_BUFFER_SIZE),
+            total_len: 0,
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        self.total_len += data.len() as u64;
+
+        while !data.is_empty() {
+            let to_take = (XXH3_BUFFER_SIZE - self.buffer.len()).min(data.len());
+            self.buffer.extend_from_slice(&data[..to_take]);
+            data = &data[to_take..];
+
+            if self.buffer.len() == XXH3_BUFFER_SIZE {
+                // Keeps from 64 to 127 bytes, so the last stripe is still there when finalizing
+                let consumed =
+                    (self.buffer.len() - XXH3_STRIPE_SIZE) / XXH3_STRIPE_SIZE * XXH3_STRIPE_SIZE;
+                consume_stripes(
+                    &mut self.accumulators,
+                    &mut self.block_stripes,
+                    &self.secret,
+                    &self.buffer[..consumed],
+                );
+                self.buffer.drain(..consumed);
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.accumulators = XXH3_INITIAL_ACCUMULATORS;
+        self.block_stripes = 0;
+        self.buffer.clear();
+        self.total_len = 0;
+    }
+
+    /// The accumulators after the whole input, when it's longer than `XXH3_MID_SIZE_MAX`
+    fn final_accumulators(&self) -> [u64; 8] {
+        let mut accumulators = self.accumulators;
+        let mut block_stripes = self.block_stripes;
+
+        // All the stripes before the last byte, and then the last 64 bytes, which may overlap them
+        let len = self.buffer.len();
+        consume_stripes(
+            &mut accumulators,
+            &mut block_stripes,
+            &self.secret,
+            &self.buffer[..(len - 1) / XXH3_STRIPE_SIZE * XXH3_STRIPE_SIZE],
+        );
+        accumulate_stripe(
+            &mut accumulators,
+            &self.buffer[len - XXH3_STRIPE_SIZE..],
+            &self.secret[self.secret.len() - XXH3_STRIPE_SIZE - XXH3_SECRET_LASTACC_START..],
+        );
+
+        accumulators
+    }
+
+    fn digest(&self) -> u64 {
+        if self.total_len <= XXH3_MID_SIZE_MAX as u64 {
+            return xxh3::xxh3_64_with_secret(&self.buffer, &self.secret);
+        }
+
+        merge_accumulators(
+            &self.final_accumulators(),
+            &self.secret[XXH3_SECRET_MERGEACCS_START..],
+            self.total_len.wrapping_mul(XXH_PRIME64_1),
+        )
+    }
+
+    fn digest128(&self) -> u128 {
+        if self.total_len <= XXH3_MID_SIZE_MAX as u64 {
+            return xxh3::xxh3_128_with_secret(&self.buffer, &self.secret);
+        }
+
+        let accumulators = self.final_accumulators();
+        let low = merge_accumulators(
+            &accumulators,
+            &self.secret[XXH3_SECRET_MERGEACCS_START..],
+            self.total_len.wrapping_mul(XXH_PRIME64_1),
+        );
+        let high = merge_accumulators(
+            &accumulators,
+            &self.secret[self.secret.len() - XXH3_STRIPE_SIZE - XXH3_SECRET_MERGEACCS_START..],
+            !self.total_len.wrapping_mul(XXH_PRIME64_2),
+        );
+
+        ((high as u128) << 64) | low as u128
+    }
+}
+
+fn accumulate_stripe(accumulators: &mut [u64; 8], stripe: &[u8], secret: &[u8]) {
+    for i in 0..accumulators.len() {
+        let data = read_u64(&stripe[8 * i..]);
+        let key = data ^ read_u64(&secret[8 * i..]);
+        accumulators[i ^ 1] = accumulators[i ^ 1].wrapping_add(data);
+        accumulators[i] = accumulators[i].wrapping_add((key & 0xFFFFFFFF) * (key >> 32));
+    }
+}
+
+fn scramble_accumulators(accumulators: &mut [u64; 8], secret: &[u8]) {
+    for (i, accumulator) in accumulators.iter_mut().enumerate() {
+        let mut value = *accumulator;
+        value ^= value >> 47;
+        value ^= read_u64(&secret[8 * i..]);
+        *accumulator = value.wrapping_mul(XXH_PRIME32_1);
+    }
+}
+
+/// Accumulates the stripes, and scrambles the accumulators after every block of stripes
+fn consume_stripes(
+    accumulators: &mut [u64; 8],
+    block_stripes: &mut usize,
+    secret: &[u8],
+    stripes: &[u8],
+) {
+    let stripes_per_block = (secret.len() - XXH3_STRIPE_SIZE) / XXH3_SECRET_CONSUME_RATE;
+
+    for stripe in stripes.chunks_exact(XXH3_STRIPE_SIZE) {
+        accumulate_stripe(
+            accumulators,
+            stripe,
+            &secret[*block_stripes * XXH3_SECRET_CONSUME_RATE..],
+        );
+        *block_stripes += 1;
+
+        if *block_stripes == stripes_per_block {
+            scramble_accumulators(accumulators, &secret[secret.len() - XXH3_STRIPE_SIZE..]);
+            *block_stripes = 0;
+        }
+    }
+}
+
+fn merge_accumulators(accumulators: &[u64; 8], secret: &[u8], start: u64) -> u64 {
+    let mut result = start;
+    for i in 0..accumulators.len() / 2 {
+        let product = (accumulators[2 * i] ^ read_u64(&secret[16 * i..])) as u128
+            * (accumulators[2 * i + 1] ^ read_u64(&secret[16 * i + 8..])) as u128;
+        result = result.wrapping_add(product as u64 ^ (product >> 64) as u64);
+    }
+
+    result ^= result >> 37;
+    result = result.wrapping_mul(0x165667919E3779F9);
+    result ^ (result >> 32)
+}
+
+/// XXH3 with a seed, or with a secret of the default size, is streamed by `xxhash_rust`
+#[derive(Clone)]
+enum Xxh3State {
+    Library(Box<xxh3::Xxh3>),
+    CustomSecret(Box<Xxh3WithSecret>),
+}
+
+impl Xxh3State {
+    fn update(&mut self, in_bytes: &[u8]) {
+        match self {
+            Xxh3State::Library(h) => h.update(in_bytes),
+            Xxh3State::CustomSecret(h) => h.update(in_bytes),
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            Xxh3State::Library(h) => h.reset(),
+            Xxh3State::CustomSecret(h) => h.reset(),
+        }
+    }
+
+    fn digest(&self) -> u64 {
+        match self {
+            Xxh3State::Library(h) => h.digest(),
+            Xxh3State::CustomSecret(h) => h.digest(),
+        }
+    }
+
+    fn digest128(&self) -> u128 {
+        match self {
+            Xxh3State::Library(h) => h.digest128(),
+            Xxh3State::CustomSecret(h) => h.digest128(),
+        }
+    }
+}
+
+macro_rules! impl_xxh3_hasher {
+    ($hasher:ident, $digest:ident, $output_size:ty, $algo_name:literal) => {
+        #[derive(Clone)]
+        pub struct $hasher(Xxh3State);
+
+        impl SizedHasher for $hasher {
+            type OutputSize = $output_size;
+
+            fn algorithm_name() -> &'static str {
+                $algo_name
+            }
+
+            fn new() -> Self {
+                Self(Xxh3State::Library(Box::new(xxh3::Xxh3::new())))
+            }
+
+            fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+                self.0.update(in_bytes.as_ref());
+                self
+            }
+
+            fn reset(&mut self) {
+                self.0.reset();
+            }
+
+            fn finalize_and_reset(&mut self) -> GenericArray<u8, Self::OutputSize> {
+                let result = self.0.$digest().to_be_bytes();
+                self.reset();
+                result.into()
+            }
+        }
+
+        impl SeededHasher for $hasher {
+            type Seed = u64;
+
+            fn with_seed(seed: u64) -> Self {
+                Self(Xxh3State::Library(Box::new(xxh3::Xxh3::with_seed(seed))))
+            }
+        }
+
+        impl Xxh3Hasher for $hasher {
+            fn with_secret(secret: &[u8]) -> Self {
+                match secret.try_into() {
+                    Ok(default_size_secret) => Self(Xxh3State::Library(Box::new(
+                        xxh3::Xxh3::with_secret(default_size_secret),
+                    ))),
+                    Err(_) => Self(Xxh3State::CustomSecret(Box::new(Xxh3WithSecret::new(
+                        secret,
+                    )))),
+                }
+            }
+        }
+    };
+}
+
+impl_xxh3_hasher!(Xxh3_64, digest, typenum::U8, "Xxh3-64");
+impl_xxh3_hasher!(Xxh3_128, digest128, typenum::U16, "Xxh3-128");
+
+/// The final version of wyhash (final4), with its default secret
+pub struct Wyhash {
+    initial: WyHasher<WyHash64>,
+    hasher: StreamedWyHasher<WyHash64>,
+}
+
+impl SizedHasher for Wyhash {
+    type OutputSize = typenum::U8;
+
+    fn algorithm_name() -> &'static str {
+        "Wyhash"
+    }
+
+    fn new() -> Self {
+        Self::with_seed(0)
+    }
+
+    fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+        self.hasher.write(in_bytes.as_ref());
+        self
+    }
+
+    fn reset(&mut self) {
+        self.hasher = self.initial.streamed();
+    }
+
+    fn finalize_and_reset(&mut self) -> GenericArray<u8, Self::OutputSize> {
+        let result = self.hasher.finish().to_be_bytes();
+        self.reset();
+        result.into()
+    }
+}
+
+impl SeededHasher for Wyhash {
+    type Seed = u64;
+
+    fn with_seed(seed: u64) -> Self {
+        let initial = WyHash64::with_seed(seed);
+        Self {
+            hasher: initial.streamed(),
+            initial,
+        }
+    }
+}
+
+/// SipHash with a 64-bit or a 128-bit output, which are computed differently
+#[derive(Clone, Copy)]
+enum SipHashState<H64: Hasher, H128: Hasher + Hasher128> {
+    Output64(H64),
+    Output128(H128),
+}
+
+impl<H64: Hasher, H128: Hasher + Hasher128> SipHashState<H64, H128> {
+    fn write(&mut self, in_bytes: &[u8]) {
+        match self {
+            SipHashState::Output64(h) => h.write(in_bytes),
+            SipHashState::Output128(h) => h.write(in_bytes),
+        }
+    }
+
+    fn finish(&self) -> Box<[u8]> {
+        match self {
+            SipHashState::Output64(h) => h.finish().to_be_bytes().into(),
+            SipHashState::Output128(h) => h.finish128().as_u128().to_be_bytes().into(),
+        }
+    }
+}
+
+/// SipHash can only output 8 or 16 bytes
+pub fn is_valid_siphash_output_size(output_size: NonZeroUsize) -> bool {
+    output_size.get() == 8 || output_size.get() == 16
+}
+
+pub trait SipHashHasher: UnsizedHasher {
+    /// The output size must be 8 or 16 bytes
+    fn with_key(key: &[u8; SIPHASH_KEY_SIZE], output_size: NonZeroUsize) -> Self;
+}
+
+macro_rules! impl_siphash_hasher {
+    ($hasher:ident, $sip64:ty, $sip128:ty, $algo_name:literal) => {
+        #[derive(Clone)]
+        pub struct $hasher {
+            initial: SipHashState<$sip64, $sip128>,
+            state: SipHashState<$sip64, $sip128>,
+        }
+
+        impl SipHashHasher for $hasher {
+            fn with_key(key: &[u8; SIPHASH_KEY_SIZE], output_size: NonZeroUsize) -> Self {
+                assert!(is_valid_siphash_output_size(output_size));
+
+                let initial = if output_size.get() == 8 {
+                    SipHashState::Output64(<$sip64>::new_with_key(key))
+                } else {
+                    SipHashState::Output128(<$sip128>::new_with_key(key))
+                };
+
+                Self {
+                    initial,
+                    state: initial,
+                }
+            }
+        }
+
+        impl UnsizedHasher for $hasher {
+            fn algorithm_name() -> &'static str {
+                $algo_name
+            }
+
+            fn new(output_size: NonZeroUsize) -> Self {
+                Self::with_key(&[0; SIPHASH_KEY_SIZE], output_size)
+            }
+
+            fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+                self.state.write(in_bytes.as_ref());
+                self
+            }
+
+            fn finalize_and_reset(&mut self) -> Box<[u8]> {
+                let result = self.state.finish();
+                self.state = self.initial;
+                result
+            }
+
+            fn output_size(&self) -> NonZeroUsize {
+                let output_size = match self.initial {
+                    SipHashState::Output64(_) => 8,
+                    SipHashState::Output128(_) => 16,
+                };
+                NonZeroUsize::new(output_size).expect("Output size is never zero")
+            }
+        }
+    };
+}
+
+impl_siphash_hasher!(
+    SipHash13,
+    siphasher::sip::SipHasher13,
+    siphasher::sip128::SipHasher13,
+    "SipHash-1-3"
+);
+impl_siphash_hasher!(
+    SipHash24,
+    siphasher::sip::SipHasher24,
+    siphasher::sip128::SipHasher24,
+    "SipHash-2-4"
+);
@@ -0,0 +1,59 @@
+//! HKDF, as defined in RFC 5869, over any hasher that can be used in HMAC
+
+use super::{
+    hmac::Hmac,
+    internal::{typenum::Unsigned, GenericArray},
+    sized_hasher::BlockSizedHasher,
+};
+
+/// The maximum number of blocks of output of the expand step, since the block counter is a single byte
+const MAX_EXPAND_BLOCKS: usize = 255;
+
+/// The maximum size of the output of the expand step with the hasher
+pub fn max_expand_output_size<H: BlockSizedHasher>() -> usize {
+    MAX_EXPAND_BLOCKS * H::OutputSize::USIZE
+}
+
+/// Extracts a pseudorandom key from the input keying material.
+/// An empty salt is the same as the default salt of zeros, since HMAC pads keys with zeros.
+pub fn extract<H: BlockSizedHasher>(
+    salt: &[u8],
+    input_key: &[u8],
+) -> GenericArray<u8, H::OutputSize> {
+    Hmac::<H>::new(salt).write(input_key).finalize_and_reset()
+}
+
+/// Expands the pseudorandom key to the output size, which must not exceed [`max_expand_output_size`]
+pub fn expand<H: BlockSizedHasher>(
+    pseudorandom_key: &[u8],
+    info: &[u8],
+    output_size: usize,
+) -> anyhow::Result<Vec<u8>> {
+    if output_size > max_expand_output_size::<H>() {
+        return Err(anyhow::anyhow!(
+            "HKDF with {} can expand to at most {} bytes, but {output_size} bytes were requested",
+            H::algorithm_name(),
+            max_expand_output_size::<H>()
+        ));
+    }
+
+    let mut hmac = Hmac::<H>::new(pseudorandom_key);
+    let mut result = Vec::with_capacity(output_size);
+    // Every block is the HMAC of the previous block, the info and the counter of the block
+    let mut block = Vec::new();
+    let mut counter: u8 = 1;
+
+    while result.len() < output_size {
+        block = hmac
+            .write(&block)
+            .write(info)
+            .write([counter])
+            .finalize_and_reset()
+            .to_vec();
+        result.extend_from_slice(&block);
+        counter = counter.wrapping_add(1);
+    }
+
+    result.truncate(output_size);
+    Ok(result)
+}
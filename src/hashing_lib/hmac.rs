@@ -0,0 +1,58 @@
+use super::{internal::GenericArray, sized_hasher::BlockSizedHasher};
+
+const INNER_PAD: u8 = 0x36;
+const OUTER_PAD: u8 = 0x5c;
+
+/// HMAC, as defined in RFC 2104, over any hasher with a known block size
+#[derive(Clone)]
+pub struct Hmac<H: BlockSizedHasher> {
+    hasher: H,
+    inner_key_pad: Vec<u8>,
+    outer_key_pad: Vec<u8>,
+}
+
+impl<H: BlockSizedHasher> Hmac<H> {
+    pub fn new(key: &[u8]) -> Self {
+        let mut hasher = H::new();
+
+        // Keys longer than the block size are hashed first, then all keys are padded with zeros to the block size
+        let mut block_key = if key.len() > H::block_size() {
+            hasher.write(key).finalize_and_reset().to_vec()
+        } else {
+            key.to_vec()
+        };
+        block_key.resize(H::block_size(), 0);
+
+        let inner_key_pad = block_key.iter().map(|b| b ^ INNER_PAD).collect::<Vec<_>>();
+        let outer_key_pad = block_key.iter().map(|b| b ^ OUTER_PAD).collect::<Vec<_>>();
+
+        hasher.write(&inner_key_pad);
+
+        Self {
+            hasher,
+            inner_key_pad,
+            outer_key_pad,
+        }
+    }
+
+    pub fn algorithm_name() -> &'static str {
+        H::hmac_algorithm_name()
+    }
+
+    pub fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+        self.hasher.write(in_bytes);
+        self
+    }
+
+    pub fn finalize_and_reset(&mut self) -> GenericArray<u8, H::OutputSize> {
+        let inner = self.hasher.finalize_and_reset();
+
+        self.hasher.write(&self.outer_key_pad);
+        self.hasher.write(inner);
+        let result = self.hasher.finalize_and_reset();
+
+        self.hasher.write(&self.inner_key_pad);
+
+        result
+    }
+}
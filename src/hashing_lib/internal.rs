@@ -1,6 +1,7 @@
 pub use blake2::digest::{
+    core_api::BlockSizeUser,
     generic_array::{sequence::Split, typenum, ArrayLength, GenericArray},
-    Digest, FixedOutputReset, OutputSizeUser, Reset,
+    Digest, ExtendableOutput, FixedOutputReset, OutputSizeUser, Reset, Update,
 };
 
 #[derive(Clone)]
@@ -25,3 +26,28 @@ impl<D: Digest + Reset + FixedOutputReset> InternalStreamHasher<D> {
         self.hasher.finalize_reset()
     }
 }
+
+/// An extendable output function. Resetting restores the initial state, so hashers that absorb
+/// parameters on construction, like cSHAKE, keep them.
+#[derive(Clone)]
+pub struct InternalXofHasher<X: ExtendableOutput + Update + Clone> {
+    initial: X,
+    hasher: X,
+}
+
+impl<X: ExtendableOutput + Update + Clone> InternalXofHasher<X> {
+    pub fn from_hasher(hasher: X) -> Self {
+        Self {
+            initial: hasher.clone(),
+            hasher,
+        }
+    }
+
+    pub fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) {
+        Update::update(&mut self.hasher, in_bytes.as_ref());
+    }
+
+    pub fn finalize_and_reset(&mut self, output_size: usize) -> Box<[u8]> {
+        std::mem::replace(&mut self.hasher, self.initial.clone()).finalize_boxed(output_size)
+    }
+}
@@ -0,0 +1,238 @@
+//! TurboSHAKE and the KangarooTwelve functions built on it, KT128 (K12) and KT256, as defined in RFC 9861.
+//!
+//! KangarooTwelve hashes `S = M || C || length_encode(|C|)`. If `S` fits in one chunk of 8192 bytes,
+//! the digest is TurboSHAKE of `S` with the domain separation byte 0x07. Otherwise, every chunk after the
+//! first is hashed separately with 0x0B into a chaining value, and the final node is
+//! `S_0 || 0x03 || 0^7 || CV_1 || ... || CV_n || length_encode(n) || 0xFF 0xFF`, hashed with 0x06.
+
+use std::num::NonZeroUsize;
+
+use super::{
+    internal::{ExtendableOutput, InternalXofHasher, Update},
+    unsized_hasher::UnsizedHasher,
+};
+
+pub const DEFAULT_TURBO_SHAKE_DOMAIN_SEPARATION: u8 = 0x1F;
+
+const CHUNK_SIZE: usize = 8192;
+const SINGLE_NODE_DOMAIN_SEPARATION: u8 = 0x07;
+const FINAL_NODE_DOMAIN_SEPARATION: u8 = 0x06;
+const LEAF_DOMAIN_SEPARATION: u8 = 0x0B;
+const FINAL_NODE_CHUNK_SUFFIX: [u8; 8] = [0x03, 0, 0, 0, 0, 0, 0, 0];
+const FINAL_NODE_SUFFIX: [u8; 2] = [0xFF, 0xFF];
+
+/// The security strength of the functions, which decides the TurboSHAKE that is used
+pub trait TurboShakeVariant {
+    type TurboShake: ExtendableOutput + Update + Clone;
+
+    /// The size of the chaining value of every leaf, which is twice the security strength
+    const CHAINING_VALUE_SIZE: usize;
+    const DEFAULT_OUTPUT_SIZE: usize;
+
+    const TURBO_SHAKE_NAME: &'static str;
+    const KANGAROO_TWELVE_NAME: &'static str;
+
+    /// The domain separation byte must be in the range 0x01..=0x7F
+    fn turbo_shake(domain_separation: u8) -> Self::TurboShake;
+}
+
+macro_rules! impl_turbo_shake_variant {
+    ($variant:ident, $xof:ty, $core:ty, $size:expr, $turbo_shake_name:expr, $kangaroo_twelve_name:expr) => {
+        #[derive(Clone)]
+        pub struct $variant;
+
+        impl TurboShakeVariant for $variant {
+            type TurboShake = $xof;
+
+            const CHAINING_VALUE_SIZE: usize = $size;
+            const DEFAULT_OUTPUT_SIZE: usize = $size;
+
+            const TURBO_SHAKE_NAME: &'static str = $turbo_shake_name;
+            const KANGAROO_TWELVE_NAME: &'static str = $kangaroo_twelve_name;
+
+            fn turbo_shake(domain_separation: u8) -> Self::TurboShake {
+                <$xof>::from_core(<$core>::new(domain_separation))
+            }
+        }
+    };
+}
+
+impl_turbo_shake_variant!(
+    Kt128,
+    sha3::TurboShake128,
+    sha3::TurboShake128Core,
+    32,
+    "TurboShake128",
+    "K12"
+);
+impl_turbo_shake_variant!(
+    Kt256,
+    sha3::TurboShake256,
+    sha3::TurboShake256Core,
+    64,
+    "TurboShake256",
+    "Kt256"
+);
+
+#[derive(Clone)]
+pub struct TurboShake<V: TurboShakeVariant> {
+    hasher: InternalXofHasher<V::TurboShake>,
+    output_size: NonZeroUsize,
+}
+
+impl<V: TurboShakeVariant> TurboShake<V> {
+    /// The domain separation byte must be in the range 0x01..=0x7F
+    pub fn with_domain_separation(domain_separation: u8, output_size: NonZeroUsize) -> Self {
+        Self {
+            hasher: InternalXofHasher::from_hasher(V::turbo_shake(domain_separation)),
+            output_size,
+        }
+    }
+}
+
+impl<V: TurboShakeVariant> UnsizedHasher for TurboShake<V> {
+    fn algorithm_name() -> &'static str {
+        V::TURBO_SHAKE_NAME
+    }
+
+    fn new(output_size: NonZeroUsize) -> Self {
+        Self::with_domain_separation(DEFAULT_TURBO_SHAKE_DOMAIN_SEPARATION, output_size)
+    }
+
+    fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+        self.hasher.write(in_bytes);
+        self
+    }
+
+    fn finalize_and_reset(&mut self) -> Box<[u8]> {
+        self.hasher.finalize_and_reset(self.output_size.get())
+    }
+
+    fn output_size(&self) -> NonZeroUsize {
+        self.output_size
+    }
+}
+
+/// The big-endian bytes of the value without leading zeros, followed by their count
+fn length_encode(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let skip = value.leading_zeros() as usize / 8;
+    let mut result = bytes[skip..].to_vec();
+    result.push((bytes.len() - skip) as u8);
+    result
+}
+
+#[derive(Clone)]
+pub struct KangarooTwelve<V: TurboShakeVariant> {
+    customization: Vec<u8>,
+    // The first chunk is kept until it's known whether there's more than one chunk,
+    // since that decides the domain separation of the node that it's hashed in
+    first_chunk: Vec<u8>,
+    final_node: Option<V::TurboShake>,
+    leaf: V::TurboShake,
+    leaf_size: usize,
+    leaf_count: u64,
+    output_size: NonZeroUsize,
+}
+
+impl<V: TurboShakeVariant> KangarooTwelve<V> {
+    pub fn with_customization(customization: &[u8], output_size: NonZeroUsize) -> Self {
+        Self {
+            customization: customization.to_vec(),
+            first_chunk: Vec::with_capacity(CHUNK_SIZE),
+            final_node: None,
+            leaf: V::turbo_shake(LEAF_DOMAIN_SEPARATION),
+            leaf_size: 0,
+            leaf_count: 0,
+            output_size,
+        }
+    }
+
+    fn absorb(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.final_node.is_none() {
+                let to_take = (CHUNK_SIZE - self.first_chunk.len()).min(data.len());
+                self.first_chunk.extend_from_slice(&data[..to_take]);
+                data = &data[to_take..];
+
+                if !data.is_empty() {
+                    // There's a second chunk, so the first chunk goes to the final node of a tree
+                    let mut final_node = V::turbo_shake(FINAL_NODE_DOMAIN_SEPARATION);
+                    final_node.update(&self.first_chunk);
+                    final_node.update(&FINAL_NODE_CHUNK_SUFFIX);
+                    self.first_chunk.clear();
+                    self.final_node = Some(final_node);
+                }
+                continue;
+            }
+
+            let to_take = (CHUNK_SIZE - self.leaf_size).min(data.len());
+            self.leaf.update(&data[..to_take]);
+            self.leaf_size += to_take;
+            data = &data[to_take..];
+
+            if self.leaf_size == CHUNK_SIZE {
+                self.end_leaf();
+            }
+        }
+    }
+
+    fn end_leaf(&mut self) {
+        let leaf = std::mem::replace(&mut self.leaf, V::turbo_shake(LEAF_DOMAIN_SEPARATION));
+        let chaining_value = leaf.finalize_boxed(V::CHAINING_VALUE_SIZE);
+        self.final_node
+            .as_mut()
+            .expect("Leaves exist only in a tree, which has a final node")
+            .update(&chaining_value);
+        self.leaf_size = 0;
+        self.leaf_count += 1;
+    }
+}
+
+impl<V: TurboShakeVariant> UnsizedHasher for KangarooTwelve<V> {
+    fn algorithm_name() -> &'static str {
+        V::KANGAROO_TWELVE_NAME
+    }
+
+    fn new(output_size: NonZeroUsize) -> Self {
+        Self::with_customization(&[], output_size)
+    }
+
+    fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+        self.absorb(in_bytes.as_ref());
+        self
+    }
+
+    fn finalize_and_reset(&mut self) -> Box<[u8]> {
+        let customization = std::mem::take(&mut self.customization);
+        self.absorb(&customization);
+        self.absorb(&length_encode(customization.len() as u64));
+        self.customization = customization;
+
+        if self.final_node.is_some() && self.leaf_size > 0 {
+            self.end_leaf();
+        }
+
+        let result = match self.final_node.take() {
+            None => {
+                let mut node = V::turbo_shake(SINGLE_NODE_DOMAIN_SEPARATION);
+                node.update(&self.first_chunk);
+                node.finalize_boxed(self.output_size.get())
+            }
+            Some(mut final_node) => {
+                final_node.update(&length_encode(self.leaf_count));
+                final_node.update(&FINAL_NODE_SUFFIX);
+                final_node.finalize_boxed(self.output_size.get())
+            }
+        };
+
+        self.first_chunk.clear();
+        self.leaf_count = 0;
+
+        result
+    }
+
+    fn output_size(&self) -> NonZeroUsize {
+        self.output_size
+    }
+}
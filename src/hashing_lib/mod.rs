@@ -1,4 +1,11 @@
 mod internal;
 
+pub mod checksum;
+pub mod fast_hash;
+pub mod hkdf;
+pub mod hmac;
+pub mod kangaroo_twelve;
 pub mod sized_hasher;
+pub mod skein;
+pub mod sp800_185;
 pub mod unsized_hasher;
@@ -1,4 +1,7 @@
-use super::internal::{typenum, ArrayLength, GenericArray, InternalStreamHasher, Split};
+use super::internal::{
+    typenum, typenum::Unsigned, ArrayLength, BlockSizeUser, GenericArray, InternalStreamHasher,
+    Split,
+};
 
 pub trait SizedHasher: Sized {
     type OutputSize: ArrayLength<u8>;
@@ -14,8 +17,15 @@ pub trait SizedHasher: Sized {
     fn finalize_and_reset(&mut self) -> GenericArray<u8, Self::OutputSize>;
 }
 
+/// A hasher that processes its input in blocks of a fixed size, which is needed to use it in HMAC
+pub trait BlockSizedHasher: SizedHasher {
+    fn block_size() -> usize;
+
+    fn hmac_algorithm_name() -> &'static str;
+}
+
 macro_rules! impl_hasher_stream {
-    ($stream_type:ident, $stream_size:ty, $algo_name:expr) => {
+    ($stream_type:ident, $digest:ty, $stream_size:ty, $algo_name:literal) => {
         impl SizedHasher for $stream_type {
             type OutputSize = $stream_size;
 
@@ -42,13 +52,25 @@ macro_rules! impl_hasher_stream {
                 self.0.reset()
             }
         }
+
+        impl BlockSizedHasher for $stream_type {
+            fn block_size() -> usize {
+                <<$digest as BlockSizeUser>::BlockSize as Unsigned>::USIZE
+            }
+
+            fn hmac_algorithm_name() -> &'static str {
+                concat!("Hmac-", $algo_name)
+            }
+        }
     };
 }
 
+/// BLAKE2b with its full output size and no parameters, which is the hasher of HMAC-BLAKE2b
 #[derive(Clone)]
-pub struct Blake2b(InternalStreamHasher<blake2::Blake2b<typenum::U64>>);
+pub struct Blake2b512(InternalStreamHasher<blake2::Blake2b512>);
+/// BLAKE2s with its full output size and no parameters, which is the hasher of HMAC-BLAKE2s
 #[derive(Clone)]
-pub struct Blake2s(InternalStreamHasher<blake2::Blake2s<typenum::U32>>);
+pub struct Blake2s256(InternalStreamHasher<blake2::Blake2s256>);
 #[derive(Clone)]
 pub struct Md5(InternalStreamHasher<md5::Md5>);
 #[derive(Clone)]
@@ -62,6 +84,10 @@ pub struct Sha384(InternalStreamHasher<sha2::Sha384>);
 #[derive(Clone)]
 pub struct Sha512(InternalStreamHasher<sha2::Sha512>);
 #[derive(Clone)]
+pub struct Sha512_224(InternalStreamHasher<sha2::Sha512_224>);
+#[derive(Clone)]
+pub struct Sha512_256(InternalStreamHasher<sha2::Sha512_256>);
+#[derive(Clone)]
 pub struct Sha3_224(InternalStreamHasher<sha3::Sha3_224>);
 #[derive(Clone)]
 pub struct Sha3_256(InternalStreamHasher<sha3::Sha3_256>);
@@ -69,51 +95,170 @@ pub struct Sha3_256(InternalStreamHasher<sha3::Sha3_256>);
 pub struct Sha3_384(InternalStreamHasher<sha3::Sha3_384>);
 #[derive(Clone)]
 pub struct Sha3_512(InternalStreamHasher<sha3::Sha3_512>);
+/// Keccak with its original padding, as Ethereum uses it, which gives different digests than SHA-3
+#[derive(Clone)]
+pub struct Keccak224(InternalStreamHasher<sha3::Keccak224>);
+#[derive(Clone)]
+pub struct Keccak256(InternalStreamHasher<sha3::Keccak256>);
+#[derive(Clone)]
+pub struct Keccak384(InternalStreamHasher<sha3::Keccak384>);
+#[derive(Clone)]
+pub struct Keccak512(InternalStreamHasher<sha3::Keccak512>);
+#[derive(Clone)]
+pub struct Ripemd160(InternalStreamHasher<ripemd::Ripemd160>);
+#[derive(Clone)]
+pub struct Whirlpool(InternalStreamHasher<whirlpool::Whirlpool>);
+#[derive(Clone)]
+pub struct Tiger(InternalStreamHasher<tiger::Tiger>);
+#[derive(Clone)]
+pub struct Tiger2(InternalStreamHasher<tiger::Tiger2>);
+#[derive(Clone)]
+pub struct Sm3(InternalStreamHasher<sm3::Sm3>);
+#[derive(Clone)]
+pub struct Streebog256(InternalStreamHasher<streebog::Streebog256>);
+#[derive(Clone)]
+pub struct Streebog512(InternalStreamHasher<streebog::Streebog512>);
+/// GOST R 34.11-94 with the test parameters of the standard
+#[derive(Clone)]
+pub struct Gost94(InternalStreamHasher<gost94::Gost94Test>);
+/// GOST R 34.11-94 with the parameters of CryptoPro (RFC 4357)
+#[derive(Clone)]
+pub struct Gost94CryptoPro(InternalStreamHasher<gost94::Gost94CryptoPro>);
+#[derive(Clone)]
+pub struct Groestl224(InternalStreamHasher<groestl::Groestl224>);
+#[derive(Clone)]
+pub struct Groestl256(InternalStreamHasher<groestl::Groestl256>);
+#[derive(Clone)]
+pub struct Groestl384(InternalStreamHasher<groestl::Groestl384>);
+#[derive(Clone)]
+pub struct Groestl512(InternalStreamHasher<groestl::Groestl512>);
+#[derive(Clone)]
+pub struct Jh224(InternalStreamHasher<jh::Jh224>);
+#[derive(Clone)]
+pub struct Jh256(InternalStreamHasher<jh::Jh256>);
+#[derive(Clone)]
+pub struct Jh384(InternalStreamHasher<jh::Jh384>);
+#[derive(Clone)]
+pub struct Jh512(InternalStreamHasher<jh::Jh512>);
+/// Ascon-Hash256 of NIST SP 800-232, the lightweight hash of the Ascon family
+#[derive(Clone)]
+pub struct AsconHash256(InternalStreamHasher<ascon_hash::AsconHash256>);
 
-impl_hasher_stream!(Blake2b, typenum::U64, "Blake2b");
-impl_hasher_stream!(Blake2s, typenum::U32, "Blake2s");
-impl_hasher_stream!(Md5, typenum::U16, "Md5");
-impl_hasher_stream!(Sha1, typenum::U20, "Sha1");
-impl_hasher_stream!(Sha224, typenum::U28, "Sha224");
-impl_hasher_stream!(Sha256, typenum::U32, "Sha256");
-impl_hasher_stream!(Sha384, typenum::U48, "Sha384");
-impl_hasher_stream!(Sha512, typenum::U64, "Sha512");
-impl_hasher_stream!(Sha3_224, typenum::U28, "Sha3-224");
-impl_hasher_stream!(Sha3_256, typenum::U32, "Sha3-256");
-impl_hasher_stream!(Sha3_384, typenum::U48, "Sha3-384");
-impl_hasher_stream!(Sha3_512, typenum::U64, "Sha3-512");
-
-#[derive(Clone)]
-pub struct Blake3 {
-    hasher: blake3::Hasher,
-}
+impl_hasher_stream!(Blake2b512, blake2::Blake2b512, typenum::U64, "Blake2b");
+impl_hasher_stream!(Blake2s256, blake2::Blake2s256, typenum::U32, "Blake2s");
+impl_hasher_stream!(Md5, md5::Md5, typenum::U16, "Md5");
+impl_hasher_stream!(Sha1, sha1::Sha1, typenum::U20, "Sha1");
+impl_hasher_stream!(Sha224, sha2::Sha224, typenum::U28, "Sha224");
+impl_hasher_stream!(Sha256, sha2::Sha256, typenum::U32, "Sha256");
+impl_hasher_stream!(Sha384, sha2::Sha384, typenum::U48, "Sha384");
+impl_hasher_stream!(Sha512, sha2::Sha512, typenum::U64, "Sha512");
+impl_hasher_stream!(Sha512_224, sha2::Sha512_224, typenum::U28, "Sha512-224");
+impl_hasher_stream!(Sha512_256, sha2::Sha512_256, typenum::U32, "Sha512-256");
+impl_hasher_stream!(Sha3_224, sha3::Sha3_224, typenum::U28, "Sha3-224");
+impl_hasher_stream!(Sha3_256, sha3::Sha3_256, typenum::U32, "Sha3-256");
+impl_hasher_stream!(Sha3_384, sha3::Sha3_384, typenum::U48, "Sha3-384");
+impl_hasher_stream!(Sha3_512, sha3::Sha3_512, typenum::U64, "Sha3-512");
+impl_hasher_stream!(Keccak224, sha3::Keccak224, typenum::U28, "Keccak224");
+impl_hasher_stream!(Keccak256, sha3::Keccak256, typenum::U32, "Keccak256");
+impl_hasher_stream!(Keccak384, sha3::Keccak384, typenum::U48, "Keccak384");
+impl_hasher_stream!(Keccak512, sha3::Keccak512, typenum::U64, "Keccak512");
+impl_hasher_stream!(Ripemd160, ripemd::Ripemd160, typenum::U20, "Ripemd160");
+impl_hasher_stream!(Whirlpool, whirlpool::Whirlpool, typenum::U64, "Whirlpool");
+impl_hasher_stream!(Tiger, tiger::Tiger, typenum::U24, "Tiger");
+impl_hasher_stream!(Tiger2, tiger::Tiger2, typenum::U24, "Tiger2");
+impl_hasher_stream!(Sm3, sm3::Sm3, typenum::U32, "Sm3");
+impl_hasher_stream!(
+    Streebog256,
+    streebog::Streebog256,
+    typenum::U32,
+    "Streebog256"
+);
+impl_hasher_stream!(
+    Streebog512,
+    streebog::Streebog512,
+    typenum::U64,
+    "Streebog512"
+);
+impl_hasher_stream!(Gost94, gost94::Gost94Test, typenum::U32, "Gost94");
+impl_hasher_stream!(
+    Gost94CryptoPro,
+    gost94::Gost94CryptoPro,
+    typenum::U32,
+    "Gost94-CryptoPro"
+);
+impl_hasher_stream!(Groestl224, groestl::Groestl224, typenum::U28, "Groestl224");
+impl_hasher_stream!(Groestl256, groestl::Groestl256, typenum::U32, "Groestl256");
+impl_hasher_stream!(Groestl384, groestl::Groestl384, typenum::U48, "Groestl384");
+impl_hasher_stream!(Groestl512, groestl::Groestl512, typenum::U64, "Groestl512");
+impl_hasher_stream!(Jh224, jh::Jh224, typenum::U28, "Jh224");
+impl_hasher_stream!(Jh256, jh::Jh256, typenum::U32, "Jh256");
+impl_hasher_stream!(Jh384, jh::Jh384, typenum::U48, "Jh384");
+impl_hasher_stream!(Jh512, jh::Jh512, typenum::U64, "Jh512");
+impl_hasher_stream!(
+    AsconHash256,
+    ascon_hash::AsconHash256,
+    typenum::U32,
+    "AsconHash256"
+);
+
+/// The original BLAKE, the SHA-3 finalist that BLAKE2 is derived from. Its crate implements an older
+/// version of the digest traits, so it can't be used with `InternalStreamHasher`.
+macro_rules! impl_blake_hasher {
+    ($hasher:ident, $blake:ty, $output_size:ty, $block_size:literal, $algo_name:literal) => {
+        #[derive(Clone)]
+        pub struct $hasher($blake);
+
+        impl SizedHasher for $hasher {
+            type OutputSize = $output_size;
+
+            fn algorithm_name() -> &'static str {
+                $algo_name
+            }
+
+            fn new() -> Self {
+                Self(blake_hash::Digest::new())
+            }
 
-impl SizedHasher for Blake3 {
-    type OutputSize = typenum::U32;
+            fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+                blake_hash::Digest::update(&mut self.0, in_bytes.as_ref());
+                self
+            }
+
+            fn finalize_and_reset(&mut self) -> GenericArray<u8, Self::OutputSize> {
+                let result = blake_hash::Digest::finalize_reset(&mut self.0);
+                GenericArray::clone_from_slice(&result)
+            }
+
+            fn reset(&mut self) {
+                blake_hash::Digest::reset(&mut self.0)
+            }
+        }
 
-    fn algorithm_name() -> &'static str {
-        "Blake3"
-    }
+        impl BlockSizedHasher for $hasher {
+            fn block_size() -> usize {
+                $block_size
+            }
 
-    fn new() -> Self {
-        Self {
-            hasher: blake3::Hasher::new(),
+            fn hmac_algorithm_name() -> &'static str {
+                concat!("Hmac-", $algo_name)
+            }
         }
-    }
-
-    fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
-        self.hasher.update(in_bytes.as_ref());
-        self
-    }
-
-    fn reset(&mut self) {
-        self.hasher.reset();
-    }
-
-    fn finalize_and_reset(&mut self) -> GenericArray<u8, Self::OutputSize> {
-        let result = self.hasher.finalize();
-        self.reset();
-        let array: [u8; 32] = result.into();
-        array.into()
-    }
+    };
 }
+
+impl_blake_hasher!(Blake224, blake_hash::Blake224, typenum::U28, 64, "Blake224");
+impl_blake_hasher!(Blake256, blake_hash::Blake256, typenum::U32, 64, "Blake256");
+impl_blake_hasher!(
+    Blake384,
+    blake_hash::Blake384,
+    typenum::U48,
+    128,
+    "Blake384"
+);
+impl_blake_hasher!(
+    Blake512,
+    blake_hash::Blake512,
+    typenum::U64,
+    128,
+    "Blake512"
+);
@@ -0,0 +1,158 @@
+//! Skein, a SHA-3 finalist built on the Threefish block cipher. Its output size is chosen
+//! at runtime and is part of its configuration, so outputs of different sizes are unrelated.
+
+use std::num::NonZeroUsize;
+
+use threefish::{Threefish1024, Threefish256, Threefish512};
+
+use super::unsized_hasher::{UnsizedHasher, VariableOutputHasher};
+
+const SCHEMA_VERSION: u64 = (1 << 32) | 0x3341_4853;
+const TREE_INFO_SEQUENTIAL: u64 = 0;
+const CONFIG_SIZE: usize = 32;
+
+const T1_FLAG_FIRST: u64 = 1 << 62;
+const T1_FLAG_FINAL: u64 = 1 << 63;
+const T1_BLOCK_TYPE_CONFIG: u64 = 4 << 56;
+const T1_BLOCK_TYPE_MESSAGE: u64 = 48 << 56;
+const T1_BLOCK_TYPE_OUTPUT: u64 = 63 << 56;
+
+macro_rules! impl_skein_hasher {
+    ($hasher:ident, $threefish:ident, $state_size:expr, $algo_name:literal) => {
+        #[derive(Clone)]
+        pub struct $hasher {
+            output_size: NonZeroUsize,
+            /// The chaining value after the configuration block, which depends on the output size
+            initial_chain: [u64; $state_size / 8],
+            chain: [u64; $state_size / 8],
+            tweak: [u64; 2],
+            /// The last block is only processed once it's known whether it's the final one
+            buffer: [u8; $state_size],
+            buffer_len: usize,
+        }
+
+        impl $hasher {
+            /// Runs the Unique Block Iteration on the block, which is zero-padded to the state size
+            fn process_block(
+                chain: &mut [u64; $state_size / 8],
+                tweak: &mut [u64; 2],
+                block: &[u8; $state_size],
+                byte_count: usize,
+            ) {
+                tweak[0] += byte_count as u64;
+                let cipher = $threefish::new_with_tweak_u64(chain, tweak);
+
+                let mut words = [0u64; $state_size / 8];
+                for (word, bytes) in words.iter_mut().zip(block.chunks_exact(8)) {
+                    *word = u64::from_le_bytes(bytes.try_into().expect("Chunks are 8 bytes"));
+                }
+                let plaintext = words;
+
+                cipher.encrypt_block_u64(&mut words);
+
+                for ((chain_word, word), plain_word) in chain.iter_mut().zip(words).zip(plaintext) {
+                    *chain_word = word ^ plain_word;
+                }
+                tweak[1] &= !T1_FLAG_FIRST;
+            }
+
+            fn message_tweak() -> [u64; 2] {
+                [0, T1_FLAG_FIRST | T1_BLOCK_TYPE_MESSAGE]
+            }
+        }
+
+        impl UnsizedHasher for $hasher {
+            fn algorithm_name() -> &'static str {
+                $algo_name
+            }
+
+            fn new(output_size: NonZeroUsize) -> Self {
+                let mut config = [0; $state_size];
+                config[..8].copy_from_slice(&SCHEMA_VERSION.to_le_bytes());
+                config[8..16].copy_from_slice(&(output_size.get() as u64 * 8).to_le_bytes());
+                config[16..24].copy_from_slice(&TREE_INFO_SEQUENTIAL.to_le_bytes());
+
+                let mut initial_chain = [0; $state_size / 8];
+                let mut tweak = [0, T1_FLAG_FIRST | T1_BLOCK_TYPE_CONFIG | T1_FLAG_FINAL];
+                Self::process_block(&mut initial_chain, &mut tweak, &config, CONFIG_SIZE);
+
+                Self {
+                    output_size,
+                    initial_chain,
+                    chain: initial_chain,
+                    tweak: Self::message_tweak(),
+                    buffer: [0; $state_size],
+                    buffer_len: 0,
+                }
+            }
+
+            fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+                let mut in_bytes = in_bytes.as_ref();
+                while !in_bytes.is_empty() {
+                    if self.buffer_len == $state_size {
+                        Self::process_block(
+                            &mut self.chain,
+                            &mut self.tweak,
+                            &self.buffer,
+                            $state_size,
+                        );
+                        self.buffer_len = 0;
+                    }
+
+                    let copied = in_bytes.len().min($state_size - self.buffer_len);
+                    self.buffer[self.buffer_len..self.buffer_len + copied]
+                        .copy_from_slice(&in_bytes[..copied]);
+                    self.buffer_len += copied;
+                    in_bytes = &in_bytes[copied..];
+                }
+                self
+            }
+
+            fn finalize_and_reset(&mut self) -> Box<[u8]> {
+                self.tweak[1] |= T1_FLAG_FINAL;
+                self.buffer[self.buffer_len..].fill(0);
+                Self::process_block(
+                    &mut self.chain,
+                    &mut self.tweak,
+                    &self.buffer,
+                    self.buffer_len,
+                );
+
+                // The output is made of blocks of the state size, by running the final chaining
+                // value over a counter
+                let mut result = vec![0; self.output_size.get()].into_boxed_slice();
+                let mut counter_block = [0; $state_size];
+                for (counter, output_block) in result.chunks_mut($state_size).enumerate() {
+                    let mut output_chain = self.chain;
+                    let mut output_tweak =
+                        [0, T1_FLAG_FIRST | T1_BLOCK_TYPE_OUTPUT | T1_FLAG_FINAL];
+                    counter_block[..8].copy_from_slice(&(counter as u64).to_le_bytes());
+                    Self::process_block(&mut output_chain, &mut output_tweak, &counter_block, 8);
+
+                    for (bytes, word) in output_block.chunks_mut(8).zip(output_chain) {
+                        bytes.copy_from_slice(&word.to_le_bytes()[..bytes.len()]);
+                    }
+                }
+
+                self.chain = self.initial_chain;
+                self.tweak = Self::message_tweak();
+                self.buffer_len = 0;
+
+                result
+            }
+
+            fn output_size(&self) -> NonZeroUsize {
+                self.output_size
+            }
+        }
+
+        /// The default output size is the state size
+        impl VariableOutputHasher for $hasher {
+            const DEFAULT_OUTPUT_SIZE: usize = $state_size;
+        }
+    };
+}
+
+impl_skein_hasher!(Skein256, Threefish256, 32, "Skein256");
+impl_skein_hasher!(Skein512, Threefish512, 64, "Skein512");
+impl_skein_hasher!(Skein1024, Threefish1024, 128, "Skein1024");
@@ -0,0 +1,383 @@
+//! The functions of NIST SP 800-185 that are built on cSHAKE: KMAC, TupleHash and ParallelHash.
+//! All of them encode the requested output size into the input, so a shorter output
+//! is not a prefix of a longer one.
+
+use std::{num::NonZeroUsize, sync::Arc};
+
+use super::{
+    internal::{ExtendableOutput, InternalXofHasher, Update},
+    unsized_hasher::{thread_pool, UnsizedHasher},
+};
+
+/// The security strength of the functions, which decides the cSHAKE that is used
+pub trait Sp800185Variant {
+    type CShake: ExtendableOutput + Update + Clone;
+
+    /// The rate of the sponge in bytes, which the encoded key of KMAC is padded to
+    const RATE: usize;
+    /// The size of the digest of every block in ParallelHash, which is twice the security strength
+    const CHAINING_VALUE_SIZE: usize;
+    const DEFAULT_OUTPUT_SIZE: usize;
+
+    const KMAC_NAME: &'static str;
+    const TUPLE_HASH_NAME: &'static str;
+    const PARALLEL_HASH_NAME: &'static str;
+
+    fn cshake(function_name: &[u8], customization: &[u8]) -> Self::CShake;
+}
+
+macro_rules! impl_sp800_185_variant {
+    ($variant:ident, $xof:ty, $core:ty, $rate:expr, $size:expr, $bits:literal) => {
+        #[derive(Clone)]
+        pub struct $variant;
+
+        impl Sp800185Variant for $variant {
+            type CShake = $xof;
+
+            const RATE: usize = $rate;
+            const CHAINING_VALUE_SIZE: usize = $size;
+            const DEFAULT_OUTPUT_SIZE: usize = $size;
+
+            const KMAC_NAME: &'static str = concat!("Kmac", $bits);
+            const TUPLE_HASH_NAME: &'static str = concat!("TupleHash", $bits);
+            const PARALLEL_HASH_NAME: &'static str = concat!("ParallelHash", $bits);
+
+            fn cshake(function_name: &[u8], customization: &[u8]) -> Self::CShake {
+                <$xof>::from_core(<$core>::new_with_function_name(
+                    function_name,
+                    customization,
+                ))
+            }
+        }
+    };
+}
+
+impl_sp800_185_variant!(
+    Sp800185_128,
+    sha3::CShake128,
+    sha3::CShake128Core,
+    168,
+    32,
+    "128"
+);
+impl_sp800_185_variant!(
+    Sp800185_256,
+    sha3::CShake256,
+    sha3::CShake256Core,
+    136,
+    64,
+    "256"
+);
+
+fn left_encode(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let skip = (value.leading_zeros() as usize / 8).min(bytes.len() - 1);
+    let mut result = vec![(bytes.len() - skip) as u8];
+    result.extend_from_slice(&bytes[skip..]);
+    result
+}
+
+fn right_encode(value: u64) -> Vec<u8> {
+    let mut result = left_encode(value);
+    result.rotate_left(1);
+    result
+}
+
+/// The length in bits, followed by the bytes
+fn encode_string(data: &[u8]) -> Vec<u8> {
+    let mut result = left_encode(data.len() as u64 * 8);
+    result.extend_from_slice(data);
+    result
+}
+
+/// Prefixes the data with the rate, and pads it with zeros to a multiple of the rate
+fn bytepad(data: &[u8], rate: usize) -> Vec<u8> {
+    let mut result = left_encode(rate as u64);
+    result.extend_from_slice(data);
+    result.resize(result.len().div_ceil(rate) * rate, 0);
+    result
+}
+
+fn output_size_in_bits(output_size: NonZeroUsize) -> u64 {
+    output_size.get() as u64 * 8
+}
+
+#[derive(Clone)]
+pub struct Kmac<V: Sp800185Variant> {
+    hasher: InternalXofHasher<V::CShake>,
+    output_size: NonZeroUsize,
+}
+
+impl<V: Sp800185Variant> Kmac<V> {
+    pub fn with_key(key: &[u8], customization: &[u8], output_size: NonZeroUsize) -> Self {
+        let mut cshake = V::cshake(b"KMAC", customization);
+        cshake.update(&bytepad(&encode_string(key), V::RATE));
+
+        Self {
+            hasher: InternalXofHasher::from_hasher(cshake),
+            output_size,
+        }
+    }
+}
+
+impl<V: Sp800185Variant> UnsizedHasher for Kmac<V> {
+    fn algorithm_name() -> &'static str {
+        V::KMAC_NAME
+    }
+
+    fn new(output_size: NonZeroUsize) -> Self {
+        Self::with_key(&[], &[], output_size)
+    }
+
+    fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+        self.hasher.write(in_bytes);
+        self
+    }
+
+    fn finalize_and_reset(&mut self) -> Box<[u8]> {
+        self.hasher
+            .write(right_encode(output_size_in_bits(self.output_size)));
+        self.hasher.finalize_and_reset(self.output_size.get())
+    }
+
+    fn output_size(&self) -> NonZeroUsize {
+        self.output_size
+    }
+}
+
+/// TupleHash of the elements that are written. The length of every element is hashed before its content,
+/// so an element is kept in memory until it ends, unless its length is announced with `begin_input`,
+/// like it is for files. Only inputs of unknown length, like stdin, are kept in memory.
+#[derive(Clone)]
+pub struct TupleHash<V: Sp800185Variant> {
+    hasher: InternalXofHasher<V::CShake>,
+    element: Vec<u8>,
+    /// The announced length of the current element, and the number of bytes written of it so far.
+    /// The bytes of an announced element are hashed as they're written, instead of kept in `element`.
+    announced: Option<(u64, u64)>,
+    // Whether finalizing has to end the current element. This is false only right after
+    // an element was ended, so that a tuple of a single empty element can be hashed.
+    element_pending: bool,
+    output_size: NonZeroUsize,
+}
+
+impl<V: Sp800185Variant> TupleHash<V> {
+    pub fn with_customization(customization: &[u8], output_size: NonZeroUsize) -> Self {
+        Self {
+            hasher: InternalXofHasher::from_hasher(V::cshake(b"TupleHash", customization)),
+            element: Vec::new(),
+            announced: None,
+            element_pending: true,
+            output_size,
+        }
+    }
+
+    fn end_element(&mut self) {
+        if self.announced.take().is_none() {
+            self.hasher.write(encode_string(&self.element));
+        }
+        self.element.clear();
+    }
+}
+
+impl<V: Sp800185Variant> UnsizedHasher for TupleHash<V> {
+    fn algorithm_name() -> &'static str {
+        V::TUPLE_HASH_NAME
+    }
+
+    fn new(output_size: NonZeroUsize) -> Self {
+        Self::with_customization(&[], output_size)
+    }
+
+    fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+        match &mut self.announced {
+            Some((_, written)) => {
+                self.hasher.write(in_bytes.as_ref());
+                *written += in_bytes.as_ref().len() as u64;
+            }
+            None => self.element.extend_from_slice(in_bytes.as_ref()),
+        }
+        self.element_pending = true;
+        self
+    }
+
+    /// The element can only be announced before any of its bytes were written
+    fn begin_input(&mut self, len: u64) {
+        if self.announced.is_none() && self.element.is_empty() {
+            self.hasher.write(left_encode(len * 8));
+            self.announced = Some((len, 0));
+            self.element_pending = true;
+        }
+    }
+
+    fn end_input(&mut self) -> anyhow::Result<()> {
+        match self.announced {
+            Some((len, written)) if len != written => Err(anyhow::anyhow!(
+                "The size of the input changed while it was hashed, from {len} to {written} bytes"
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn end_tuple_element(&mut self) -> anyhow::Result<()> {
+        self.end_input()?;
+        self.end_element();
+        self.element_pending = false;
+        Ok(())
+    }
+
+    fn finalize_and_reset(&mut self) -> Box<[u8]> {
+        if self.element_pending {
+            self.end_element();
+        }
+        self.element_pending = true;
+
+        self.hasher
+            .write(right_encode(output_size_in_bits(self.output_size)));
+        self.hasher.finalize_and_reset(self.output_size.get())
+    }
+
+    fn output_size(&self) -> NonZeroUsize {
+        self.output_size
+    }
+}
+
+/// ParallelHash of the input. With more than one thread, the complete blocks of every write are hashed
+/// in parallel on a pool of that many threads. Blocks that are split between writes are kept until
+/// they're complete, and hashed on the current thread.
+#[derive(Clone)]
+pub struct ParallelHash<V: Sp800185Variant> {
+    hasher: InternalXofHasher<V::CShake>,
+    block: Vec<u8>,
+    block_size: NonZeroUsize,
+    block_count: u64,
+    output_size: NonZeroUsize,
+    /// Without a thread pool, all the blocks are hashed on the current thread
+    thread_pool: Option<Arc<rayon_core::ThreadPool>>,
+}
+
+pub const DEFAULT_PARALLEL_HASH_BLOCK_SIZE: usize = 8192;
+
+/// Every task of the thread pool hashes blocks of at least this many bytes in total,
+/// so that small blocks don't cost more to coordinate than to hash
+const MIN_PARALLEL_TASK_SIZE: usize = 64 * 1024;
+
+impl<V: Sp800185Variant> ParallelHash<V> {
+    pub fn with_parameters(
+        block_size: NonZeroUsize,
+        customization: &[u8],
+        output_size: NonZeroUsize,
+    ) -> Self {
+        let mut cshake = V::cshake(b"ParallelHash", customization);
+        cshake.update(&left_encode(block_size.get() as u64));
+
+        Self {
+            hasher: InternalXofHasher::from_hasher(cshake),
+            block: Vec::with_capacity(block_size.get()),
+            block_size,
+            block_count: 0,
+            output_size,
+            thread_pool: None,
+        }
+    }
+
+    /// Hashes the complete blocks of large writes with the given number of threads
+    pub fn with_threads(mut self, threads: NonZeroUsize) -> anyhow::Result<Self> {
+        self.thread_pool = thread_pool(threads)?;
+        Ok(self)
+    }
+
+    fn hash_block(block: &[u8]) -> Box<[u8]> {
+        let mut block_hasher = V::cshake(&[], &[]);
+        block_hasher.update(block);
+        block_hasher.finalize_boxed(V::CHAINING_VALUE_SIZE)
+    }
+
+    fn end_block(&mut self) {
+        self.hasher.write(Self::hash_block(&self.block));
+        self.block.clear();
+        self.block_count += 1;
+    }
+
+    /// Hashes complete blocks, in parallel if there is a thread pool, and writes their chaining values in order
+    fn write_blocks(&mut self, blocks: &[u8]) {
+        let block_size = self.block_size.get();
+        let blocks_per_task = (MIN_PARALLEL_TASK_SIZE / block_size).max(1);
+
+        let mut chaining_values = vec![Box::<[u8]>::default(); blocks.len() / block_size];
+        let hash_blocks = |values: &mut [Box<[u8]>], task_blocks: &[u8]| {
+            for (value, block) in values.iter_mut().zip(task_blocks.chunks(block_size)) {
+                *value = Self::hash_block(block);
+            }
+        };
+
+        match &self.thread_pool {
+            Some(thread_pool) => thread_pool.scope(|scope| {
+                for (values, task_blocks) in chaining_values
+                    .chunks_mut(blocks_per_task)
+                    .zip(blocks.chunks(blocks_per_task * block_size))
+                {
+                    scope.spawn(move |_| hash_blocks(values, task_blocks));
+                }
+            }),
+            None => hash_blocks(&mut chaining_values, blocks),
+        }
+
+        for value in &chaining_values {
+            self.hasher.write(value);
+        }
+        self.block_count += chaining_values.len() as u64;
+    }
+}
+
+impl<V: Sp800185Variant> UnsizedHasher for ParallelHash<V> {
+    fn algorithm_name() -> &'static str {
+        V::PARALLEL_HASH_NAME
+    }
+
+    fn new(output_size: NonZeroUsize) -> Self {
+        let block_size = NonZeroUsize::new(DEFAULT_PARALLEL_HASH_BLOCK_SIZE)
+            .expect("Default block size is never zero");
+        Self::with_parameters(block_size, &[], output_size)
+    }
+
+    fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+        let block_size = self.block_size.get();
+        let mut data = in_bytes.as_ref();
+
+        // Complete the block that was started by previous writes
+        if !self.block.is_empty() {
+            let to_take = (block_size - self.block.len()).min(data.len());
+            self.block.extend_from_slice(&data[..to_take]);
+            data = &data[to_take..];
+
+            if self.block.len() == block_size {
+                self.end_block();
+            }
+        }
+
+        let complete_size = data.len() / block_size * block_size;
+        if complete_size > 0 {
+            self.write_blocks(&data[..complete_size]);
+        }
+        self.block.extend_from_slice(&data[complete_size..]);
+
+        self
+    }
+
+    fn finalize_and_reset(&mut self) -> Box<[u8]> {
+        if !self.block.is_empty() {
+            self.end_block();
+        }
+
+        self.hasher.write(right_encode(self.block_count));
+        self.hasher
+            .write(right_encode(output_size_in_bits(self.output_size)));
+        self.block_count = 0;
+        self.hasher.finalize_and_reset(self.output_size.get())
+    }
+
+    fn output_size(&self) -> NonZeroUsize {
+        self.output_size
+    }
+}
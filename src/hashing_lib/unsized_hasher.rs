@@ -1,4 +1,23 @@
-use std::num::NonZeroUsize;
+use std::{num::NonZeroUsize, path::Path, sync::Arc};
+
+use super::internal::InternalXofHasher;
+
+/// The smallest write that is worth splitting between threads. Below this,
+/// the cost of coordinating the threads outweighs the gain, according to the BLAKE3 documentation.
+const MIN_MULTITHREADED_WRITE_SIZE: usize = 128 * 1024;
+
+/// A pool of the given number of threads, or none for a single thread, which is the current one
+pub fn thread_pool(threads: NonZeroUsize) -> anyhow::Result<Option<Arc<rayon_core::ThreadPool>>> {
+    if threads.get() == 1 {
+        return Ok(None);
+    }
+
+    let thread_pool = rayon_core::ThreadPoolBuilder::new()
+        .num_threads(threads.get())
+        .build()
+        .map_err(|e| anyhow::anyhow!("Creating a pool of {threads} threads failed: {e}"))?;
+    Ok(Some(Arc::new(thread_pool)))
+}
 
 pub trait UnsizedHasher: Sized {
     fn algorithm_name() -> &'static str;
@@ -10,56 +29,343 @@ pub trait UnsizedHasher: Sized {
     fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self;
 
     fn finalize_and_reset(&mut self) -> Box<[u8]>;
+
+    /// Hashes the whole file, for hashers that read files in their own way.
+    /// Returns false if the hasher doesn't, and the file should be written into it instead.
+    fn write_file(&mut self, _path: &Path) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    /// Announces the length of the input that is written next, when it's known before reading it,
+    /// like for files. Hashers that hash the length before the content, like TupleHash, use it
+    /// to hash the input as it's written, instead of keeping it in memory.
+    fn begin_input(&mut self, _len: u64) {}
+
+    /// Checks that the input that was announced with `begin_input` had the announced length
+    fn end_input(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Ends the current element of a tuple, for hashers of tuples of inputs, like TupleHash
+    fn end_tuple_element(&mut self) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!(
+            "Algorithm {} cannot hash tuples of inputs",
+            Self::algorithm_name()
+        ))
+    }
 }
 
-pub struct K12<'a> {
-    hasher: Option<k12::KangarooTwelve<'a>>,
+#[derive(Clone)]
+pub struct Blake3 {
+    hasher: blake3::Hasher,
     output_size: NonZeroUsize,
+    output_offset: u64,
+    /// Without a thread pool, the hasher uses only the current thread
+    thread_pool: Option<Arc<rayon_core::ThreadPool>>,
+}
+
+impl Blake3 {
+    pub fn new_keyed(key: &[u8; 32], output_size: NonZeroUsize) -> Self {
+        Self {
+            hasher: blake3::Hasher::new_keyed(key),
+            output_size,
+            output_offset: 0,
+            thread_pool: None,
+        }
+    }
+
+    pub fn new_derive_key(context: &str, output_size: NonZeroUsize) -> Self {
+        Self {
+            hasher: blake3::Hasher::new_derive_key(context),
+            output_size,
+            output_offset: 0,
+            thread_pool: None,
+        }
+    }
+
+    /// Makes the output start from the given position in the output stream, instead of its beginning
+    pub fn with_output_offset(mut self, output_offset: u64) -> Self {
+        self.output_offset = output_offset;
+        self
+    }
+
+    /// Hashes large writes and whole files with the given number of threads. Files are memory-mapped.
+    pub fn with_threads(mut self, threads: NonZeroUsize) -> anyhow::Result<Self> {
+        self.thread_pool = thread_pool(threads)?;
+        Ok(self)
+    }
 }
 
-impl<'a> K12<'a> {
-    fn hasher(&mut self) -> &mut k12::KangarooTwelve<'a> {
-        // An invariant is used that the value is always Some(), as long as the object is alive
-        debug_assert!(self.hasher.is_some());
-        unsafe { self.hasher.as_mut().unwrap_unchecked() }
+impl UnsizedHasher for Blake3 {
+    fn algorithm_name() -> &'static str {
+        "Blake3"
     }
 
-    fn finalize_and_reset_inner(&mut self) -> Box<[u8]> {
-        use k12::digest::ExtendableOutput;
+    fn new(output_size: NonZeroUsize) -> Self {
+        Self {
+            hasher: blake3::Hasher::new(),
+            output_size,
+            output_offset: 0,
+            thread_pool: None,
+        }
+    }
 
-        let result = self
-            .hasher
-            .take()
-            .expect("Must exist")
-            .finalize_boxed(self.output_size.get());
+    fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+        let in_bytes = in_bytes.as_ref();
+        match &self.thread_pool {
+            Some(thread_pool) if in_bytes.len() >= MIN_MULTITHREADED_WRITE_SIZE => {
+                thread_pool.install(|| self.hasher.update_rayon(in_bytes));
+            }
+            _ => {
+                self.hasher.update(in_bytes);
+            }
+        }
+        self
+    }
 
-        // Reset the hasher, to maintain the invariant that hasher is Some
-        self.hasher = Some(k12::KangarooTwelve::default());
+    fn write_file(&mut self, path: &Path) -> anyhow::Result<bool> {
+        let Some(thread_pool) = &self.thread_pool else {
+            return Ok(false);
+        };
+        thread_pool.install(|| self.hasher.update_mmap_rayon(path))?;
+        Ok(true)
+    }
+
+    fn finalize_and_reset(&mut self) -> Box<[u8]> {
+        let mut reader = self.hasher.finalize_xof();
+        reader.set_position(self.output_offset);
+
+        let mut result = vec![0; self.output_size.get()].into_boxed_slice();
+        reader.fill(&mut result);
+
+        // Resetting keeps the key or the context of the hasher
+        self.hasher.reset();
 
         result
     }
+
+    fn output_size(&self) -> NonZeroUsize {
+        self.output_size
+    }
+}
+
+/// The parameter block of BLAKE2, as defined in RFC 7693, with the limits of each variant
+pub trait Blake2Hasher: UnsizedHasher {
+    const MAX_OUTPUT_SIZE: usize;
+    const MAX_KEY_SIZE: usize;
+    const MAX_SALT_SIZE: usize;
+    const MAX_PERSONAL_SIZE: usize;
+
+    /// The parameters must be within the limits above. Salt and personalization shorter than
+    /// their maximum size are padded with zeros.
+    fn with_parameters(output_size: NonZeroUsize, key: &[u8], salt: &[u8], personal: &[u8])
+        -> Self;
+}
+
+macro_rules! impl_blake2_hasher {
+    ($hasher_type:ident, $simd_crate:ident, $algo_name:expr) => {
+        #[derive(Clone)]
+        pub struct $hasher_type {
+            params: $simd_crate::Params,
+            state: $simd_crate::State,
+            output_size: NonZeroUsize,
+        }
+
+        impl Blake2Hasher for $hasher_type {
+            const MAX_OUTPUT_SIZE: usize = $simd_crate::OUTBYTES;
+            const MAX_KEY_SIZE: usize = $simd_crate::KEYBYTES;
+            const MAX_SALT_SIZE: usize = $simd_crate::SALTBYTES;
+            const MAX_PERSONAL_SIZE: usize = $simd_crate::PERSONALBYTES;
+
+            fn with_parameters(
+                output_size: NonZeroUsize,
+                key: &[u8],
+                salt: &[u8],
+                personal: &[u8],
+            ) -> Self {
+                let params = $simd_crate::Params::new()
+                    .hash_length(output_size.get())
+                    .key(key)
+                    .salt(salt)
+                    .personal(personal)
+                    .clone();
+
+                Self {
+                    state: params.to_state(),
+                    params,
+                    output_size,
+                }
+            }
+        }
+
+        impl UnsizedHasher for $hasher_type {
+            fn algorithm_name() -> &'static str {
+                $algo_name
+            }
+
+            fn new(output_size: NonZeroUsize) -> Self {
+                Self::with_parameters(output_size, &[], &[], &[])
+            }
+
+            fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+                self.state.update(in_bytes.as_ref());
+                self
+            }
+
+            fn finalize_and_reset(&mut self) -> Box<[u8]> {
+                let result = self.state.finalize();
+                self.state = self.params.to_state();
+                result.as_bytes().into()
+            }
+
+            fn output_size(&self) -> NonZeroUsize {
+                self.output_size
+            }
+        }
+    };
+}
+
+impl_blake2_hasher!(Blake2b, blake2b_simd, "Blake2b");
+impl_blake2_hasher!(Blake2s, blake2s_simd, "Blake2s");
+
+/// Hashers of any output size, whose only option is the output size
+pub trait VariableOutputHasher: UnsizedHasher {
+    const DEFAULT_OUTPUT_SIZE: usize;
+}
+
+/// The SHAKE family of extendable output functions, from FIPS 202 and NIST SP 800-185
+pub trait ShakeHasher: UnsizedHasher {
+    const DEFAULT_OUTPUT_SIZE: usize;
+}
+
+/// cSHAKE, which is SHAKE with a function name and a customization string for domain separation
+pub trait CShakeHasher: ShakeHasher {
+    fn with_customization(
+        function_name: &[u8],
+        customization: &[u8],
+        output_size: NonZeroUsize,
+    ) -> Self;
+}
+
+macro_rules! impl_shake_hasher {
+    ($hasher_type:ident, $xof:ty, $new_xof:expr, $default_output_size:expr, $algo_name:expr) => {
+        #[derive(Clone)]
+        pub struct $hasher_type {
+            hasher: InternalXofHasher<$xof>,
+            output_size: NonZeroUsize,
+        }
+
+        impl ShakeHasher for $hasher_type {
+            const DEFAULT_OUTPUT_SIZE: usize = $default_output_size;
+        }
+
+        impl UnsizedHasher for $hasher_type {
+            fn algorithm_name() -> &'static str {
+                $algo_name
+            }
+
+            fn new(output_size: NonZeroUsize) -> Self {
+                Self {
+                    hasher: InternalXofHasher::from_hasher($new_xof),
+                    output_size,
+                }
+            }
+
+            fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
+                self.hasher.write(in_bytes);
+                self
+            }
+
+            fn finalize_and_reset(&mut self) -> Box<[u8]> {
+                self.hasher.finalize_and_reset(self.output_size.get())
+            }
+
+            fn output_size(&self) -> NonZeroUsize {
+                self.output_size
+            }
+        }
+    };
+}
+
+macro_rules! impl_cshake_hasher {
+    ($hasher_type:ident, $xof:ty, $core:ty) => {
+        impl CShakeHasher for $hasher_type {
+            fn with_customization(
+                function_name: &[u8],
+                customization: &[u8],
+                output_size: NonZeroUsize,
+            ) -> Self {
+                let core = <$core>::new_with_function_name(function_name, customization);
+                Self {
+                    hasher: InternalXofHasher::from_hasher(<$xof>::from_core(core)),
+                    output_size,
+                }
+            }
+        }
+    };
+}
+
+impl_shake_hasher!(
+    Shake128,
+    sha3::Shake128,
+    sha3::Shake128::default(),
+    32,
+    "Shake128"
+);
+impl_shake_hasher!(
+    Shake256,
+    sha3::Shake256,
+    sha3::Shake256::default(),
+    64,
+    "Shake256"
+);
+impl_shake_hasher!(
+    CShake128,
+    sha3::CShake128,
+    sha3::CShake128::from_core(sha3::CShake128Core::new(&[])),
+    32,
+    "CShake128"
+);
+impl_shake_hasher!(
+    CShake256,
+    sha3::CShake256,
+    sha3::CShake256::from_core(sha3::CShake256Core::new(&[])),
+    64,
+    "CShake256"
+);
+impl_cshake_hasher!(CShake128, sha3::CShake128, sha3::CShake128Core);
+impl_cshake_hasher!(CShake256, sha3::CShake256, sha3::CShake256Core);
+
+/// Ascon-XOF128 of NIST SP 800-232
+#[derive(Clone)]
+pub struct AsconXof128 {
+    hasher: InternalXofHasher<ascon_hash::AsconXof128>,
+    output_size: NonZeroUsize,
+}
+
+impl VariableOutputHasher for AsconXof128 {
+    const DEFAULT_OUTPUT_SIZE: usize = 32;
 }
 
-impl<'a> UnsizedHasher for K12<'a> {
+impl UnsizedHasher for AsconXof128 {
     fn algorithm_name() -> &'static str {
-        "K12"
+        "AsconXof128"
     }
 
     fn new(output_size: NonZeroUsize) -> Self {
         Self {
-            hasher: Some(k12::KangarooTwelve::default()),
+            hasher: InternalXofHasher::from_hasher(ascon_hash::AsconXof128::default()),
             output_size,
         }
     }
 
     fn write<T: AsRef<[u8]>>(&mut self, in_bytes: T) -> &mut Self {
-        use k12::digest::Update;
-        self.hasher().update(in_bytes.as_ref());
+        self.hasher.write(in_bytes);
         self
     }
 
     fn finalize_and_reset(&mut self) -> Box<[u8]> {
-        self.finalize_and_reset_inner()
+        self.hasher.finalize_and_reset(self.output_size.get())
     }
 
     fn output_size(&self) -> NonZeroUsize {
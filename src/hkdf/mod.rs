@@ -0,0 +1,125 @@
+//! The HKDF subcommand, which derives keys with HKDF (RFC 5869) using HMAC with any algorithm that supports it.
+//! Besides the whole derivation, the extract and expand steps can be run separately,
+//! to check every step of key schedules, like those of TLS 1.3 and Noise.
+
+use std::{collections::BTreeMap, path::Path};
+
+use sha3::digest::typenum::Unsigned;
+
+use crate::{
+    hasher::{
+        options::{
+            common::{KEY_KEY, OUTPUT_SIZE_KEY, SALT_KEY},
+            hkdf_options::{HkdfOptions, INFO_KEY},
+            traits::HashingOptions,
+        },
+        visit_hmac_hasher, HmacHasherVisitor,
+    },
+    hashing_lib::{hkdf, sized_hasher::BlockSizedHasher},
+    input::InputSource,
+    output::convert_output,
+    program_options::{HashAlgorithm, HkdfStep, THashOptions},
+};
+
+/// Runs the step of HKDF with the hasher of the algorithm
+pub fn hkdf_step(
+    algo: HashAlgorithm,
+    step: HkdfStep,
+    options: BTreeMap<String, String>,
+    input: impl FnOnce() -> anyhow::Result<Vec<u8>>,
+) -> anyhow::Result<Vec<u8>> {
+    let runner = HkdfStepRunner {
+        step,
+        options,
+        input,
+    };
+    visit_hmac_hasher(algo, runner).unwrap_or_else(|| {
+        Err(anyhow::anyhow!(
+            "Algorithm {algo} cannot be used with HKDF. Only algorithms that can be used as HMAC are supported"
+        ))
+    })
+}
+
+/// Runs the step with the hasher of the algorithm that it's visited with
+struct HkdfStepRunner<F> {
+    step: HkdfStep,
+    options: BTreeMap<String, String>,
+    input: F,
+}
+
+impl<F: FnOnce() -> anyhow::Result<Vec<u8>>> HmacHasherVisitor for HkdfStepRunner<F> {
+    type Output = anyhow::Result<Vec<u8>>;
+
+    fn visit<H: BlockSizedHasher + 'static>(self) -> Self::Output {
+        hkdf_step_with::<H>(self.step, self.options, self.input)
+    }
+}
+
+fn hkdf_step_with<H: BlockSizedHasher>(
+    step: HkdfStep,
+    options: BTreeMap<String, String>,
+    input: impl FnOnce() -> anyhow::Result<Vec<u8>>,
+) -> anyhow::Result<Vec<u8>> {
+    let options = HkdfOptions::<H>::parse(options)?;
+
+    let unused_option = match step {
+        HkdfStep::Extract if options.info.is_some() => Some(INFO_KEY),
+        HkdfStep::Extract if options.output_size.is_some() => Some(OUTPUT_SIZE_KEY),
+        HkdfStep::Expand if options.salt.is_some() => Some(SALT_KEY),
+        _ => None,
+    };
+    if let Some(option_key) = unused_option {
+        return Err(anyhow::anyhow!(
+            "Option `{option_key}` is not used by the {step} step of HKDF"
+        ));
+    }
+
+    let key = match options.key {
+        Some(key) => key,
+        None => input()?,
+    };
+    let salt = options.salt.unwrap_or_default();
+    let info = options.info.unwrap_or_default();
+    let output_size = options
+        .output_size
+        .map(|s| s.get())
+        .unwrap_or(<H::OutputSize as Unsigned>::USIZE);
+
+    match step {
+        HkdfStep::Derive => {
+            let pseudorandom_key = hkdf::extract::<H>(&salt, &key);
+            hkdf::expand::<H>(&pseudorandom_key, &info, output_size)
+        }
+        HkdfStep::Extract => Ok(hkdf::extract::<H>(&salt, &key).to_vec()),
+        HkdfStep::Expand => hkdf::expand::<H>(&key, &info, output_size),
+    }
+}
+
+/// Runs the step of HKDF with the settings of the command line, and returns what should be printed
+pub fn run_hkdf(
+    args: &THashOptions,
+    step: HkdfStep,
+    key_file: Option<&Path>,
+) -> anyhow::Result<Vec<u8>> {
+    if args.iters().get() != 1 {
+        return Err(anyhow::anyhow!("Iterations cannot be used with HKDF"));
+    }
+
+    let options = args.options()?;
+    if key_file.is_some() && options.contains_key(KEY_KEY) {
+        return Err(anyhow::anyhow!(
+            "The key cannot be provided both as an option and as an input file"
+        ));
+    }
+
+    let input = || match key_file {
+        Some(p) => InputSource::File(p.to_path_buf()).read_to_end(),
+        None => InputSource::Stdin.read_to_end(),
+    };
+
+    let output = hkdf_step(args.hash_algo()?, step, options, input)?;
+    Ok(convert_output(output, args.output_format))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    hasher::options::{
+        common::{KEY_KEY, OUTPUT_SIZE_KEY, SALT_KEY},
+        hkdf_options::INFO_KEY,
+    },
+    program_options::{HashAlgorithm, HkdfStep},
+};
+
+use super::hkdf_step;
+
+fn make_options(options: &[(&str, &str)]) -> BTreeMap<String, String> {
+    options
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn no_input() -> anyhow::Result<Vec<u8>> {
+    panic!("The key is given in the options")
+}
+
+#[test]
+fn rfc5869() {
+    // Test cases 1, 3 and 4 of RFC 5869, as (algorithm, key, salt, info, pseudorandom key, output)
+    let test_vectors = [
+        (
+            HashAlgorithm::Sha256,
+            "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+            "000102030405060708090a0b0c",
+            "f0f1f2f3f4f5f6f7f8f9",
+            "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5",
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865",
+        ),
+        (
+            HashAlgorithm::Sha256,
+            "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+            "",
+            "",
+            "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04",
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8",
+        ),
+        (
+            HashAlgorithm::Sha1,
+            "0b0b0b0b0b0b0b0b0b0b0b",
+            "000102030405060708090a0b0c",
+            "f0f1f2f3f4f5f6f7f8f9",
+            "9b6c18c432a7bf8f0e71c8eb88f4b30baa2ba243",
+            "085a01ea1b10f36933068b56efa5ad81a4f14b822f5b091568a9cdd4f155fda2c22e422478d305f3f896",
+        ),
+    ];
+
+    for (algo, key, salt, info, pseudorandom_key, output) in test_vectors {
+        let options = make_options(&[
+            (KEY_KEY, key),
+            (SALT_KEY, salt),
+            (INFO_KEY, info),
+            (OUTPUT_SIZE_KEY, "42"),
+        ]);
+        let derived = hkdf_step(algo, HkdfStep::Derive, options, no_input).unwrap();
+        assert_eq!(hex::encode(derived), output);
+
+        let options = make_options(&[(KEY_KEY, key), (SALT_KEY, salt)]);
+        let extracted = hkdf_step(algo, HkdfStep::Extract, options, no_input).unwrap();
+        assert_eq!(hex::encode(extracted), pseudorandom_key);
+
+        let options = make_options(&[
+            (KEY_KEY, pseudorandom_key),
+            (INFO_KEY, info),
+            (OUTPUT_SIZE_KEY, "42"),
+        ]);
+        let expanded = hkdf_step(algo, HkdfStep::Expand, options, no_input).unwrap();
+        assert_eq!(hex::encode(expanded), output);
+    }
+}
+
+#[test]
+fn key_from_input() {
+    let options = make_options(&[(SALT_KEY, "text:salt"), (INFO_KEY, "text:info")]);
+    let derived = hkdf_step(HashAlgorithm::Sha3_256, HkdfStep::Derive, options, || {
+        Ok(b"abc".to_vec())
+    })
+    .unwrap();
+    assert_eq!(
+        hex::encode(derived),
+        "841295d911843932232daff5747d437c434fa3120d9678c1f97fd12117daa8e8"
+    );
+}
+
+#[test]
+fn blake2() {
+    // To generate, in Python: HKDF from RFC 5869 with hmac.new(key, data, hashlib.blake2b)
+    let test_vectors = [
+        (
+            HashAlgorithm::Blake2b,
+            "42",
+            "6e8b246487dacca658b8fef9c7a1b44d593ad0bbeeb0ebf89a5eb41a9a3cb37990e835feba7588ec7d6b",
+        ),
+        (
+            HashAlgorithm::Blake2s,
+            "32",
+            "8d455aa7a7df9214a8017516e3846eca730e16d1c12453884eff122ac4e43f43",
+        ),
+    ];
+
+    for (algo, output_size, output) in test_vectors {
+        let options = make_options(&[
+            (SALT_KEY, "text:salt"),
+            (INFO_KEY, "text:info"),
+            (OUTPUT_SIZE_KEY, output_size),
+        ]);
+        let derived = hkdf_step(algo, HkdfStep::Derive, options, || Ok(b"abc".to_vec())).unwrap();
+        assert_eq!(hex::encode(derived), output);
+    }
+}
+
+#[test]
+fn output_size() {
+    // The output is at most 255 blocks of the size of the digest
+    let options = make_options(&[(KEY_KEY, "00"), (OUTPUT_SIZE_KEY, "8160")]);
+    let expanded = hkdf_step(HashAlgorithm::Sha256, HkdfStep::Expand, options, no_input).unwrap();
+    assert_eq!(expanded.len(), 8160);
+
+    let options = make_options(&[(KEY_KEY, "00"), (OUTPUT_SIZE_KEY, "8161")]);
+    assert!(hkdf_step(HashAlgorithm::Sha256, HkdfStep::Expand, options, no_input).is_err());
+
+    // Without an output size, the output is as long as a digest
+    let options = make_options(&[(KEY_KEY, "00")]);
+    let derived = hkdf_step(HashAlgorithm::Sha384, HkdfStep::Derive, options, no_input).unwrap();
+    assert_eq!(derived.len(), 48);
+}
+
+#[test]
+fn invalid_options() {
+    let invalid_options = [
+        (HashAlgorithm::Blake3, HkdfStep::Derive, &[(KEY_KEY, "00")]),
+        (
+            HashAlgorithm::Sha256,
+            HkdfStep::Extract,
+            &[(INFO_KEY, "00")],
+        ),
+        (
+            HashAlgorithm::Sha256,
+            HkdfStep::Extract,
+            &[(OUTPUT_SIZE_KEY, "16")],
+        ),
+        (HashAlgorithm::Sha256, HkdfStep::Expand, &[(SALT_KEY, "00")]),
+        (HashAlgorithm::Sha256, HkdfStep::Derive, &[("seed", "00")]),
+    ];
+
+    for (algo, step, options) in invalid_options {
+        let mut options = make_options(options);
+        options
+            .entry(KEY_KEY.to_string())
+            .or_insert("00".to_string());
+        assert!(hkdf_step(algo, step, options, no_input).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    fs::File,
+    io::Read,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+
+use crate::{
+    hasher::traits::DynHasher,
+    reader::InputReader,
+    tree_hash::{tree_digest, tree_digest_with_jobs, tree_digests},
+};
+
+/// The name used on the command line, and in the output, to refer to stdin
+pub const STDIN_NAME: &str = "-";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Stdin,
+    File(PathBuf),
+}
+
+impl InputSource {
+    pub fn from_path(p: PathBuf) -> Self {
+        if p.as_os_str() == STDIN_NAME {
+            InputSource::Stdin
+        } else {
+            InputSource::File(p)
+        }
+    }
+
+    /// The name of the input as it should be printed in the output
+    pub fn display_name(&self) -> String {
+        match self {
+            InputSource::Stdin => STDIN_NAME.to_string(),
+            InputSource::File(p) => p.display().to_string(),
+        }
+    }
+
+    /// Reads the whole input into the hasher. The hasher is not finalized.
+    pub fn read_into_hasher(
+        &self,
+        hasher: &mut impl DynHasher,
+        reader: InputReader,
+    ) -> anyhow::Result<()> {
+        self.read_into_hashers(std::slice::from_mut(hasher), reader)
+    }
+
+    /// Reads the whole input once, and writes every buffer into all the hashers. The hashers are not finalized.
+    pub fn read_into_hashers(
+        &self,
+        hashers: &mut [impl DynHasher],
+        reader: InputReader,
+    ) -> anyhow::Result<()> {
+        match self {
+            InputSource::Stdin => reader.read_into_hashers(hashers, std::io::stdin()),
+            InputSource::File(p) => {
+                check_file(p)?;
+                // Some special files claim to be empty while they aren't, so only lengths of files with content are announced
+                let len = std::fs::metadata(p)
+                    .ok()
+                    .filter(|m| m.is_file() && m.len() > 0)
+                    .map(|m| m.len());
+                if let ([hasher], true) = (&mut *hashers, reader.lets_hashers_read_files()) {
+                    if hasher
+                        .write_file(p)
+                        .context(format!("Reading file failed: {}", p.display()))?
+                    {
+                        return Ok(());
+                    }
+                }
+                if let Some(len) = len {
+                    hashers.iter_mut().for_each(|h| h.begin_input(len));
+                }
+                reader
+                    .read_file_into_hashers(hashers, p)
+                    .context(format!("Reading file failed: {}", p.display()))?;
+                hashers
+                    .iter_mut()
+                    .try_for_each(|h| h.end_input())
+                    .context(format!("Reading file failed: {}", p.display()))
+            }
+        }
+    }
+
+    /// Reads the whole input into memory
+    pub fn read_to_end(&self) -> anyhow::Result<Vec<u8>> {
+        let mut result = Vec::new();
+        match self {
+            InputSource::Stdin => {
+                std::io::stdin().lock().read_to_end(&mut result)?;
+            }
+            InputSource::File(p) => {
+                open_file(p)?
+                    .read_to_end(&mut result)
+                    .context(format!("Reading file failed: {}", p.display()))?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Hashes the whole input and returns the digest. On failure, the hasher is reset before returning.
+/// If `recursive` is set, directories are hashed as a tree, see [`crate::tree_hash`].
+pub fn hash_input(
+    hasher: &mut impl DynHasher,
+    input: &InputSource,
+    recursive: bool,
+    reader: InputReader,
+) -> anyhow::Result<Vec<u8>> {
+    let result = match input {
+        InputSource::File(p) if recursive && p.is_dir() => tree_digest(hasher, p, reader),
+        _ => input
+            .read_into_hasher(hasher, reader)
+            .map(|()| hasher.finalize_and_reset()),
+    };
+
+    match result {
+        Ok(digest) => Ok(digest),
+        Err(e) => {
+            // Discard whatever was written before the failure
+            let _ = hasher.finalize_and_reset();
+            Err(e)
+        }
+    }
+}
+
+/// Hashes the whole input like [`hash_input`], but the files of a directory are hashed by `jobs` workers,
+/// each with its own hasher from `make_hasher`. See [`tree_digest_with_jobs`].
+pub fn hash_input_with_jobs<H: DynHasher>(
+    hasher: &mut impl DynHasher,
+    input: &InputSource,
+    recursive: bool,
+    reader: InputReader,
+    jobs: NonZeroUsize,
+    make_hasher: impl Fn() -> anyhow::Result<H> + Sync,
+) -> anyhow::Result<Vec<u8>> {
+    match input {
+        InputSource::File(p) if recursive && jobs.get() > 1 && p.is_dir() => {
+            let result = tree_digest_with_jobs(hasher, p, reader, jobs, make_hasher);
+            if result.is_err() {
+                // Discard whatever was written before the failure
+                let _ = hasher.finalize_and_reset();
+            }
+            result
+        }
+        _ => hash_input(hasher, input, recursive, reader),
+    }
+}
+
+/// Hashes the whole input with all the hashers, and returns their digests in the same order.
+/// Files and stdin are read once for all the hashers, and so are the files of directories,
+/// which are hashed as trees with `recursive`. On failure, all the hashers are reset before returning.
+pub fn hash_input_with_all(
+    hashers: &mut [impl DynHasher],
+    input: &InputSource,
+    recursive: bool,
+    reader: InputReader,
+) -> anyhow::Result<Vec<Vec<u8>>> {
+    let result = match input {
+        InputSource::File(p) if recursive && p.is_dir() => tree_digests(hashers, p, reader),
+        _ => input.read_into_hashers(hashers, reader).map(|()| {
+            hashers
+                .iter_mut()
+                .map(|hasher| hasher.finalize_and_reset())
+                .collect()
+        }),
+    };
+
+    if result.is_err() {
+        // Discard whatever was written before the failure
+        for hasher in hashers.iter_mut() {
+            let _ = hasher.finalize_and_reset();
+        }
+    }
+
+    result
+}
+
+/// Hashes all inputs as the elements of one tuple, in order, and returns the digest
+pub fn hash_tuple(
+    hasher: &mut impl DynHasher,
+    inputs: &[InputSource],
+    reader: InputReader,
+) -> anyhow::Result<Vec<u8>> {
+    for input in inputs {
+        input.read_into_hasher(hasher, reader)?;
+        hasher.end_tuple_element()?;
+    }
+
+    Ok(hasher.finalize_and_reset())
+}
+
+pub fn open_file(p: impl AsRef<Path>) -> anyhow::Result<File> {
+    let p = p.as_ref();
+    check_file(p)?;
+
+    let f = File::open(p).context(format!("Opening file failed: {}", p.display()))?;
+
+    Ok(f)
+}
+
+/// Checks that the path exists, and that it is a file
+pub fn check_file(p: &Path) -> anyhow::Result<()> {
+    if !p.exists() {
+        return Err(anyhow::anyhow!("File not found: {}", p.display()));
+    }
+
+    if !p.is_file() {
+        return Err(anyhow::anyhow!(
+            "Path provided is not a file or unreadable: {}",
+            p.display()
+        ));
+    }
+
+    Ok(())
+}
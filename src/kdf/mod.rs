@@ -0,0 +1,301 @@
+//! Key derivation from passwords, with Argon2, scrypt, PBKDF2 and bcrypt.
+//!
+//! The derived key is either printed as raw bytes, in any of the output formats, or encoded in a PHC string,
+//! like `$argon2id$v=19$m=19456,t=2,p=1$<salt>$<key>`, which carries the parameters and the salt with the key.
+//! bcrypt has no PHC form, so it's encoded in its own modular crypt format, `$2b$<cost>$<salt><key>`, instead.
+//! These strings can be checked against passwords with [`verify`].
+
+pub mod options;
+pub mod verify;
+
+use std::{collections::BTreeMap, path::Path};
+
+use base64::Engine;
+use password_hash::{
+    rand_core::{OsRng, RngCore},
+    PasswordHasher, SaltString,
+};
+
+use crate::{
+    hasher::options::traits::HashingOptions,
+    input::InputSource,
+    output::convert_output,
+    program_options::{KdfAlgorithm, THashOptions},
+};
+use options::{Argon2Options, BcryptOptions, Pbkdf2Options, ScryptOptions, BCRYPT_SALT_SIZE};
+
+/// The size of the encoded key at the end of a bcrypt string, after the salt
+const BCRYPT_ENCODED_KEY_SIZE: usize = 31;
+
+pub trait KeyDerivation {
+    /// Derives the raw bytes of the key. A salt must have been given.
+    fn derive(&self, password: &[u8]) -> anyhow::Result<Vec<u8>>;
+
+    /// Derives the key and encodes it with its parameters and salt, as a PHC string, or in the format of bcrypt.
+    /// A random salt is used if none was given.
+    fn encode(&self, password: &[u8]) -> anyhow::Result<String>;
+}
+
+pub trait Argon2Variant {
+    const ALGORITHM: argon2::Algorithm;
+    const NAME: &'static str;
+}
+
+macro_rules! impl_argon2_variant {
+    ($variant:ident, $algorithm:expr, $name:expr) => {
+        pub struct $variant;
+
+        impl Argon2Variant for $variant {
+            const ALGORITHM: argon2::Algorithm = $algorithm;
+            const NAME: &'static str = $name;
+        }
+    };
+}
+
+impl_argon2_variant!(Argon2d, argon2::Algorithm::Argon2d, "Argon2d");
+impl_argon2_variant!(Argon2i, argon2::Algorithm::Argon2i, "Argon2i");
+impl_argon2_variant!(Argon2id, argon2::Algorithm::Argon2id, "Argon2id");
+
+/// The hash function of HMAC in PBKDF2
+pub trait Pbkdf2Variant {
+    const ALGORITHM: pbkdf2::Algorithm;
+    const NAME: &'static str;
+    /// The output size of the hash function, which is the default size of the derived key
+    const OUTPUT_SIZE: usize;
+
+    fn pbkdf2(password: &[u8], salt: &[u8], rounds: u32, output: &mut [u8]);
+}
+
+macro_rules! impl_pbkdf2_variant {
+    ($variant:ident, $hash:ty, $algorithm:expr, $output_size:expr, $name:expr) => {
+        pub struct $variant;
+
+        impl Pbkdf2Variant for $variant {
+            const ALGORITHM: pbkdf2::Algorithm = $algorithm;
+            const NAME: &'static str = $name;
+            const OUTPUT_SIZE: usize = $output_size;
+
+            fn pbkdf2(password: &[u8], salt: &[u8], rounds: u32, output: &mut [u8]) {
+                pbkdf2::pbkdf2_hmac::<$hash>(password, salt, rounds, output)
+            }
+        }
+    };
+}
+
+impl_pbkdf2_variant!(
+    Pbkdf2Sha1,
+    sha1::Sha1,
+    pbkdf2::Algorithm::Pbkdf2Sha1,
+    20,
+    "Pbkdf2Sha1"
+);
+impl_pbkdf2_variant!(
+    Pbkdf2Sha256,
+    sha2::Sha256,
+    pbkdf2::Algorithm::Pbkdf2Sha256,
+    32,
+    "Pbkdf2Sha256"
+);
+impl_pbkdf2_variant!(
+    Pbkdf2Sha512,
+    sha2::Sha512,
+    pbkdf2::Algorithm::Pbkdf2Sha512,
+    64,
+    "Pbkdf2Sha512"
+);
+
+pub fn make_key_derivation(
+    algorithm: KdfAlgorithm,
+    options: BTreeMap<String, String>,
+) -> anyhow::Result<Box<dyn KeyDerivation>> {
+    let result: Box<dyn KeyDerivation> = match algorithm {
+        KdfAlgorithm::Argon2d => Box::new(Argon2Options::<Argon2d>::parse(options)?),
+        KdfAlgorithm::Argon2i => Box::new(Argon2Options::<Argon2i>::parse(options)?),
+        KdfAlgorithm::Argon2id => Box::new(Argon2Options::<Argon2id>::parse(options)?),
+        KdfAlgorithm::Bcrypt => Box::new(BcryptOptions::parse(options)?),
+        KdfAlgorithm::Pbkdf2Sha1 => Box::new(Pbkdf2Options::<Pbkdf2Sha1>::parse(options)?),
+        KdfAlgorithm::Pbkdf2Sha256 => Box::new(Pbkdf2Options::<Pbkdf2Sha256>::parse(options)?),
+        KdfAlgorithm::Pbkdf2Sha512 => Box::new(Pbkdf2Options::<Pbkdf2Sha512>::parse(options)?),
+        KdfAlgorithm::Scrypt => Box::new(ScryptOptions::parse(options)?),
+    };
+
+    Ok(result)
+}
+
+/// Derives a key from the password in the given file, or stdin, and returns what should be printed
+pub fn run_kdf(
+    args: &THashOptions,
+    algorithm: KdfAlgorithm,
+    password_file: Option<&Path>,
+    phc: bool,
+) -> anyhow::Result<Vec<u8>> {
+    if args.iters().get() != 1 {
+        return Err(anyhow::anyhow!(
+            "Iterations cannot be used with key derivation. The cost of every algorithm is set with its options"
+        ));
+    }
+
+    let key_derivation = make_key_derivation(algorithm, args.options()?)?;
+
+    let password = match password_file {
+        Some(p) => InputSource::File(p.to_path_buf()),
+        None => InputSource::Stdin,
+    }
+    .read_to_end()?;
+
+    if phc {
+        let encoded = key_derivation.encode(&password)?;
+        Ok(format!("{encoded}\n").into_bytes())
+    } else {
+        let key = key_derivation.derive(&password)?;
+        Ok(convert_output(key, args.output_format))
+    }
+}
+
+fn required_salt<'a>(salt: &'a Option<Vec<u8>>, algo_name: &str) -> anyhow::Result<&'a [u8]> {
+    salt.as_deref().ok_or_else(|| {
+        anyhow::anyhow!(
+            "{algo_name} requires a salt for raw output, with option `salt`. A random salt is only generated with --phc"
+        )
+    })
+}
+
+fn phc_salt(salt: &Option<Vec<u8>>) -> anyhow::Result<SaltString> {
+    match salt {
+        Some(salt) => SaltString::encode_b64(salt)
+            .map_err(|e| anyhow::anyhow!("The salt cannot be encoded in a PHC string: {e}")),
+        None => Ok(SaltString::generate(&mut OsRng)),
+    }
+}
+
+impl<V: Argon2Variant> Argon2Options<V> {
+    fn argon2(&self) -> anyhow::Result<argon2::Argon2<'static>> {
+        let params = argon2::Params::new(
+            self.memory,
+            self.iterations,
+            self.parallelism,
+            Some(self.output_size.get()),
+        )
+        .map_err(|e| anyhow::anyhow!("Invalid parameters for {}: {e}", V::NAME))?;
+
+        Ok(argon2::Argon2::new(
+            V::ALGORITHM,
+            argon2::Version::V0x13,
+            params,
+        ))
+    }
+}
+
+impl<V: Argon2Variant> KeyDerivation for Argon2Options<V> {
+    fn derive(&self, password: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let salt = required_salt(&self.salt, V::NAME)?;
+        let mut result = vec![0; self.output_size.get()];
+        self.argon2()?
+            .hash_password_into(password, salt, &mut result)
+            .map_err(|e| anyhow::anyhow!("{} failed: {e}", V::NAME))?;
+        Ok(result)
+    }
+
+    fn encode(&self, password: &[u8]) -> anyhow::Result<String> {
+        let salt = phc_salt(&self.salt)?;
+        let hash = self
+            .argon2()?
+            .hash_password(password, &salt)
+            .map_err(|e| anyhow::anyhow!("{} failed: {e}", V::NAME))?;
+        Ok(hash.to_string())
+    }
+}
+
+impl ScryptOptions {
+    fn params(&self, output_size: usize) -> anyhow::Result<scrypt::Params> {
+        scrypt::Params::new(self.log_n, self.r, self.p, output_size)
+            .map_err(|e| anyhow::anyhow!("Invalid parameters for {}: {e}", Self::algo_name()))
+    }
+}
+
+impl KeyDerivation for ScryptOptions {
+    fn derive(&self, password: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let salt = required_salt(&self.salt, Self::algo_name())?;
+        // The size in the parameters is only used for PHC strings, which limit it.
+        // Raw output can have any size, so the recommended size is there only to pass validation.
+        let params = self.params(scrypt::Params::RECOMMENDED_LEN)?;
+        let mut result = vec![0; self.output_size.get()];
+        scrypt::scrypt(password, salt, &params, &mut result)
+            .map_err(|e| anyhow::anyhow!("{} failed: {e}", Self::algo_name()))?;
+        Ok(result)
+    }
+
+    fn encode(&self, password: &[u8]) -> anyhow::Result<String> {
+        let salt = phc_salt(&self.salt)?;
+        let params = self.params(self.output_size.get())?;
+        let hash = scrypt::Scrypt
+            .hash_password_customized(password, None, None, params, &salt)
+            .map_err(|e| anyhow::anyhow!("{} failed: {e}", Self::algo_name()))?;
+        Ok(hash.to_string())
+    }
+}
+
+impl<V: Pbkdf2Variant> KeyDerivation for Pbkdf2Options<V> {
+    fn derive(&self, password: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let salt = required_salt(&self.salt, V::NAME)?;
+        let mut result = vec![0; self.output_size.get()];
+        V::pbkdf2(password, salt, self.rounds, &mut result);
+        Ok(result)
+    }
+
+    fn encode(&self, password: &[u8]) -> anyhow::Result<String> {
+        let salt = phc_salt(&self.salt)?;
+        let params = pbkdf2::Params {
+            rounds: self.rounds,
+            output_length: self.output_size.get(),
+        };
+        let hash = pbkdf2::Pbkdf2
+            .hash_password_customized(password, Some(V::ALGORITHM.ident()), None, params, &salt)
+            .map_err(|e| anyhow::anyhow!("{} failed: {e}", V::NAME))?;
+        Ok(hash.to_string())
+    }
+}
+
+impl BcryptOptions {
+    /// The whole bcrypt string, with the cost, salt and key
+    fn bcrypt_string(
+        &self,
+        password: &[u8],
+        salt: [u8; BCRYPT_SALT_SIZE],
+    ) -> anyhow::Result<String> {
+        let parts = bcrypt::hash_with_salt(password, self.cost, salt)
+            .map_err(|e| anyhow::anyhow!("{} failed: {e}", Self::algo_name()))?;
+        Ok(parts.format_for_version(bcrypt::Version::TwoB))
+    }
+}
+
+impl KeyDerivation for BcryptOptions {
+    fn derive(&self, password: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let salt = self.salt.ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} requires a salt for raw output, with option `salt`. A random salt is only generated with --phc",
+                Self::algo_name()
+            )
+        })?;
+
+        // bcrypt only exposes its output encoded, after the salt, in its own variant of base64
+        let encoded = self.bcrypt_string(password, salt)?;
+        let encoded_key = &encoded[encoded.len() - BCRYPT_ENCODED_KEY_SIZE..];
+        let result = bcrypt::BASE_64
+            .decode(encoded_key)
+            .expect("bcrypt always encodes its output correctly");
+        Ok(result)
+    }
+
+    fn encode(&self, password: &[u8]) -> anyhow::Result<String> {
+        let salt = self.salt.unwrap_or_else(|| {
+            let mut salt = [0; BCRYPT_SALT_SIZE];
+            OsRng.fill_bytes(&mut salt);
+            salt
+        });
+        self.bcrypt_string(password, salt)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,267 @@
+use std::{collections::BTreeMap, marker::PhantomData, num::NonZeroUsize};
+
+use crate::hasher::options::{
+    common::{parse_bytes_option, parse_option, BytesEncoding, OUTPUT_SIZE_KEY, SALT_KEY},
+    traits::HashingOptions,
+};
+
+use super::{Argon2Variant, Pbkdf2Variant};
+
+pub const MEMORY_KEY: &str = "memory";
+pub const ITERATIONS_KEY: &str = "iterations";
+pub const PARALLELISM_KEY: &str = "parallelism";
+pub const LOG_N_KEY: &str = "log-n";
+pub const R_KEY: &str = "r";
+pub const P_KEY: &str = "p";
+pub const ROUNDS_KEY: &str = "rounds";
+pub const COST_KEY: &str = "cost";
+
+pub const DEFAULT_KDF_OUTPUT_SIZE: usize = 32;
+/// The number of rounds recommended by OWASP for PBKDF2-HMAC-SHA256
+pub const DEFAULT_PBKDF2_ROUNDS: u32 = 600_000;
+pub const BCRYPT_SALT_SIZE: usize = 16;
+
+fn parse_salt(options: &BTreeMap<String, String>) -> anyhow::Result<Option<Vec<u8>>> {
+    parse_bytes_option(options, SALT_KEY, BytesEncoding::Hex)
+}
+
+fn parse_output_size(
+    options: &BTreeMap<String, String>,
+    default_output_size: usize,
+) -> anyhow::Result<NonZeroUsize> {
+    let default_output_size =
+        NonZeroUsize::new(default_output_size).expect("Default output size is never zero");
+
+    parse_option::<NonZeroUsize>(options, OUTPUT_SIZE_KEY, default_output_size)
+}
+
+fn salt_description() -> (String, String) {
+    (
+        SALT_KEY.to_string(),
+        "The salt, which is required for raw output. With --phc, a random salt is generated if none is given. The salt is hex, or prefixed with base64:, text: or file:".to_string(),
+    )
+}
+
+fn output_size_description(default_output_size: usize) -> (String, String) {
+    (
+        OUTPUT_SIZE_KEY.to_string(),
+        format!("The size of the derived key in bytes. Default is {default_output_size}"),
+    )
+}
+
+pub struct Argon2Options<V: Argon2Variant> {
+    /// The memory size in KiB
+    pub memory: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    pub salt: Option<Vec<u8>>,
+    pub output_size: NonZeroUsize,
+    _phantom: PhantomData<V>,
+}
+
+impl<V: Argon2Variant> TryFrom<BTreeMap<String, String>> for Argon2Options<V> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            memory: parse_option(&options, MEMORY_KEY, argon2::Params::DEFAULT_M_COST)?,
+            iterations: parse_option(&options, ITERATIONS_KEY, argon2::Params::DEFAULT_T_COST)?,
+            parallelism: parse_option(&options, PARALLELISM_KEY, argon2::Params::DEFAULT_P_COST)?,
+            salt: parse_salt(&options)?,
+            output_size: parse_output_size(&options, DEFAULT_KDF_OUTPUT_SIZE)?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<V: Argon2Variant> HashingOptions for Argon2Options<V> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [
+            (
+                MEMORY_KEY.to_string(),
+                format!(
+                    "The memory size in KiB. Default is {}",
+                    argon2::Params::DEFAULT_M_COST
+                ),
+            ),
+            (
+                ITERATIONS_KEY.to_string(),
+                format!(
+                    "The number of passes over the memory. Default is {}",
+                    argon2::Params::DEFAULT_T_COST
+                ),
+            ),
+            (
+                PARALLELISM_KEY.to_string(),
+                format!(
+                    "The number of lanes. Default is {}",
+                    argon2::Params::DEFAULT_P_COST
+                ),
+            ),
+            salt_description(),
+            output_size_description(DEFAULT_KDF_OUTPUT_SIZE),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        V::NAME
+    }
+}
+
+pub struct ScryptOptions {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+    pub salt: Option<Vec<u8>>,
+    pub output_size: NonZeroUsize,
+}
+
+impl TryFrom<BTreeMap<String, String>> for ScryptOptions {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            log_n: parse_option(&options, LOG_N_KEY, scrypt::Params::RECOMMENDED_LOG_N)?,
+            r: parse_option(&options, R_KEY, scrypt::Params::RECOMMENDED_R)?,
+            p: parse_option(&options, P_KEY, scrypt::Params::RECOMMENDED_P)?,
+            salt: parse_salt(&options)?,
+            output_size: parse_output_size(&options, DEFAULT_KDF_OUTPUT_SIZE)?,
+        })
+    }
+}
+
+impl HashingOptions for ScryptOptions {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [
+            (
+                LOG_N_KEY.to_string(),
+                format!(
+                    "The base 2 logarithm of the cost parameter N. Default is {}",
+                    scrypt::Params::RECOMMENDED_LOG_N
+                ),
+            ),
+            (
+                R_KEY.to_string(),
+                format!(
+                    "The block size parameter. Default is {}",
+                    scrypt::Params::RECOMMENDED_R
+                ),
+            ),
+            (
+                P_KEY.to_string(),
+                format!(
+                    "The parallelization parameter. Default is {}",
+                    scrypt::Params::RECOMMENDED_P
+                ),
+            ),
+            salt_description(),
+            output_size_description(DEFAULT_KDF_OUTPUT_SIZE),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        "Scrypt"
+    }
+}
+
+pub struct Pbkdf2Options<V: Pbkdf2Variant> {
+    pub rounds: u32,
+    pub salt: Option<Vec<u8>>,
+    pub output_size: NonZeroUsize,
+    _phantom: PhantomData<V>,
+}
+
+impl<V: Pbkdf2Variant> TryFrom<BTreeMap<String, String>> for Pbkdf2Options<V> {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let rounds = parse_option(&options, ROUNDS_KEY, DEFAULT_PBKDF2_ROUNDS)?;
+        if rounds == 0 {
+            return Err(anyhow::anyhow!(
+                "Option `{ROUNDS_KEY}` for {} must be a positive number",
+                V::NAME
+            ));
+        }
+
+        Ok(Self {
+            rounds,
+            salt: parse_salt(&options)?,
+            output_size: parse_output_size(&options, V::OUTPUT_SIZE)?,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<V: Pbkdf2Variant> HashingOptions for Pbkdf2Options<V> {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [
+            (
+                ROUNDS_KEY.to_string(),
+                format!("The number of rounds of HMAC. Default is {DEFAULT_PBKDF2_ROUNDS}"),
+            ),
+            salt_description(),
+            output_size_description(V::OUTPUT_SIZE),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        V::NAME
+    }
+}
+
+pub struct BcryptOptions {
+    pub cost: u32,
+    pub salt: Option<[u8; BCRYPT_SALT_SIZE]>,
+}
+
+impl TryFrom<BTreeMap<String, String>> for BcryptOptions {
+    type Error = anyhow::Error;
+
+    fn try_from(options: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        let salt = parse_salt(&options)?
+            .map(|salt| {
+                <[u8; BCRYPT_SALT_SIZE]>::try_from(salt.as_slice()).map_err(|_| {
+                    anyhow::anyhow!(
+                        "Option `{SALT_KEY}` for bcrypt must be {BCRYPT_SALT_SIZE} bytes, but {} bytes were provided",
+                        salt.len()
+                    )
+                })
+            })
+            .transpose()?;
+
+        Ok(Self {
+            cost: parse_option(&options, COST_KEY, bcrypt::DEFAULT_COST)?,
+            salt,
+        })
+    }
+}
+
+impl HashingOptions for BcryptOptions {
+    fn options_descriptions() -> BTreeMap<String, String> {
+        [
+            (
+                COST_KEY.to_string(),
+                format!(
+                    "The base 2 logarithm of the number of rounds, from 4 to 31. Default is {}",
+                    bcrypt::DEFAULT_COST
+                ),
+            ),
+            (
+                SALT_KEY.to_string(),
+                format!("The salt of {BCRYPT_SALT_SIZE} bytes, which is required for raw output. With --phc, a random salt is generated if none is given. The salt is hex, or prefixed with base64:, text: or file:"),
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn algo_name() -> &'static str {
+        "Bcrypt"
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    hasher::options::common::{OUTPUT_SIZE_KEY, SALT_KEY},
+    program_options::KdfAlgorithm,
+};
+
+use super::{
+    make_key_derivation,
+    options::{COST_KEY, ITERATIONS_KEY, LOG_N_KEY, MEMORY_KEY, P_KEY, ROUNDS_KEY, R_KEY},
+    verify,
+};
+
+fn make_options(options: &[(&str, &str)]) -> BTreeMap<String, String> {
+    options
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn argon2() {
+    // From the reference implementation of Argon2
+    let options = make_options(&[
+        (MEMORY_KEY, "256"),
+        (ITERATIONS_KEY, "2"),
+        (SALT_KEY, "text:somesalt"),
+    ]);
+
+    let test_vectors = [
+        (
+            KdfAlgorithm::Argon2i,
+            "89e9029f4637b295beb027056a7336c414fadd43f6b208645281cb214a56452f",
+            "$argon2i$v=19$m=256,t=2,p=1$c29tZXNhbHQ$iekCn0Y3spW+sCcFanM2xBT63UP2sghkUoHLIUpWRS8",
+        ),
+        (
+            KdfAlgorithm::Argon2id,
+            "9dfeb910e80bad0311fee20f9c0e2b12c17987b4cac90c2ef54d5b3021c68bfe",
+            "$argon2id$v=19$m=256,t=2,p=1$c29tZXNhbHQ$nf65EOgLrQMR/uIPnA4rEsF5h7TKyQwu9U1bMCHGi/4",
+        ),
+    ];
+
+    for (algorithm, expected_key, expected_phc) in test_vectors {
+        let key_derivation = make_key_derivation(algorithm, options.clone()).unwrap();
+        assert_eq!(
+            hex::encode(key_derivation.derive(b"password").unwrap()),
+            expected_key
+        );
+        assert_eq!(key_derivation.encode(b"password").unwrap(), expected_phc);
+    }
+
+    let mut argon2d_options = options.clone();
+    argon2d_options.insert(OUTPUT_SIZE_KEY.to_string(), "16".to_string());
+    let argon2d_key = make_key_derivation(KdfAlgorithm::Argon2d, argon2d_options)
+        .unwrap()
+        .derive(b"password")
+        .unwrap();
+    assert_eq!(argon2d_key.len(), 16);
+}
+
+#[test]
+fn scrypt() {
+    // From RFC 7914
+    let options = make_options(&[
+        (LOG_N_KEY, "10"),
+        (R_KEY, "8"),
+        (P_KEY, "16"),
+        (SALT_KEY, "text:NaCl"),
+        (OUTPUT_SIZE_KEY, "64"),
+    ]);
+    let key_derivation = make_key_derivation(KdfAlgorithm::Scrypt, options).unwrap();
+    assert_eq!(
+        hex::encode(key_derivation.derive(b"password").unwrap()),
+        "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b3731622eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"
+    );
+    assert_eq!(
+        key_derivation.encode(b"password").unwrap(),
+        "$scrypt$ln=10,r=8,p=16$TmFDbA$/bq+HJ00cgB4VucZDQHp/nxq18vII3gw53N2Y0s3MWIurzDZLiKjiG/xCSedmDDaxyevuUqD7m2DYMvfoswGQA"
+    );
+}
+
+#[test]
+fn pbkdf2() {
+    let test_vectors = [
+        // From RFC 6070
+        (
+            KdfAlgorithm::Pbkdf2Sha1,
+            "ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957",
+        ),
+        // RFC 6070 only covers SHA-1. These take the same inputs, and were generated with
+        // Python's hashlib.pbkdf2_hmac, with the digest size as the key length.
+        (
+            KdfAlgorithm::Pbkdf2Sha256,
+            "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43",
+        ),
+        (
+            KdfAlgorithm::Pbkdf2Sha512,
+            "e1d9c16aa681708a45f5c7c4e215ceb66e011a2e9f0040713f18aefdb866d53cf76cab2868a39b9f7840edce4fef5a82be67335c77a6068e04112754f27ccf4e",
+        ),
+    ];
+
+    let options = make_options(&[(ROUNDS_KEY, "2"), (SALT_KEY, "text:salt")]);
+    for (algorithm, expected_key) in test_vectors {
+        let key_derivation = make_key_derivation(algorithm, options.clone()).unwrap();
+        assert_eq!(
+            hex::encode(key_derivation.derive(b"password").unwrap()),
+            expected_key
+        );
+    }
+
+    let options = make_options(&[(ROUNDS_KEY, "1000"), (SALT_KEY, "text:salt")]);
+    let key_derivation = make_key_derivation(KdfAlgorithm::Pbkdf2Sha256, options).unwrap();
+    assert_eq!(
+        key_derivation.encode(b"password").unwrap(),
+        "$pbkdf2-sha256$i=1000,l=32$c2FsdA$YywoEuRtRgQQK6dhjp1tfS+BKPYma0oDJk0qBGC33LM"
+    );
+}
+
+#[test]
+fn bcrypt() {
+    let options = make_options(&[
+        (COST_KEY, "5"),
+        (SALT_KEY, "2671d48d6cd5c3a6c926140d2f286812"),
+    ]);
+    let key_derivation = make_key_derivation(KdfAlgorithm::Bcrypt, options).unwrap();
+    assert_eq!(
+        key_derivation.encode(b"My S3cre7 P@55w0rd!").unwrap(),
+        "$2b$05$HlFShUxTu4ZHHfOLJwfmCeDj/kuKFKboanXtDJXxCC7aIPTUgxNDe"
+    );
+    assert_eq!(
+        hex::encode(key_derivation.derive(b"My S3cre7 P@55w0rd!").unwrap()),
+        "165066c0c1cc76a72966f14b673104f5c2915568b33c58"
+    );
+}
+
+#[test]
+fn random_salt() {
+    let options = make_options(&[(MEMORY_KEY, "64"), (ITERATIONS_KEY, "1")]);
+    let key_derivation = make_key_derivation(KdfAlgorithm::Argon2id, options).unwrap();
+
+    let first = key_derivation.encode(b"password").unwrap();
+    let second = key_derivation.encode(b"password").unwrap();
+    assert!(first.starts_with("$argon2id$v=19$m=64,t=1,p=1$"));
+    assert_ne!(first, second);
+
+    // Raw output cannot be reproduced without the salt
+    assert!(key_derivation.derive(b"password").is_err());
+}
+
+#[test]
+fn invalid_options() {
+    let invalid_options = [
+        (KdfAlgorithm::Argon2id, make_options(&[(ROUNDS_KEY, "2")])),
+        (KdfAlgorithm::Scrypt, make_options(&[(R_KEY, "x")])),
+        (
+            KdfAlgorithm::Pbkdf2Sha256,
+            make_options(&[(ROUNDS_KEY, "0")]),
+        ),
+        (KdfAlgorithm::Bcrypt, make_options(&[(SALT_KEY, "0011")])),
+    ];
+
+    for (algorithm, options) in invalid_options {
+        assert!(make_key_derivation(algorithm, options).is_err());
+    }
+
+    let options = make_options(&[
+        (COST_KEY, "3"),
+        (SALT_KEY, "00112233445566778899aabbccddeeff"),
+    ]);
+    let key_derivation = make_key_derivation(KdfAlgorithm::Bcrypt, options).unwrap();
+    assert!(key_derivation.derive(b"password").is_err());
+}
+
+#[test]
+fn verify_password() {
+    let hash_strings = [
+        (
+            "password",
+            "$argon2id$v=19$m=256,t=2,p=1$c29tZXNhbHQ$nf65EOgLrQMR/uIPnA4rEsF5h7TKyQwu9U1bMCHGi/4",
+        ),
+        (
+            "password",
+            "$argon2i$v=19$m=256,t=2,p=1$c29tZXNhbHQ$iekCn0Y3spW+sCcFanM2xBT63UP2sghkUoHLIUpWRS8",
+        ),
+        (
+            "password",
+            "$scrypt$ln=10,r=8,p=16$TmFDbA$/bq+HJ00cgB4VucZDQHp/nxq18vII3gw53N2Y0s3MWIurzDZLiKjiG/xCSedmDDaxyevuUqD7m2DYMvfoswGQA",
+        ),
+        (
+            "password",
+            "$pbkdf2-sha256$i=1000,l=32$c2FsdA$YywoEuRtRgQQK6dhjp1tfS+BKPYma0oDJk0qBGC33LM",
+        ),
+        (
+            "My S3cre7 P@55w0rd!",
+            "$2b$05$HlFShUxTu4ZHHfOLJwfmCeDj/kuKFKboanXtDJXxCC7aIPTUgxNDe",
+        ),
+        // From the specification of sha-crypt
+        (
+            "Hello world!",
+            "$5$saltstring$5B8vYYiY.CVt1RlTTf8KbXBH3hsxY/GNooZaBBGWEc5",
+        ),
+        (
+            "Hello world!",
+            "$6$saltstring$svn8UoSVapNtMuq1ukKS4tPQd8iKwSMHWjl/O817G3uBnIFNjnQJuesI68u4OTLiBFdcbYEdFCoEOfaS35inz1",
+        ),
+        (
+            "Hello world!",
+            "$6$rounds=10000$saltstringsaltst$OW1/O6BYHV6BcXZu8QVeXbDWra3Oeqh0sbHbbMCVNSnCM/UrjmM0Dp8vOuZeHBy/YTBmSK6H9qs/y3RnOaw5v.",
+        ),
+        // Too few rounds are clamped to 1000, so these are the hashes of the specification
+        // with `rounds=1000`, which it gives for `rounds=10`
+        (
+            "the minimum number is still observed",
+            "$5$rounds=10$roundstoolow$yfvwcWrQ8l/K0DAWyuPMDNHpIVlTQebY9l/gL972bIC",
+        ),
+        (
+            "the minimum number is still observed",
+            "$6$rounds=10$roundstoolow$kUMsbe306n21p9R.FRkW3IGn.S9NPN0x50YhH1xhLsPuWGsUSklZt58jaTfF4ZEQpyUNGc0dqbpBYYBaHHrsX.",
+        ),
+    ];
+
+    for (password, hash_string) in hash_strings {
+        assert!(verify::verify_password(hash_string, password.as_bytes()).unwrap());
+        assert!(!verify::verify_password(hash_string, b"wrong password").unwrap());
+    }
+
+    let invalid_hash_strings = [
+        "",
+        "password",
+        "$1$saltstring$hash",
+        "$6$rounds=x$saltstring$hash",
+        "$6$rounds=$saltstring$hash",
+        "$6$rounds=-5$saltstring$hash",
+        "$2b$05$short",
+        "$md5$v=1$c29tZXNhbHQ$aGFzaA",
+    ];
+
+    for hash_string in invalid_hash_strings {
+        assert!(verify::verify_password(hash_string, b"password").is_err());
+    }
+}
+
+#[test]
+fn verify_derived_key() {
+    let options = make_options(&[(LOG_N_KEY, "4"), (R_KEY, "8")]);
+    let encoded = make_key_derivation(KdfAlgorithm::Scrypt, options)
+        .unwrap()
+        .encode(b"password")
+        .unwrap();
+
+    assert!(verify::verify_password(&encoded, b"password").unwrap());
+    assert!(!verify::verify_password(&encoded, b"Password").unwrap());
+}
+
+#[test]
+fn piped_password_line_ending() {
+    let expected: [(&[u8], &[u8]); 6] = [
+        (b"password\n", b"password"),
+        (b"password\r\n", b"password"),
+        (b"password", b"password"),
+        (b"password\n\n", b"password\n"),
+        (b"pass\rword\r", b"pass\rword\r"),
+        (b"\n", b""),
+    ];
+
+    for (piped, password) in expected {
+        assert_eq!(verify::strip_line_ending(piped.to_vec()), password);
+    }
+}
@@ -0,0 +1,137 @@
+//! Verification of passwords against stored password hashes: PHC strings of Argon2, scrypt and PBKDF2,
+//! and the modular crypt format of bcrypt (`$2b$`, and the older `$2a$`, `$2x$` and `$2y$`),
+//! sha256-crypt (`$5$`) and sha512-crypt (`$6$`). All comparisons of the keys are done in constant time.
+
+use std::{io::IsTerminal, path::Path};
+
+use password_hash::{PasswordHash, PasswordVerifier};
+use subtle::ConstantTimeEq;
+
+use crate::input::InputSource;
+
+const PASSWORD_PROMPT: &str = "Password: ";
+
+/// Returns whether the password matches the hash string. Fails if the hash string is malformed or unsupported.
+pub fn verify_password(hash_string: &str, password: &[u8]) -> anyhow::Result<bool> {
+    let hash_string = hash_string.trim();
+
+    if ["$2a$", "$2b$", "$2x$", "$2y$"]
+        .iter()
+        .any(|prefix| hash_string.starts_with(prefix))
+    {
+        return bcrypt::verify(password, hash_string)
+            .map_err(|e| anyhow::anyhow!("Invalid bcrypt string: {e}"));
+    }
+
+    if hash_string.starts_with("$5$") || hash_string.starts_with("$6$") {
+        return verify_sha_crypt(hash_string, password);
+    }
+
+    let phc_hash = PasswordHash::new(hash_string).map_err(|e| {
+        anyhow::anyhow!(
+            "The hash string is neither a valid PHC string nor a supported crypt string: {e}"
+        )
+    })?;
+
+    let algorithm = phc_hash.algorithm.as_str();
+    let result = if algorithm.starts_with("argon2") {
+        argon2::Argon2::default().verify_password(password, &phc_hash)
+    } else if algorithm == scrypt::ALG_ID.as_str() {
+        scrypt::Scrypt.verify_password(password, &phc_hash)
+    } else if algorithm.starts_with("pbkdf2") {
+        pbkdf2::Pbkdf2.verify_password(password, &phc_hash)
+    } else {
+        return Err(anyhow::anyhow!(
+            "Algorithm {algorithm} of the PHC string is not supported"
+        ));
+    };
+
+    match result {
+        Ok(()) => Ok(true),
+        Err(password_hash::Error::Password) => Ok(false),
+        Err(e) => Err(anyhow::anyhow!("Invalid {algorithm} string: {e}")),
+    }
+}
+
+/// Parses the rounds of a sha-crypt string. Like glibc, rounds out of the supported range
+/// are clamped to it, instead of rejected.
+fn parse_sha_crypt_rounds(rounds: &str) -> Option<usize> {
+    let rounds = rounds.strip_prefix("rounds=")?;
+    if rounds.is_empty() || !rounds.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    // Only digits are left, so parsing fails only when the number is too large
+    let rounds = rounds.parse::<usize>().unwrap_or(usize::MAX);
+    Some(rounds.clamp(sha_crypt::ROUNDS_MIN, sha_crypt::ROUNDS_MAX))
+}
+
+/// Verifies a sha256-crypt or sha512-crypt string, `$<5 or 6>$[rounds=<N>$]<salt>$<hash>`
+fn verify_sha_crypt(hash_string: &str, password: &[u8]) -> anyhow::Result<bool> {
+    let invalid = || anyhow::anyhow!("Invalid sha-crypt string: {hash_string}");
+
+    let fields = hash_string.split('$').collect::<Vec<_>>();
+    let (id, rounds, salt, hash) = match fields.as_slice() {
+        ["", id, rounds, salt, hash] => {
+            let rounds = parse_sha_crypt_rounds(rounds).ok_or_else(invalid)?;
+            (*id, rounds, *salt, *hash)
+        }
+        ["", id, salt, hash] => (*id, sha_crypt::ROUNDS_DEFAULT, *salt, *hash),
+        _ => return Err(invalid()),
+    };
+
+    let rounds_error = |e| anyhow::anyhow!("Invalid rounds in sha-crypt string: {e:?}");
+    let expected = match id {
+        "5" => sha_crypt::sha256_crypt_b64(
+            password,
+            salt.as_bytes(),
+            &sha_crypt::Sha256Params::new(rounds).map_err(rounds_error)?,
+        ),
+        "6" => sha_crypt::sha512_crypt_b64(
+            password,
+            salt.as_bytes(),
+            &sha_crypt::Sha512Params::new(rounds).map_err(rounds_error)?,
+        ),
+        _ => return Err(invalid()),
+    }
+    .map_err(|e| anyhow::anyhow!("sha-crypt failed: {e:?}"))?;
+
+    Ok(expected.as_bytes().ct_eq(hash.as_bytes()).into())
+}
+
+/// Reads the password from the file if one is given. Otherwise, the password is prompted for
+/// without echo if stdin is a terminal, or all of stdin is read, without the line ending that
+/// `echo` and other programs that pipe a password add.
+fn read_password(password_file: Option<&Path>) -> anyhow::Result<Vec<u8>> {
+    if let Some(p) = password_file {
+        return InputSource::File(p.to_path_buf()).read_to_end();
+    }
+
+    if std::io::stdin().is_terminal() {
+        let password = rpassword::prompt_password(PASSWORD_PROMPT)?;
+        return Ok(password.into_bytes());
+    }
+
+    Ok(strip_line_ending(InputSource::Stdin.read_to_end()?))
+}
+
+/// Removes one trailing `\n` or `\r\n`, like the prompt does
+pub(super) fn strip_line_ending(mut password: Vec<u8>) -> Vec<u8> {
+    if password.last() == Some(&b'\n') {
+        password.pop();
+        if password.last() == Some(&b'\r') {
+            password.pop();
+        }
+    }
+
+    password
+}
+
+/// Reads the password and verifies it. Returns whether it matched.
+pub fn run_verify_password(
+    hash_string: &str,
+    password_file: Option<&Path>,
+) -> anyhow::Result<bool> {
+    let password = read_password(password_file)?;
+    verify_password(hash_string, &password)
+}
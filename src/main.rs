@@ -1,99 +1,185 @@
+mod bench;
+mod check;
 mod hasher;
 mod hashing_lib;
+mod hkdf;
+mod input;
+mod kdf;
+mod manifest;
+mod multi_hash;
+mod output;
 mod program_options;
+mod reader;
+mod tree_hash;
+mod worker_pool;
 
-use std::io::{Read, Write};
+use std::{io::Write, num::NonZeroUsize, time::Duration};
 
-use anyhow::Context;
-use clap::Parser;
-use hasher::{make_hasher, traits::DynHasher};
-use program_options::OutputFormat;
+use hasher::make_hasher;
+use input::{hash_input_with_jobs, hash_tuple};
+use output::{convert_output, digest_line};
+use program_options::{Command, OutputFormat};
+use worker_pool::run_in_order;
 
 fn main() -> anyhow::Result<()> {
-    let args: program_options::THashOptions = program_options::THashOptions::parse();
+    let args: program_options::THashOptions = program_options::THashOptions::parse_in_order();
 
-    let options = args.options()?;
-
-    let hash_digest = {
-        let mut hasher = make_hasher(args.hash_algo, args.iters(), options)?;
-
-        match args.file {
-            Some(f) => {
-                let reader = open_file(f)?;
-                buffer_into_hasher(&mut hasher, reader)?;
+    match &args.command {
+        Some(Command::Manifest { dir }) => return manifest::run_manifest(&args, dir),
+        Some(Command::Diff { old, new }) => {
+            if !manifest::run_diff(&args, old, new)? {
+                std::process::exit(1);
             }
-            None => {
-                let stdin = std::io::stdin();
-                buffer_into_hasher(&mut hasher, stdin.lock())?
+            return Ok(());
+        }
+        Some(Command::Kdf {
+            algorithm,
+            file,
+            phc,
+        }) => {
+            let output = kdf::run_kdf(&args, *algorithm, file.as_deref(), *phc)?;
+            write_to_stdout(&output);
+            return Ok(());
+        }
+        Some(Command::Hkdf { step, file }) => {
+            let output = hkdf::run_hkdf(&args, *step, file.as_deref())?;
+            write_to_stdout(&output);
+            return Ok(());
+        }
+        Some(Command::Bench {
+            algorithms,
+            sizes,
+            min_time,
+            json,
+        }) => {
+            return bench::run_bench(
+                &args,
+                algorithms,
+                sizes,
+                Duration::from_millis(*min_time),
+                *json,
+            )
+        }
+        Some(Command::VerifyPassword { hash, file }) => {
+            // A mismatch and an error have different exit codes, so that scripts can tell them apart
+            match kdf::verify::run_verify_password(hash, file.as_deref()) {
+                Ok(true) => return Ok(()),
+                Ok(false) => {
+                    eprintln!("thash: the password does not match");
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("Error: {e:?}");
+                    std::process::exit(2);
+                }
             }
         }
-        hasher.finalize_and_reset()
-    };
+        None => (),
+    }
 
-    {
-        let stdout = std::io::stdout();
-        let mut stdout_handle = stdout.lock();
-        let output = convert_output(hash_digest, args.output_format);
-        stdout_handle
-            .write_all(&output)
-            .expect("Writing result to stdout failing");
+    if args.check {
+        if !check::run_check(&args)? {
+            std::process::exit(1);
+        }
+        return Ok(());
     }
 
-    Ok(())
-}
+    if args.all || args.table || args.hash_algos().len() > 1 {
+        return multi_hash::run_multi_hash(&args);
+    }
+
+    let algo = args.hash_algo()?;
+    let options = args.options_for(algo)?;
+    let inputs = args.inputs();
+    let reader = args.input_reader();
+    let jobs = args.jobs();
+
+    let make_worker_hasher = || make_hasher(algo, args.iters(), options.clone());
+    let mut hasher = make_worker_hasher()?;
 
-fn open_file(p: impl AsRef<std::path::Path>) -> anyhow::Result<impl Read> {
-    let p = p.as_ref();
-    if !p.exists() {
-        return Err(anyhow::anyhow!("File not found: {}", p.display()));
+    if args.tuple {
+        let hash_digest = hash_tuple(&mut hasher, &inputs, reader)?;
+        write_to_stdout(&convert_output(hash_digest, args.output_format));
+        return Ok(());
     }
 
-    if !p.is_file() {
+    let with_names = args.with_names || inputs.len() > 1;
+
+    if !with_names {
+        let hash_digest = hash_input_with_jobs(
+            &mut hasher,
+            &inputs[0],
+            args.recursive,
+            reader,
+            jobs,
+            make_worker_hasher,
+        )?;
+        write_to_stdout(&convert_output(hash_digest, args.output_format));
+        return Ok(());
+    }
+
+    if matches!(args.output_format, OutputFormat::Binary) {
         return Err(anyhow::anyhow!(
-            "Path provided is not a file or unreadable: {}",
-            p.display()
+            "Binary output format cannot be used when printing digests with file names"
         ));
     }
 
-    let f = std::fs::File::open(p).context(format!("Opening file failed: {}", p.display()))?;
-
-    Ok(f)
-}
-
-fn buffer_into_hasher(hasher: &mut impl DynHasher, mut source: impl Read) -> anyhow::Result<()> {
-    let mut buffer = [0; 4096];
-
-    loop {
-        let bytes_read = source.read(&mut buffer)?;
+    // Many inputs are hashed in parallel, while the files of a single tree are hashed in parallel
+    let (input_jobs, tree_jobs) = if inputs.len() > 1 {
+        (jobs, NonZeroUsize::MIN)
+    } else {
+        (NonZeroUsize::MIN, jobs)
+    };
 
-        if bytes_read == 0 {
-            break Ok(());
-        }
+    let mut failures = 0;
+    run_in_order(
+        input_jobs,
+        &inputs,
+        make_worker_hasher,
+        |worker_hasher, input| {
+            hash_input_with_jobs(
+                worker_hasher,
+                input,
+                args.recursive,
+                reader,
+                tree_jobs,
+                make_worker_hasher,
+            )
+        },
+        |input, result| {
+            match result {
+                Ok(hash_digest) => {
+                    let line = digest_line(
+                        &hash_digest,
+                        args.output_format,
+                        &input.display_name(),
+                        args.binary,
+                    );
+                    write_to_stdout(line.as_bytes());
+                }
+                Err(e) => {
+                    eprintln!("thash: {}: {:#}", input.display_name(), e);
+                    failures += 1;
+                }
+            }
+            Ok(())
+        },
+    )?;
 
-        hasher.write(&buffer[..bytes_read]);
+    if failures > 0 {
+        return Err(anyhow::anyhow!(
+            "{failures} of {} inputs could not be hashed",
+            inputs.len()
+        ));
     }
-}
 
-fn convert_output(output: Vec<u8>, output_format: OutputFormat) -> Vec<u8> {
-    use base64::prelude::*;
-
-    match output_format {
-        OutputFormat::Binary => output, // We don't add a new line to binary mode
-        OutputFormat::HexLower => newlined(hex::encode(&output)).as_bytes().to_vec(),
-        OutputFormat::HexUpper => newlined(hex::encode_upper(&output)).as_bytes().to_vec(),
-        OutputFormat::Base64 => newlined(BASE64_STANDARD.encode(output)).as_bytes().to_vec(),
-        OutputFormat::Base64NoPad => newlined(BASE64_STANDARD_NO_PAD.encode(output))
-            .as_bytes()
-            .to_vec(),
-        OutputFormat::Base64UrlSafe => newlined(BASE64_URL_SAFE.encode(output)).as_bytes().to_vec(),
-        OutputFormat::Base64UrlSafeNoPad => newlined(BASE64_URL_SAFE_NO_PAD.encode(output))
-            .as_bytes()
-            .to_vec(),
-    }
+    Ok(())
 }
 
-/// Adds a new line to an owned string
-fn newlined(s: impl Into<String>) -> String {
-    let s = s.into();
-    format!("{}\n", s)
+fn write_to_stdout(data: &[u8]) {
+    let stdout = std::io::stdout();
+    let mut stdout_handle = stdout.lock();
+    stdout_handle
+        .write_all(data)
+        .expect("Writing result to stdout failing");
 }
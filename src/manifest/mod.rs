@@ -0,0 +1,576 @@
+//! Manifests of directory trees, and differences between them.
+//!
+//! A manifest is a text file. The first line is a header, with the settings used for hashing:
+//! `#thash-manifest<TAB>version=1<TAB>algorithm=<ALGO><TAB>iterations=<N>[<TAB>option=<KEY>=<VALUE>]...[<TAB>secret-option=<KEY>]...`
+//!
+//! Options that are secrets, like the key of HMAC, are not written. Only their names are, so that
+//! they can be asked for when a directory is hashed again with the settings of the manifest.
+//!
+//! Then every entry in the tree is written in a line, sorted bytewise by path:
+//! `<type><TAB><mode><TAB><size><TAB><digest><TAB><path>`
+//!
+//! The type is `f`, `d` or `l` (file, directory or symbolic link). The mode is the permission bits in octal,
+//! or `-` for symbolic links and on platforms that do not have them. The digest is in lower-case hex, or `-` for directories.
+//! Symbolic links are not followed, their size and digest are of their target path.
+//! Paths are relative to the root of the tree and use `/` as a separator. Backslashes, tabs and new lines in them
+//! and in option values are escaped as `\\`, `\t` and `\n`. Names that are not valid UTF-8 are not supported.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    io::{BufRead, BufReader},
+    num::{NonZeroU64, NonZeroUsize},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use clap::ValueEnum;
+
+use crate::{
+    hasher::{
+        make_hasher,
+        options::{blake2_options::BLAKE2_KEY_KEY, common::KEY_KEY, fast_hash_options::SECRET_KEY},
+        traits::DynHasher,
+    },
+    input::open_file,
+    program_options::{HashAlgorithm, THashOptions},
+    reader::InputReader,
+    tree_hash::{entry_digest, name_bytes, EntryType},
+    worker_pool::run_in_order,
+};
+
+const MANIFEST_MAGIC: &str = "#thash-manifest";
+const MANIFEST_VERSION: &str = "1";
+
+/// The options whose values are never written into a manifest
+const SECRET_OPTIONS: [&str; 3] = [KEY_KEY, BLAKE2_KEY_KEY, SECRET_KEY];
+
+/// The settings that were used to calculate the digests in a manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestSettings {
+    pub algo: HashAlgorithm,
+    pub iters: NonZeroU64,
+    pub options: BTreeMap<String, String>,
+    /// The names of the secret options that were used, whose values are not part of the manifest
+    pub secret_options: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub entry_type: EntryType,
+    pub mode: Option<u32>,
+    pub size: u64,
+    pub digest: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    pub settings: ManifestSettings,
+    pub entries: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManifestDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<String>,
+    pub renamed: Vec<(String, String)>,
+}
+
+impl ManifestDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.renamed.is_empty()
+    }
+}
+
+impl ManifestSettings {
+    /// Splits the options into those that are written into the manifest, and the values of the secret ones
+    pub fn new(
+        algo: HashAlgorithm,
+        iters: NonZeroU64,
+        options: BTreeMap<String, String>,
+    ) -> (Self, BTreeMap<String, String>) {
+        let (secrets, options): (BTreeMap<_, _>, BTreeMap<_, _>) = options
+            .into_iter()
+            .partition(|(k, _)| SECRET_OPTIONS.contains(&k.as_str()));
+
+        let settings = Self {
+            algo,
+            iters,
+            options,
+            secret_options: secrets.keys().cloned().collect(),
+        };
+        (settings, secrets)
+    }
+
+    /// Makes a hasher with the settings. The secrets must hold the value of every secret option
+    /// of the manifest, and no other secret.
+    pub fn make_hasher(
+        &self,
+        secrets: &BTreeMap<String, String>,
+    ) -> anyhow::Result<Box<dyn DynHasher>> {
+        if let Some(missing) = self
+            .secret_options
+            .iter()
+            .find(|name| !secrets.contains_key(*name))
+        {
+            return Err(anyhow::anyhow!(
+                "The manifest was created with the secret option `{missing}`, which is not stored in it. Provide it again, with `--key-file` for a key"
+            ));
+        }
+        if let Some(unused) = secrets
+            .keys()
+            .find(|name| !self.secret_options.contains(*name))
+        {
+            return Err(anyhow::anyhow!(
+                "The manifest was created without the secret option `{unused}`"
+            ));
+        }
+
+        let mut options = self.options.clone();
+        options.extend(secrets.clone());
+        make_hasher(self.algo, self.iters, options)
+    }
+
+    fn header_line(&self) -> String {
+        let mut fields = vec![
+            MANIFEST_MAGIC.to_string(),
+            format!("version={MANIFEST_VERSION}"),
+            format!("algorithm={}", self.algo),
+            format!("iterations={}", self.iters),
+        ];
+        fields.extend(
+            self.options
+                .iter()
+                .map(|(k, v)| format!("option={}={}", escape_field(k), escape_field(v))),
+        );
+        fields.extend(
+            self.secret_options
+                .iter()
+                .map(|k| format!("secret-option={}", escape_field(k))),
+        );
+        fields.join("\t")
+    }
+
+    fn parse_header(line: &str) -> anyhow::Result<Self> {
+        let mut fields = line.split('\t');
+        if fields.next() != Some(MANIFEST_MAGIC) {
+            return Err(anyhow::anyhow!("Not a thash manifest, header is missing"));
+        }
+
+        let mut version = None;
+        let mut algo = None;
+        let mut iters = None;
+        let mut options = BTreeMap::new();
+        let mut secret_options = BTreeSet::new();
+
+        for field in fields {
+            let (key, value) = field
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Invalid manifest header field: {field}"))?;
+            match key {
+                "version" => version = Some(value.to_string()),
+                "algorithm" => {
+                    algo = Some(HashAlgorithm::from_str(value, false).map_err(|e| {
+                        anyhow::anyhow!("Invalid algorithm in manifest header: {e}")
+                    })?)
+                }
+                "iterations" => {
+                    iters = Some(
+                        value
+                            .parse::<NonZeroU64>()
+                            .context("Invalid iterations in manifest header")?,
+                    )
+                }
+                "option" => {
+                    let (k, v) = value.split_once('=').ok_or_else(|| {
+                        anyhow::anyhow!("Invalid option in manifest header: {value}")
+                    })?;
+                    options.insert(unescape_field(k)?, unescape_field(v)?);
+                }
+                "secret-option" => {
+                    secret_options.insert(unescape_field(value)?);
+                }
+                _ => return Err(anyhow::anyhow!("Unknown manifest header field: {key}")),
+            }
+        }
+
+        if version.as_deref() != Some(MANIFEST_VERSION) {
+            return Err(anyhow::anyhow!(
+                "Unsupported manifest version: {}",
+                version.unwrap_or_default()
+            ));
+        }
+
+        Ok(Self {
+            algo: algo.ok_or_else(|| anyhow::anyhow!("Manifest header has no algorithm"))?,
+            iters: iters.ok_or_else(|| anyhow::anyhow!("Manifest header has no iterations"))?,
+            options,
+            secret_options,
+        })
+    }
+}
+
+impl ManifestEntry {
+    fn to_line(&self) -> String {
+        let mode = self
+            .mode
+            .map(|m| format!("{m:o}"))
+            .unwrap_or_else(|| "-".to_string());
+        let digest = self
+            .digest
+            .as_ref()
+            .map(hex::encode)
+            .unwrap_or_else(|| "-".to_string());
+        format!(
+            "{}\t{mode}\t{}\t{digest}\t{}",
+            self.entry_type.tag() as char,
+            self.size,
+            escape_field(&self.path)
+        )
+    }
+
+    fn parse_line(line: &str) -> anyhow::Result<Self> {
+        let fields = line.splitn(5, '\t').collect::<Vec<_>>();
+        let [entry_type, mode, size, digest, path] = fields[..] else {
+            return Err(anyhow::anyhow!("Invalid manifest line: {line}"));
+        };
+
+        let entry_type = match entry_type.as_bytes() {
+            [tag] => EntryType::from_tag(*tag),
+            _ => None,
+        }
+        .ok_or_else(|| anyhow::anyhow!("Invalid entry type in manifest: {entry_type}"))?;
+        let mode = match mode {
+            "-" => None,
+            m => Some(u32::from_str_radix(m, 8).context(format!("Invalid mode in manifest: {m}"))?),
+        };
+        let size = size
+            .parse::<u64>()
+            .context(format!("Invalid size in manifest: {size}"))?;
+        let digest = match digest {
+            "-" => None,
+            d => Some(hex::decode(d).context(format!("Invalid digest in manifest: {d}"))?),
+        };
+
+        Ok(Self {
+            path: unescape_field(path)?,
+            entry_type,
+            mode,
+            size,
+            digest,
+        })
+    }
+}
+
+impl Manifest {
+    /// Walks the directory and hashes all the files in it, with `jobs` workers
+    pub fn from_directory(
+        settings: ManifestSettings,
+        secrets: &BTreeMap<String, String>,
+        root: &Path,
+        reader: InputReader,
+        jobs: NonZeroUsize,
+    ) -> anyhow::Result<Self> {
+        if !root.is_dir() {
+            return Err(anyhow::anyhow!(
+                "Path is not a directory: {}",
+                root.display()
+            ));
+        }
+
+        // Validates the settings before walking the directory
+        settings.make_hasher(secrets)?;
+
+        let mut walked = Vec::new();
+        collect_entries(root, "", &mut walked)?;
+        walked.sort_by(|a, b| a.0.path.as_bytes().cmp(b.0.path.as_bytes()));
+
+        let mut entries = Vec::with_capacity(walked.len());
+        run_in_order(
+            jobs,
+            &walked,
+            || settings.make_hasher(secrets),
+            |hasher, (entry, entry_path)| match entry.entry_type {
+                EntryType::Directory => Ok(None),
+                entry_type => entry_digest(hasher, entry_path, entry_type, reader).map(Some),
+            },
+            |(entry, _), digest| {
+                entries.push(ManifestEntry {
+                    digest: digest?,
+                    ..entry.clone()
+                });
+                Ok(())
+            },
+        )?;
+
+        Ok(Self { settings, entries })
+    }
+
+    pub fn to_text(&self) -> String {
+        std::iter::once(self.settings.header_line())
+            .chain(self.entries.iter().map(ManifestEntry::to_line))
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    pub fn parse(source: impl BufRead) -> anyhow::Result<Self> {
+        let mut lines = source.lines();
+        let header = lines
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Manifest is empty"))??;
+        let settings = ManifestSettings::parse_header(&header)?;
+
+        let entries = lines
+            .filter(|line| !matches!(line, Ok(l) if l.is_empty()))
+            .map(|line| ManifestEntry::parse_line(&line?))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self { settings, entries })
+    }
+
+    /// Finds the differences from this manifest to the other one. Files that were removed and added
+    /// with the same digest are reported as renamed.
+    pub fn diff(&self, other: &Manifest) -> ManifestDiff {
+        let old = self
+            .entries
+            .iter()
+            .map(|e| (e.path.as_str(), e))
+            .collect::<BTreeMap<_, _>>();
+        let new = other
+            .entries
+            .iter()
+            .map(|e| (e.path.as_str(), e))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut result = ManifestDiff::default();
+
+        for (path, old_entry) in &old {
+            if let Some(new_entry) = new.get(path) {
+                if is_modified(old_entry, new_entry) {
+                    result.modified.push(path.to_string());
+                }
+            }
+        }
+
+        let mut added = new
+            .keys()
+            .filter(|path| !old.contains_key(*path))
+            .copied()
+            .collect::<BTreeSet<_>>();
+
+        // Added files by their digest, so that they can be matched with removed files
+        let mut added_files_by_digest = BTreeMap::<&[u8], VecDeque<&str>>::new();
+        for path in &added {
+            let entry = new[path];
+            if let (EntryType::File, Some(digest)) = (entry.entry_type, &entry.digest) {
+                added_files_by_digest
+                    .entry(digest)
+                    .or_default()
+                    .push_back(path);
+            }
+        }
+
+        for (path, entry) in old.iter().filter(|(path, _)| !new.contains_key(*path)) {
+            let renamed_to = match (entry.entry_type, &entry.digest) {
+                (EntryType::File, Some(digest)) => added_files_by_digest
+                    .get_mut(digest.as_slice())
+                    .and_then(|paths| paths.pop_front()),
+                _ => None,
+            };
+
+            match renamed_to {
+                Some(new_path) => {
+                    added.remove(new_path);
+                    result
+                        .renamed
+                        .push((path.to_string(), new_path.to_string()));
+                }
+                None => result.removed.push(path.to_string()),
+            }
+        }
+
+        result.added = added.into_iter().map(|p| p.to_string()).collect();
+
+        result
+    }
+}
+
+fn is_modified(old: &ManifestEntry, new: &ManifestEntry) -> bool {
+    let mode_changed = match (old.mode, new.mode) {
+        (Some(a), Some(b)) => a != b,
+        _ => false,
+    };
+
+    old.entry_type != new.entry_type
+        || old.size != new.size
+        || old.digest != new.digest
+        || mode_changed
+}
+
+/// Walks the directory, and collects its entries with their paths. Digests are left out, so that files
+/// can be hashed later.
+fn collect_entries(
+    dir: &Path,
+    prefix: &str,
+    entries: &mut Vec<(ManifestEntry, PathBuf)>,
+) -> anyhow::Result<()> {
+    for entry in
+        std::fs::read_dir(dir).context(format!("Reading directory failed: {}", dir.display()))?
+    {
+        let entry = entry.context(format!("Reading directory failed: {}", dir.display()))?;
+        let entry_path = entry.path();
+        let metadata = entry
+            .metadata()
+            .context(format!("Reading metadata failed: {}", entry_path.display()))?;
+        let entry_type = EntryType::from_file_type(&metadata.file_type())
+            .ok_or_else(|| anyhow::anyhow!("Unsupported file type: {}", entry_path.display()))?;
+
+        // Replacing invalid bytes could give two names the same path, and hide a change
+        let name = String::from_utf8(name_bytes(&entry.file_name())).map_err(|_| {
+            anyhow::anyhow!(
+                "File name is not valid UTF-8, so it cannot be written in a manifest: {}",
+                entry_path.display()
+            )
+        })?;
+        let path = format!("{prefix}{name}");
+
+        let size = match entry_type {
+            EntryType::Directory => {
+                collect_entries(&entry_path, &format!("{path}/"), entries)?;
+                0
+            }
+            EntryType::File => metadata.len(),
+            EntryType::Symlink => {
+                let target = std::fs::read_link(&entry_path).context(format!(
+                    "Reading symbolic link failed: {}",
+                    entry_path.display()
+                ))?;
+                name_bytes(target.as_os_str()).len() as u64
+            }
+        };
+
+        entries.push((
+            ManifestEntry {
+                path,
+                entry_type,
+                // The permissions of symbolic links are not meaningful, and they differ between platforms
+                mode: match entry_type {
+                    EntryType::Symlink => None,
+                    _ => permission_bits(&metadata),
+                },
+                size,
+                digest: None,
+            },
+            entry_path,
+        ));
+    }
+
+    Ok(())
+}
+
+fn permission_bits(metadata: &std::fs::Metadata) -> Option<u32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        Some(metadata.permissions().mode() & 0o7777)
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+fn escape_field(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape_field(s: &str) -> anyhow::Result<String> {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('\\') => result.push('\\'),
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            _ => return Err(anyhow::anyhow!("Invalid escape sequence in manifest: {s}")),
+        }
+    }
+
+    Ok(result)
+}
+
+fn read_manifest(path: &Path) -> anyhow::Result<Manifest> {
+    Manifest::parse(BufReader::new(open_file(path)?))
+        .context(format!("Reading manifest failed: {}", path.display()))
+}
+
+pub fn run_manifest(args: &THashOptions, dir: &Path) -> anyhow::Result<()> {
+    let (settings, secrets) =
+        ManifestSettings::new(args.hash_algo()?, args.iters(), args.options()?);
+
+    let manifest =
+        Manifest::from_directory(settings, &secrets, dir, args.input_reader(), args.jobs())?;
+    print!("{}", manifest.to_text());
+
+    Ok(())
+}
+
+/// Prints the differences between the manifests. Returns whether they are identical.
+/// The secret options of the old manifest are taken from the arguments, to hash a directory.
+pub fn run_diff(args: &THashOptions, old: &Path, new: &Path) -> anyhow::Result<bool> {
+    let old_manifest = read_manifest(old)?;
+
+    let new_manifest = if new.is_dir() {
+        let settings = old_manifest.settings.clone();
+        let (_, secrets) = ManifestSettings::new(settings.algo, settings.iters, args.options()?);
+        Manifest::from_directory(settings, &secrets, new, args.input_reader(), args.jobs())?
+    } else {
+        let manifest = read_manifest(new)?;
+        if manifest.settings != old_manifest.settings {
+            return Err(anyhow::anyhow!(
+                "The manifests were created with different algorithms, iterations or options, so their digests cannot be compared"
+            ));
+        }
+        manifest
+    };
+
+    let diff = old_manifest.diff(&new_manifest);
+
+    for path in &diff.added {
+        println!("added\t{}", escape_field(path));
+    }
+    for path in &diff.removed {
+        println!("removed\t{}", escape_field(path));
+    }
+    for path in &diff.modified {
+        println!("modified\t{}", escape_field(path));
+    }
+    for (old_path, new_path) in &diff.renamed {
+        println!(
+            "renamed\t{}\t{}",
+            escape_field(old_path),
+            escape_field(new_path)
+        );
+    }
+
+    Ok(diff.is_empty())
+}
+
+#[cfg(test)]
+mod tests;
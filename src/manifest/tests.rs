@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{program_options::HashAlgorithm, reader::InputReader, tree_hash::EntryType};
+
+use super::{Manifest, ManifestDiff, ManifestEntry, ManifestSettings};
+
+fn file(path: &str, digest: &[u8]) -> ManifestEntry {
+    ManifestEntry {
+        path: path.to_string(),
+        entry_type: EntryType::File,
+        mode: Some(0o644),
+        size: digest.len() as u64,
+        digest: Some(digest.to_vec()),
+    }
+}
+
+fn manifest(entries: Vec<ManifestEntry>) -> Manifest {
+    Manifest {
+        settings: ManifestSettings {
+            algo: HashAlgorithm::Sha256,
+            iters: 2.try_into().unwrap(),
+            options: [("output-size".to_string(), "1\t2".to_string())]
+                .into_iter()
+                .collect::<BTreeMap<_, _>>(),
+            secret_options: BTreeSet::new(),
+        },
+        entries,
+    }
+}
+
+#[test]
+fn text_roundtrip() {
+    let m = manifest(vec![
+        ManifestEntry {
+            path: "dir".to_string(),
+            entry_type: EntryType::Directory,
+            mode: Some(0o755),
+            size: 0,
+            digest: None,
+        },
+        file("dir/a\tb\\c\nd", &[1, 2, 3]),
+        ManifestEntry {
+            path: "link".to_string(),
+            entry_type: EntryType::Symlink,
+            mode: None,
+            size: 5,
+            digest: Some(vec![4, 5]),
+        },
+    ]);
+
+    let text = m.to_text();
+    assert!(text.starts_with(
+        "#thash-manifest\tversion=1\talgorithm=sha256\titerations=2\toption=output-size=1\\t2\n"
+    ));
+    assert!(text.contains("f\t644\t3\t010203\tdir/a\\tb\\\\c\\nd\n"));
+    assert!(text.contains("l\t-\t5\t0405\tlink\n"));
+    assert_eq!(Manifest::parse(text.as_bytes()).unwrap(), m);
+}
+
+#[test]
+fn invalid_manifests() {
+    assert!(Manifest::parse("".as_bytes()).is_err());
+    assert!(Manifest::parse("f\t644\t3\t010203\ta\n".as_bytes()).is_err());
+    assert!(Manifest::parse(
+        "#thash-manifest\tversion=2\talgorithm=sha256\titerations=1\n".as_bytes()
+    )
+    .is_err());
+    assert!(Manifest::parse(
+        "#thash-manifest\tversion=1\talgorithm=sha256\titerations=1\nx\t644\t3\t010203\ta\n"
+            .as_bytes()
+    )
+    .is_err());
+}
+
+#[test]
+fn diff() {
+    let old = manifest(vec![
+        file("same", b"1"),
+        file("changed", b"2"),
+        file("gone", b"3"),
+        file("moved", b"4"),
+    ]);
+    let new = manifest(vec![
+        file("same", b"1"),
+        file("changed", b"5"),
+        file("new", b"6"),
+        file("moved-here", b"4"),
+    ]);
+
+    assert_eq!(
+        old.diff(&new),
+        ManifestDiff {
+            added: vec!["new".to_string()],
+            removed: vec!["gone".to_string()],
+            modified: vec!["changed".to_string()],
+            renamed: vec![("moved".to_string(), "moved-here".to_string())],
+        }
+    );
+    assert!(old.diff(&old).is_empty());
+}
+
+#[test]
+fn secret_options_are_not_written() {
+    let options = [
+        ("key".to_string(), "0011223344".to_string()),
+        ("output-size".to_string(), "16".to_string()),
+    ]
+    .into_iter()
+    .collect::<BTreeMap<_, _>>();
+    let (settings, secrets) =
+        ManifestSettings::new(HashAlgorithm::Kmac128, 1.try_into().unwrap(), options);
+    assert_eq!(settings.secret_options, BTreeSet::from(["key".to_string()]));
+    assert_eq!(secrets["key"], "0011223344");
+
+    let m = Manifest {
+        settings,
+        entries: vec![],
+    };
+    let text = m.to_text();
+    assert!(!text.contains("0011223344"));
+    assert!(text.ends_with("\toption=output-size=16\tsecret-option=key\n"));
+    assert_eq!(Manifest::parse(text.as_bytes()).unwrap(), m);
+
+    // The key has to be given again to hash with the settings
+    assert!(m.settings.make_hasher(&BTreeMap::new()).is_err());
+    assert!(m.settings.make_hasher(&secrets).is_ok());
+    let unkeyed = manifest(vec![]);
+    assert!(unkeyed.settings.make_hasher(&secrets).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn names_that_are_not_utf8_are_rejected() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = std::env::temp_dir().join(format!("thash-manifest-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let name = std::ffi::OsStr::from_bytes(b"a\xff");
+    // Some file systems only allow UTF-8 names
+    if std::fs::write(dir.join(name), "abc").is_ok() {
+        let (settings, secrets) = ManifestSettings::new(
+            HashAlgorithm::Sha256,
+            1.try_into().unwrap(),
+            BTreeMap::new(),
+        );
+        let result = Manifest::from_directory(
+            settings,
+            &secrets,
+            &dir,
+            InputReader::default(),
+            1.try_into().unwrap(),
+        );
+        assert!(result.is_err());
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
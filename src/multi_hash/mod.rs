@@ -0,0 +1,168 @@
+//! Hashing with many algorithms at once. Every input is read once, and each buffer that is read
+//! is written into the hashers of all the algorithms, so large files don't have to be read again
+//! for every digest that is published with them.
+
+use crate::{
+    hasher::{make_hasher, traits::DynHasher},
+    input::hash_input_with_all,
+    output::{digest_to_string, tagged_line},
+    program_options::{HashAlgorithm, OutputFormat, THashOptions},
+    worker_pool::run_in_order,
+};
+
+/// Hashes all the inputs with all the requested algorithms, and prints the digests
+/// as tagged lines, or as a table
+pub fn run_multi_hash(args: &THashOptions) -> anyhow::Result<()> {
+    if args.tuple {
+        return Err(anyhow::anyhow!(
+            "Tuples of inputs can only be hashed with a single algorithm"
+        ));
+    }
+
+    if matches!(args.output_format, OutputFormat::Binary) {
+        return Err(anyhow::anyhow!(
+            "Binary output format cannot be used when hashing with more than one algorithm"
+        ));
+    }
+
+    let algos = make_hashers(args)?
+        .into_iter()
+        .map(|(algo, _)| algo)
+        .collect::<Vec<_>>();
+    if args.all {
+        print_skipped_algos(args, &algos);
+    }
+    let inputs = args.inputs();
+    let with_names = args.with_names || inputs.len() > 1;
+
+    let mut table = prettytable::Table::new();
+    if with_names {
+        table.add_row(prettytable::row!["Input", "Algorithm", "Digest"]);
+    } else {
+        table.add_row(prettytable::row!["Algorithm", "Digest"]);
+    }
+
+    let mut failures = 0;
+    run_in_order(
+        args.jobs(),
+        &inputs,
+        || {
+            // Every worker makes the same algorithms, since they are chosen only by the options
+            Ok(make_hashers(args)?
+                .into_iter()
+                .map(|(_, hasher)| hasher)
+                .collect::<Vec<_>>())
+        },
+        |hashers, input| hash_input_with_all(hashers, input, args.recursive, args.input_reader()),
+        |input, digests| {
+            let digests = match digests {
+                Ok(digests) => digests,
+                Err(e) => {
+                    eprintln!("thash: {}: {:#}", input.display_name(), e);
+                    failures += 1;
+                    return Ok(());
+                }
+            };
+
+            for (algo, digest) in algos.iter().zip(digests) {
+                if args.table {
+                    let digest = digest_to_string(&digest, args.output_format)
+                        .expect("Binary output format is rejected before hashing");
+                    if with_names {
+                        table.add_row(prettytable::row![
+                            input.display_name(),
+                            algo_tag(*algo),
+                            digest
+                        ]);
+                    } else {
+                        table.add_row(prettytable::row![algo_tag(*algo), digest]);
+                    }
+                } else {
+                    let line = tagged_line(
+                        &digest,
+                        args.output_format,
+                        &algo_tag(*algo),
+                        &input.display_name(),
+                    );
+                    print!("{line}");
+                }
+            }
+            Ok(())
+        },
+    )?;
+
+    if args.table && failures < inputs.len() {
+        print!("{table}");
+    }
+
+    if failures > 0 {
+        return Err(anyhow::anyhow!(
+            "{failures} of {} inputs could not be hashed",
+            inputs.len()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Makes the hashers of the requested algorithms, in order. With `--all`, the algorithms that don't
+/// accept the given options, or that require options that weren't given, are left out.
+fn make_hashers(args: &THashOptions) -> anyhow::Result<Vec<(HashAlgorithm, Box<dyn DynHasher>)>> {
+    if !args.all {
+        return args
+            .hash_algos()
+            .into_iter()
+            .map(|algo| {
+                Ok((
+                    algo,
+                    make_hasher(algo, args.iters(), args.options_for(algo)?)?,
+                ))
+            })
+            .collect();
+    }
+
+    let result = args
+        .hash_algos()
+        .into_iter()
+        .map(|algo| Ok((algo, args.options_for(algo)?)))
+        .collect::<anyhow::Result<Vec<_>>>()?
+        .into_iter()
+        .filter_map(|(algo, options)| {
+            make_hasher(algo, args.iters(), options)
+                .ok()
+                .map(|hasher| (algo, hasher))
+        })
+        .collect::<Vec<_>>();
+
+    if result.is_empty() {
+        return Err(anyhow::anyhow!("No algorithm accepts the given options"));
+    }
+
+    Ok(result)
+}
+
+/// Lists the algorithms that `--all` left out, since they can't be used with the given options
+fn print_skipped_algos(args: &THashOptions, algos: &[HashAlgorithm]) {
+    let skipped = args
+        .hash_algos()
+        .into_iter()
+        .filter(|algo| !algos.contains(algo))
+        .map(|algo| algo.to_string())
+        .collect::<Vec<_>>();
+
+    if !skipped.is_empty() {
+        eprintln!(
+            "thash: skipped the algorithms that cannot be used with the given options: {}",
+            skipped.join(", ")
+        );
+    }
+}
+
+/// The name of the algorithm in tagged lines, like `SHA256` or `SHA3-256`.
+/// Checking reads it back in any letter case.
+pub fn algo_tag(algo: HashAlgorithm) -> String {
+    algo.to_string().to_uppercase()
+}
+
+#[cfg(test)]
+mod tests;
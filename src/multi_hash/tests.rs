@@ -0,0 +1,54 @@
+use clap::ValueEnum;
+
+use crate::{
+    hasher::{make_hasher, traits::DynHasher},
+    program_options::HashAlgorithm,
+    reader::InputReader,
+};
+
+use super::algo_tag;
+
+#[test]
+fn tags_are_read_back_by_check() {
+    for algo in HashAlgorithm::value_variants() {
+        assert_eq!(
+            HashAlgorithm::from_str(&algo_tag(*algo), true),
+            Ok(*algo),
+            "{algo}"
+        );
+    }
+    assert_eq!(algo_tag(HashAlgorithm::Sha3_256), "SHA3-256");
+}
+
+#[test]
+fn all_hashers_get_the_whole_input() {
+    let algos = [
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Blake3,
+        HashAlgorithm::Md5,
+    ];
+    // Longer than the buffer, so it's read in many parts
+    let data = (0..10000).map(|i| i as u8).collect::<Vec<_>>();
+
+    let mut hashers = algos
+        .iter()
+        .map(|algo| make_hasher(*algo, 1.try_into().unwrap(), Default::default()).unwrap())
+        .collect::<Vec<_>>();
+    let reader = InputReader {
+        buffer_size: 4096.try_into().unwrap(),
+        ..Default::default()
+    };
+    reader
+        .read_into_hashers(&mut hashers, data.as_slice())
+        .unwrap();
+
+    for (algo, hasher) in algos.iter().zip(hashers.iter_mut()) {
+        let mut single = make_hasher(*algo, 1.try_into().unwrap(), Default::default()).unwrap();
+        single.write(&data);
+        assert_eq!(
+            hasher.finalize_and_reset(),
+            single.finalize_and_reset(),
+            "{algo}"
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use crate::program_options::OutputFormat;
+
+pub fn convert_output(output: Vec<u8>, output_format: OutputFormat) -> Vec<u8> {
+    match digest_to_string(&output, output_format) {
+        Some(s) => newlined(s).as_bytes().to_vec(),
+        None => output, // We don't add a new line to binary mode
+    }
+}
+
+/// Encodes the digest as text in the given format. Returns None for the binary format.
+pub fn digest_to_string(output: &[u8], output_format: OutputFormat) -> Option<String> {
+    use base64::prelude::*;
+
+    let result = match output_format {
+        OutputFormat::Binary => return None,
+        OutputFormat::HexLower => hex::encode(output),
+        OutputFormat::HexUpper => hex::encode_upper(output),
+        OutputFormat::Base64 => BASE64_STANDARD.encode(output),
+        OutputFormat::Base64NoPad => BASE64_STANDARD_NO_PAD.encode(output),
+        OutputFormat::Base64UrlSafe => BASE64_URL_SAFE.encode(output),
+        OutputFormat::Base64UrlSafeNoPad => BASE64_URL_SAFE_NO_PAD.encode(output),
+    };
+
+    Some(result)
+}
+
+/// Decodes a digest that was encoded as text in the given format, the inverse of `digest_to_string`.
+/// Hex is accepted in any letter case. Returns None for the binary format or if decoding fails.
+pub fn string_to_digest(s: &str, output_format: OutputFormat) -> Option<Vec<u8>> {
+    use base64::prelude::*;
+
+    match output_format {
+        OutputFormat::Binary => None,
+        OutputFormat::HexLower | OutputFormat::HexUpper => hex::decode(s).ok(),
+        OutputFormat::Base64 => BASE64_STANDARD.decode(s).ok(),
+        OutputFormat::Base64NoPad => BASE64_STANDARD_NO_PAD.decode(s).ok(),
+        OutputFormat::Base64UrlSafe => BASE64_URL_SAFE.decode(s).ok(),
+        OutputFormat::Base64UrlSafeNoPad => BASE64_URL_SAFE_NO_PAD.decode(s).ok(),
+    }
+}
+
+/// Creates a line in the layout of GNU's `*sum` programs: `<digest>  <path>`, or `<digest> *<path>` in binary mode.
+/// Like GNU, if the path contains a backslash or a new line, these are escaped and the line is prefixed with a backslash.
+pub fn digest_line(
+    digest: &[u8],
+    output_format: OutputFormat,
+    name: &str,
+    binary_mode: bool,
+) -> String {
+    let digest = digest_to_string(digest, output_format)
+        .expect("Binary output format is rejected before printing names");
+    let separator = if binary_mode { " *" } else { "  " };
+
+    match escape_file_name(name) {
+        Some(escaped) => newlined(format!("\\{digest}{separator}{escaped}")),
+        None => newlined(format!("{digest}{separator}{name}")),
+    }
+}
+
+/// Creates a line in the BSD layout, `ALGO (path) = <digest>`, which GNU's `*sum` programs print with `--tag`.
+/// Paths that need escaping are escaped the same way as in `digest_line`.
+pub fn tagged_line(digest: &[u8], output_format: OutputFormat, tag: &str, name: &str) -> String {
+    let digest = digest_to_string(digest, output_format)
+        .expect("Binary output format is rejected before printing names");
+
+    match escape_file_name(name) {
+        Some(escaped) => newlined(format!("\\{tag} ({escaped}) = {digest}")),
+        None => newlined(format!("{tag} ({name}) = {digest}")),
+    }
+}
+
+/// Escapes backslashes and new lines in a file name, the way GNU's `*sum` programs do.
+/// Returns None if the name does not need escaping.
+pub fn escape_file_name(name: &str) -> Option<String> {
+    if !name.contains(['\\', '\n', '\r']) {
+        return None;
+    }
+
+    let escaped = name
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+
+    Some(escaped)
+}
+
+/// The inverse of `escape_file_name`. Returns None if the name contains an invalid escape sequence.
+pub fn unescape_file_name(name: &str) -> Option<String> {
+    let mut result = String::with_capacity(name.len());
+    let mut chars = name.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next()? {
+            '\\' => result.push('\\'),
+            'n' => result.push('\n'),
+            'r' => result.push('\r'),
+            _ => return None,
+        }
+    }
+
+    Some(result)
+}
+
+/// Adds a new line to an owned string
+fn newlined(s: impl Into<String>) -> String {
+    let s = s.into();
+    format!("{}\n", s)
+}
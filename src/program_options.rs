@@ -1,6 +1,15 @@
-use std::{collections::BTreeMap, num::NonZeroU64};
+use std::{
+    collections::BTreeMap,
+    num::{NonZeroU64, NonZeroUsize},
+    path::PathBuf,
+};
 
-use clap::{Parser, ValueEnum};
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+
+use crate::{
+    hasher::options::common::THREADS_KEY, input::InputSource, reader::InputReader,
+    worker_pool::default_jobs,
+};
 
 #[derive(Parser, Clone, Debug, Default)]
 #[command(
@@ -12,22 +21,91 @@ use clap::{Parser, ValueEnum};
 )]
 pub struct THashOptions {
     /// The algorithm that will be used to hash the given data.
-    #[arg(long, short('a'), value_name("ALGORITHM"), default_value_t = HashAlgorithm::default())]
-    pub hash_algo: HashAlgorithm,
+    /// A comma separated list of algorithms, like `sha256,blake3,md5`, hashes every input with all of them,
+    /// while reading it only once.
+    #[arg(
+        long,
+        short('a'),
+        value_name("ALGORITHM"),
+        value_delimiter(','),
+        default_values_t = [HashAlgorithm::default()],
+        global = true
+    )]
+    hash_algo: Vec<HashAlgorithm>,
+
+    /// Hash the inputs with every algorithm that accepts the given options, while reading them only once.
+    /// The algorithms that are left out are listed on stderr.
+    #[arg(long, conflicts_with("hash_algo"))]
+    pub all: bool,
+
+    /// When hashing with more than one algorithm, print the digests as a table instead of tagged lines.
+    /// Tagged lines have the BSD layout `ALGO (path) = digest`, which can be verified with `--check`.
+    #[arg(long)]
+    pub table: bool,
 
     /// The number of iterations for hashing the data, recursively.
     /// This works by using the output of a hash function (as bytes) as input for the same hash function.
-    #[arg(long, short('i'), value_name("NUMBER"), default_value_t = 1, value_parser=parse_iters)]
+    #[arg(long, short('i'), value_name("NUMBER"), default_value_t = 1, value_parser=parse_iters, global = true)]
     iters: u64,
 
     /// Output format. The result will go into stdout.
-    #[arg(long, short('F'), default_value_t = OutputFormat::default())]
+    #[arg(long, short('F'), default_value_t = OutputFormat::default(), global = true)]
     pub output_format: OutputFormat,
 
-    /// An optional path of the source file to read, in case you do not want to use stdin.
-    /// If not provided, the program expects to get data from stdin.
-    #[arg(long, short('f'))]
-    pub file: Option<std::path::PathBuf>,
+    /// An optional path of a source file to read, in case you do not want to use stdin.
+    /// Can be specified multiple times. If no files are provided, the program expects to get data from stdin.
+    #[arg(long, short('f'), action = clap::ArgAction::Append)]
+    file: Vec<PathBuf>,
+
+    /// Paths of files to hash, similar to `-f`. Use `-` to read from stdin.
+    #[arg(value_name("FILE"))]
+    files: Vec<PathBuf>,
+
+    /// Hash directories recursively. A directory produces a single digest over its whole tree,
+    /// built from the names, types and digests of its entries, in a way that does not depend on the machine.
+    #[arg(long, short('r'))]
+    pub recursive: bool,
+
+    /// Always print the path next to the digest, in the `<digest>  <path>` layout of GNU's `*sum` programs.
+    /// This is the default when more than one input is given.
+    #[arg(long, short('N'))]
+    pub with_names: bool,
+
+    /// Use the binary mode marker (` *` instead of two spaces) between the digest and the path.
+    /// This only affects the output, the data is read the same way in both cases.
+    #[arg(long, short('b'))]
+    pub binary: bool,
+
+    /// Hash all inputs as the elements of one tuple, and print a single digest.
+    /// This is only supported by algorithms that hash tuples, like TupleHash.
+    #[arg(long, conflicts_with_all(["check", "recursive", "with_names"]))]
+    pub tuple: bool,
+
+    /// Read checksum lists from the given files (or stdin) and verify them.
+    /// Both the GNU `<digest>  <path>` layout and the BSD `ALGO (path) = digest` layout are supported.
+    /// In the BSD layout, the algorithm is taken from the line itself.
+    #[arg(long, short('c'))]
+    pub check: bool,
+
+    /// When checking, do not print OK for every successfully verified file.
+    #[arg(long, requires("check"))]
+    pub quiet: bool,
+
+    /// When checking, do not print anything. The exit code shows whether verification succeeded.
+    #[arg(long, requires("check"))]
+    pub status: bool,
+
+    /// When checking, exit with a failure code for improperly formatted checksum lines.
+    #[arg(long, requires("check"))]
+    pub strict: bool,
+
+    /// When checking, do not fail or report anything for files that do not exist.
+    #[arg(long, requires("check"))]
+    pub ignore_missing: bool,
+
+    /// When checking, print a warning for every improperly formatted checksum line.
+    #[arg(long, short('w'), requires("check"))]
+    pub warn: bool,
 
     /// Options related to hashing algorithms. Some algorithms provide options (or parameters)
     /// related to their hashing. These can be set here.
@@ -35,19 +113,262 @@ pub struct THashOptions {
         short = 'o',
         long = "option",
         action = clap::ArgAction::Append,
-        number_of_values(1)
+        number_of_values(1),
+        global = true
     )]
     options: Vec<String>,
+
+    /// A file with the raw bytes of a key, for algorithms that accept a key, like HMAC.
+    /// This is equivalent to `-o key=file:<PATH>`.
+    #[arg(long, value_name("PATH"), global = true)]
+    key_file: Option<PathBuf>,
+
+    /// The number of threads, for algorithms that can hash large inputs in parallel, which are BLAKE3
+    /// and ParallelHash. It is passed to them as `-o threads=<NUMBER>`, and ignored by the other algorithms.
+    /// It isn't used for manifests, so that their header only holds what changes the digests.
+    #[arg(long, value_name("NUMBER"), global = true)]
+    threads: Option<NonZeroUsize>,
+
+    /// The number of files that are hashed at the same time, when many files are given, in a tree with `--recursive`,
+    /// in a manifest, or in checksum lists. Each one is hashed by its own worker, and the output stays in the same order.
+    /// Default is the number of available CPU cores. With threads for BLAKE3 or ParallelHash, the jobs are limited so that
+    /// jobs times threads doesn't exceed the CPU cores.
+    #[arg(long, short('j'), value_name("NUMBER"), global = true)]
+    jobs: Option<NonZeroUsize>,
+
+    /// How files are read. Stdin is always read into buffers, also on a separate thread with `threaded`.
+    /// With `buffered` and `mmap`, BLAKE3 with threads maps files into memory itself.
+    #[arg(long, value_name("MODE"), default_value_t = IoMode::default(), global = true)]
+    pub io: IoMode,
+
+    /// The size of every read, in bytes, or with a K, M or G suffix (powers of 1024), like `1M`.
+    /// It is rounded up to a multiple of 4 KiB. Default is 256K.
+    #[arg(long, value_name("SIZE"), value_parser = parse_size, global = true)]
+    buffer_size: Option<NonZeroUsize>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum Command {
+    /// Write a manifest of a directory tree to stdout, with the type, mode, size and digest of every entry.
+    Manifest {
+        /// The directory to create a manifest for
+        dir: PathBuf,
+    },
+
+    /// Compare a manifest with another manifest, or with a directory, and report added, removed,
+    /// modified and renamed files. The algorithm, iterations and options are taken from the first manifest.
+    /// Exits with a failure code if any differences were found.
+    Diff {
+        /// The manifest to compare against
+        old: PathBuf,
+        /// A manifest, or a directory that will be hashed with the settings of the first manifest
+        new: PathBuf,
+    },
+
+    /// Derive a key from a password, read from stdin or a file. The cost parameters and the salt are set
+    /// with options, like `-o salt=<HEX>`. The key is printed in the output format,
+    /// or as a PHC string (or a bcrypt string) with `--phc`.
+    Kdf {
+        /// The key derivation function
+        algorithm: KdfAlgorithm,
+        /// A file to read the password from, instead of stdin. The whole file is the password, including new lines.
+        #[arg(long, short('f'), value_name("PATH"))]
+        file: Option<PathBuf>,
+        /// Print the key with its parameters and salt as a PHC string, like `$argon2id$v=19$...`.
+        /// A random salt is generated if none is given.
+        #[arg(long)]
+        phc: bool,
+    },
+
+    /// Derive a key with HKDF (RFC 5869), using HMAC with the algorithm of `-a`. The input keying material
+    /// is read from stdin or a file, or given with `-o key=<HEX>`. The salt, info and size of the output
+    /// are set with options. The extract and expand steps can also be run separately.
+    Hkdf {
+        /// The step to run. `derive` runs extract and then expand, `expand` takes the pseudorandom key as its key.
+        #[arg(default_value_t = HkdfStep::default())]
+        step: HkdfStep,
+        /// A file to read the key from, instead of stdin
+        #[arg(long, short('f'), value_name("PATH"))]
+        file: Option<PathBuf>,
+    },
+
+    /// Measure how fast algorithms hash in-memory buffers of several sizes, to compare them on this machine.
+    /// The options and iterations are used like when hashing. Prints a table of the throughput, and of the
+    /// cycles per byte where the CPU has a cycle counter.
+    Bench {
+        /// The algorithms to measure, separated by commas. Default is every algorithm that accepts the given options.
+        #[arg(value_name("ALGORITHM"), value_delimiter(','))]
+        algorithms: Vec<HashAlgorithm>,
+        /// The sizes of the buffers, separated by commas, in bytes or with a K, M or G suffix (powers of 1024)
+        #[arg(long, value_name("SIZES"), value_delimiter(','), default_value("64,4K,1M,1G"), value_parser = parse_size)]
+        sizes: Vec<NonZeroUsize>,
+        /// The minimum time to measure every algorithm with every size, in milliseconds
+        #[arg(long, value_name("MILLISECONDS"), default_value_t = 500)]
+        min_time: u64,
+        /// Print the results as JSON, to compare them between versions and machines
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Verify a password against a password hash string: a PHC string of Argon2, scrypt or PBKDF2,
+    /// or a bcrypt (`$2b$`), sha256-crypt (`$5$`) or sha512-crypt (`$6$`) string.
+    /// The password is read from a file, or prompted for if stdin is a terminal, or read from stdin.
+    /// One line ending at the end of stdin is not part of the password.
+    /// Exits with code 1 if the password does not match, and with code 2 if it cannot be verified,
+    /// like when the hash string is malformed or the password cannot be read.
+    VerifyPassword {
+        /// The password hash string. Quote it, since it contains `$`.
+        hash: String,
+        /// A file to read the password from. The whole file is the password, including new lines.
+        #[arg(long, short('f'), value_name("PATH"))]
+        file: Option<PathBuf>,
+    },
 }
 
 impl THashOptions {
+    /// Parses the command line like `parse`, but keeps the files of `-f` and the positional files
+    /// in the order they were given in, so that they're hashed and printed in that order
+    pub fn parse_in_order() -> Self {
+        let matches = Self::command().get_matches();
+        let mut args = match Self::from_arg_matches(&matches) {
+            Ok(args) => args,
+            Err(e) => e.format(&mut Self::command()).exit(),
+        };
+        args.order_inputs(&matches);
+        args
+    }
+
+    /// Moves all the files into `files`, sorted by their position on the command line
+    fn order_inputs(&mut self, matches: &ArgMatches) {
+        let file_indices = matches.indices_of("file").into_iter().flatten();
+        let files_indices = matches.indices_of("files").into_iter().flatten();
+
+        let mut indexed_files = file_indices
+            .zip(std::mem::take(&mut self.file))
+            .chain(files_indices.zip(std::mem::take(&mut self.files)))
+            .collect::<Vec<_>>();
+        indexed_files.sort_by_key(|(index, _)| *index);
+
+        self.files = indexed_files.into_iter().map(|(_, path)| path).collect();
+    }
+
     pub fn options(&self) -> anyhow::Result<BTreeMap<String, String>> {
-        parse_options(&self.options)
+        let mut result = parse_options(&self.options)?;
+
+        if let Some(key_file) = &self.key_file {
+            let key_file_value = format!("file:{}", key_file.display());
+            if result.insert("key".to_string(), key_file_value).is_some() {
+                return Err(anyhow::anyhow!(
+                    "The key cannot be provided both as an option and as a file"
+                ));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// The options for hashing with the algorithm. Besides [`Self::options`], this holds `--threads`
+    /// if the algorithm hashes with threads, so that the other algorithms can be used with it.
+    pub fn options_for(&self, algo: HashAlgorithm) -> anyhow::Result<BTreeMap<String, String>> {
+        let mut result = self.options()?;
+
+        if let Some(threads) = self.threads.filter(|_| algo.hashes_with_threads()) {
+            if result
+                .insert(THREADS_KEY.to_string(), threads.to_string())
+                .is_some()
+            {
+                return Err(anyhow::anyhow!(
+                    "The number of threads cannot be provided both as an option and with `--threads`"
+                ));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// The algorithm, for the uses that only support a single algorithm
+    pub fn hash_algo(&self) -> anyhow::Result<HashAlgorithm> {
+        match self.hash_algos().as_slice() {
+            [algo] => Ok(*algo),
+            _ => Err(anyhow::anyhow!(
+                "Only a single algorithm can be used here, but more than one was requested"
+            )),
+        }
+    }
+
+    /// The algorithms to hash with, in the order they were given and without duplicates
+    pub fn hash_algos(&self) -> Vec<HashAlgorithm> {
+        if self.all {
+            return HashAlgorithm::value_variants().to_vec();
+        }
+
+        let mut result = Vec::with_capacity(self.hash_algo.len());
+        for algo in &self.hash_algo {
+            if !result.contains(algo) {
+                result.push(*algo);
+            }
+        }
+        result
     }
 
     pub fn iters(&self) -> NonZeroU64 {
         NonZeroU64::new(self.iters).expect("Already checked iters > 0 while parsing")
     }
+
+    /// The number of jobs. Every job of an algorithm that hashes with threads has its own threads,
+    /// so then the jobs are limited, to keep the total number of threads within the CPU cores.
+    pub fn jobs(&self) -> NonZeroUsize {
+        let jobs = self.jobs.unwrap_or_else(default_jobs);
+
+        if !self.hash_algos().iter().any(|a| a.hashes_with_threads()) {
+            return jobs;
+        }
+
+        let threads = self.threads.or_else(|| {
+            parse_options(&self.options)
+                .ok()?
+                .get(THREADS_KEY)?
+                .parse()
+                .ok()
+        });
+        match threads {
+            Some(threads) => {
+                let max_jobs = NonZeroUsize::new(default_jobs().get() / threads.get())
+                    .unwrap_or(NonZeroUsize::MIN);
+                jobs.min(max_jobs)
+            }
+            None => jobs,
+        }
+    }
+
+    pub fn input_reader(&self) -> InputReader {
+        let default = InputReader::default();
+        InputReader {
+            mode: self.io,
+            buffer_size: self.buffer_size.unwrap_or(default.buffer_size),
+        }
+    }
+
+    /// The inputs to hash, in the order they were given on the command line with [`Self::parse_in_order`].
+    /// If none are given, stdin is used.
+    pub fn inputs(&self) -> Vec<InputSource> {
+        let result = self
+            .file
+            .iter()
+            .chain(self.files.iter())
+            .cloned()
+            .map(InputSource::from_path)
+            .collect::<Vec<_>>();
+
+        if result.is_empty() {
+            vec![InputSource::Stdin]
+        } else {
+            result
+        }
+    }
 }
 
 fn parse_options(opts: &[String]) -> anyhow::Result<BTreeMap<String, String>> {
@@ -75,25 +396,155 @@ fn parse_iters(value: &str) -> anyhow::Result<u64> {
     Ok(result.get())
 }
 
-#[derive(ValueEnum, Debug, Clone, Default, Copy, strum_macros::Display)]
+fn parse_size(value: &str) -> anyhow::Result<NonZeroUsize> {
+    let (number, multiplier) = match value.char_indices().last() {
+        Some((i, 'k' | 'K')) => (&value[..i], 1 << 10),
+        Some((i, 'm' | 'M')) => (&value[..i], 1 << 20),
+        Some((i, 'g' | 'G')) => (&value[..i], 1 << 30),
+        _ => (value, 1),
+    };
+    let v = number
+        .parse::<usize>()
+        .map_err(|e| anyhow::anyhow!("Size must be a number, with an optional suffix: {e}"))?;
+    v.checked_mul(multiplier)
+        .and_then(NonZeroUsize::new)
+        .ok_or_else(|| anyhow::anyhow!("Size must be a positive number that fits in memory"))
+}
+
+#[derive(
+    ValueEnum, Debug, Clone, Default, Copy, PartialEq, Eq, PartialOrd, Ord, strum_macros::Display,
+)]
 #[strum(serialize_all = "kebab-case")]
 #[clap(rename_all = "kebab_case")]
 pub enum HashAlgorithm {
+    Adler32,
+    AsconHash256,
+    AsconXof128,
+    Blake224,
+    Blake256,
+    Blake384,
+    Blake512,
     #[default]
     Blake2b,
     Blake2s,
     Blake3,
+    Cksum,
+    Crc,
+    Crc32,
+    Crc32c,
+    Crc64,
+    Crc64Ecma,
+    Crc64Nvme,
+    Cshake128,
+    Cshake256,
+    Gost94,
+    Gost94Cryptopro,
+    Groestl224,
+    Groestl256,
+    Groestl384,
+    Groestl512,
+    Jh224,
+    Jh256,
+    Jh384,
+    Jh512,
+    #[value(alias = "kt128")]
     K12,
+    Keccak224,
+    Keccak256,
+    Keccak384,
+    Keccak512,
+    Kmac128,
+    Kmac256,
+    Kt256,
     Md5,
+    Parallelhash128,
+    Parallelhash256,
+    Ripemd160,
     Sha1,
     Sha224,
     Sha256,
     Sha384,
     Sha512,
+    Sha512_224,
+    Sha512_256,
     Sha3_224,
     Sha3_256,
     Sha3_384,
     Sha3_512,
+    Shake128,
+    Shake256,
+    Siphash13,
+    Siphash24,
+    Skein256,
+    Skein512,
+    Skein1024,
+    Sm3,
+    Streebog256,
+    Streebog512,
+    Tiger,
+    Tiger2,
+    Tuplehash128,
+    Tuplehash256,
+    Turboshake128,
+    Turboshake256,
+    Whirlpool,
+    Wyhash,
+    Xxh32,
+    Xxh64,
+    #[value(alias = "xxh3")]
+    Xxh3_64,
+    #[value(alias = "xxh128")]
+    Xxh3_128,
+}
+
+impl HashAlgorithm {
+    /// Whether the algorithm can hash large inputs with many threads, with `--threads`
+    pub fn hashes_with_threads(self) -> bool {
+        matches!(
+            self,
+            HashAlgorithm::Blake3 | HashAlgorithm::Parallelhash128 | HashAlgorithm::Parallelhash256
+        )
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display)]
+#[strum(serialize_all = "kebab-case")]
+#[clap(rename_all = "kebab_case")]
+pub enum KdfAlgorithm {
+    Argon2d,
+    Argon2i,
+    Argon2id,
+    Bcrypt,
+    Pbkdf2Sha1,
+    Pbkdf2Sha256,
+    Pbkdf2Sha512,
+    Scrypt,
+}
+
+#[derive(ValueEnum, Debug, Clone, Default, Copy, PartialEq, Eq, strum_macros::Display)]
+#[strum(serialize_all = "kebab-case")]
+#[clap(rename_all = "kebab_case")]
+pub enum HkdfStep {
+    #[default]
+    Derive,
+    Extract,
+    Expand,
+}
+
+#[derive(ValueEnum, Debug, Clone, Default, Copy, PartialEq, Eq, strum_macros::Display)]
+#[strum(serialize_all = "kebab-case")]
+#[clap(rename_all = "kebab_case")]
+pub enum IoMode {
+    /// Read into large buffers, and tell the OS that files are read sequentially, so it reads ahead
+    #[default]
+    Buffered,
+    /// Map files into memory. A file must not be truncated while it is hashed
+    Mmap,
+    /// Read files with direct I/O, bypassing the page cache, on Linux. Elsewhere, and on file systems
+    /// that do not support it, files are read like `buffered`
+    Direct,
+    /// Read on a separate thread, so that reading the next buffer overlaps with hashing the current one
+    Threaded,
 }
 
 #[derive(ValueEnum, Debug, Clone, Default, Copy, strum_macros::Display)]
@@ -0,0 +1,264 @@
+//! Reading inputs into hashers. Files can be read into large buffers, mapped into memory,
+//! read with direct I/O that bypasses the page cache, or read on a separate thread, so that
+//! reading overlaps with hashing. See [`IoMode`].
+
+use std::{fs::File, io::Read, num::NonZeroUsize, path::Path, sync::mpsc};
+
+use crate::{hasher::traits::DynHasher, input::open_file, program_options::IoMode};
+
+/// The alignment of buffers, and the granularity of their sizes, which is enough for direct I/O
+/// on common block devices
+const BUFFER_ALIGNMENT: usize = 4096;
+
+/// How inputs are read into hashers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputReader {
+    pub mode: IoMode,
+    pub buffer_size: NonZeroUsize,
+}
+
+impl Default for InputReader {
+    fn default() -> Self {
+        Self {
+            mode: IoMode::default(),
+            buffer_size: NonZeroUsize::new(256 * 1024).expect("Not zero"),
+        }
+    }
+}
+
+impl InputReader {
+    /// Whether hashers that read files in their own way, like multithreaded BLAKE3, may do so.
+    /// They map files into memory, so they're only let to with `buffered` and `mmap`.
+    pub fn lets_hashers_read_files(&self) -> bool {
+        matches!(self.mode, IoMode::Buffered | IoMode::Mmap)
+    }
+
+    /// Reads the whole file, and writes its content into all the hashers
+    pub fn read_file_into_hashers(
+        &self,
+        hashers: &mut [impl DynHasher],
+        path: &Path,
+    ) -> anyhow::Result<()> {
+        match self.mode {
+            IoMode::Buffered => self.buffer_into_hashers(hashers, open_sequential(path)?),
+            IoMode::Mmap => self.map_into_hashers(hashers, open_sequential(path)?),
+            IoMode::Direct => self.buffer_into_hashers(hashers, open_direct(path)?),
+            IoMode::Threaded => self.read_on_thread(hashers, open_sequential(path)?),
+        }
+    }
+
+    /// Reads the source to its end, and writes every buffer into all the hashers.
+    /// Memory mapping and direct I/O only apply to files, so other sources are read into buffers.
+    pub fn read_into_hashers(
+        &self,
+        hashers: &mut [impl DynHasher],
+        source: impl Read + Send,
+    ) -> anyhow::Result<()> {
+        match self.mode {
+            IoMode::Threaded => self.read_on_thread(hashers, source),
+            IoMode::Buffered | IoMode::Mmap | IoMode::Direct => {
+                self.buffer_into_hashers(hashers, source)
+            }
+        }
+    }
+
+    fn buffer_len(&self) -> usize {
+        self.buffer_size.get().next_multiple_of(BUFFER_ALIGNMENT)
+    }
+
+    fn buffer_into_hashers(
+        &self,
+        hashers: &mut [impl DynHasher],
+        mut source: impl Read,
+    ) -> anyhow::Result<()> {
+        let mut buffer = AlignedBuffer::new(self.buffer_len());
+
+        loop {
+            let bytes_read = source.read(buffer.as_mut_slice())?;
+
+            if bytes_read == 0 {
+                break Ok(());
+            }
+
+            write_into_hashers(hashers, &buffer.as_mut_slice()[..bytes_read]);
+        }
+    }
+
+    fn map_into_hashers(&self, hashers: &mut [impl DynHasher], file: File) -> anyhow::Result<()> {
+        // Empty files cannot be mapped, and some special files claim to be empty, while they are not
+        if file.metadata()?.len() == 0 {
+            return self.buffer_into_hashers(hashers, file);
+        }
+
+        // SAFETY: The map is only read, and it doesn't outlive the file. If another process truncates
+        // the file while it's hashed, reading it fails with a signal, which is the documented risk of this mode.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        #[cfg(unix)]
+        {
+            // Only a hint, so a failure doesn't matter
+            let _ = map.advise(memmap2::Advice::Sequential);
+        }
+
+        // Every hasher gets a chunk before moving on, so that the chunk is still in the CPU cache
+        for chunk in map.chunks(self.buffer_len()) {
+            write_into_hashers(hashers, chunk);
+        }
+
+        Ok(())
+    }
+
+    /// Reads into two buffers on a separate thread. While one buffer is hashed, the other one is filled.
+    fn read_on_thread(
+        &self,
+        hashers: &mut [impl DynHasher],
+        mut source: impl Read + Send,
+    ) -> anyhow::Result<()> {
+        let (filled_sender, filled_receiver) =
+            mpsc::sync_channel::<std::io::Result<(AlignedBuffer, usize)>>(1);
+        let (empty_sender, empty_receiver) = mpsc::channel::<AlignedBuffer>();
+        for _ in 0..2 {
+            empty_sender
+                .send(AlignedBuffer::new(self.buffer_len()))
+                .expect("The receiver is alive");
+        }
+
+        // Everything is moved into the scope, so that the reader thread stops if hashing panics
+        std::thread::scope(move |scope| {
+            scope.spawn(move || {
+                for mut buffer in empty_receiver {
+                    let result = source.read(buffer.as_mut_slice());
+                    let done = !matches!(result, Ok(bytes_read) if bytes_read > 0);
+                    if filled_sender.send(result.map(|n| (buffer, n))).is_err() || done {
+                        break;
+                    }
+                }
+            });
+
+            loop {
+                let (mut buffer, bytes_read) = filled_receiver.recv().expect(
+                    "The reader thread stops only after the end of the source or an error",
+                )?;
+
+                if bytes_read == 0 {
+                    break Ok(());
+                }
+
+                write_into_hashers(hashers, &buffer.as_mut_slice()[..bytes_read]);
+
+                // The reader thread is still waiting for buffers, since it didn't reach the end
+                let _ = empty_sender.send(buffer);
+            }
+        })
+    }
+}
+
+fn write_into_hashers(hashers: &mut [impl DynHasher], data: &[u8]) {
+    for hasher in hashers.iter_mut() {
+        hasher.write(data);
+    }
+}
+
+/// A buffer on the heap, whose start is aligned to [`BUFFER_ALIGNMENT`]
+struct AlignedBuffer {
+    storage: Vec<u8>,
+    offset: usize,
+    len: usize,
+}
+
+impl AlignedBuffer {
+    fn new(len: usize) -> Self {
+        let storage = vec![0; len + BUFFER_ALIGNMENT];
+        let offset = storage.as_ptr().align_offset(BUFFER_ALIGNMENT);
+        assert!(offset < BUFFER_ALIGNMENT, "Alignment of buffer failed");
+
+        Self {
+            storage,
+            offset,
+            len,
+        }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.storage[self.offset..self.offset + self.len]
+    }
+}
+
+/// Opens the file, and tells the OS that it's read sequentially, so it can read ahead more
+fn open_sequential(path: &Path) -> anyhow::Result<File> {
+    let file = open_file(path)?;
+
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::io::AsRawFd;
+
+        // SAFETY: The descriptor belongs to the open file. This is only a hint, so a failure doesn't matter.
+        unsafe {
+            libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_SEQUENTIAL);
+        }
+    }
+
+    Ok(file)
+}
+
+/// Opens the file for direct I/O where it's supported, or like [`open_sequential`] elsewhere
+fn open_direct(path: &Path) -> anyhow::Result<DirectFile> {
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        crate::input::check_file(path)?;
+
+        match std::fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_DIRECT)
+            .open(path)
+        {
+            Ok(file) => return Ok(DirectFile(file)),
+            // The file system doesn't support direct I/O
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) => (),
+            Err(e) => {
+                return Err(anyhow::Error::new(e)
+                    .context(format!("Opening file failed: {}", path.display())))
+            }
+        }
+    }
+
+    open_sequential(path).map(DirectFile)
+}
+
+/// A file that may be opened for direct I/O. If a read is rejected, because the file system
+/// doesn't support direct I/O for it, the rest of the file is read through the page cache.
+struct DirectFile(File);
+
+impl Read for DirectFile {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.0.read(buf) {
+            #[cfg(target_os = "linux")]
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) => {
+                disable_direct_io(&self.0)?;
+                self.0.read(buf)
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn disable_direct_io(file: &File) -> std::io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    // SAFETY: F_GETFL and F_SETFL only read and change the status flags of the open descriptor
+    let flags = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETFL) };
+    if flags < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: As above
+    if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_SETFL, flags & !libc::O_DIRECT) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
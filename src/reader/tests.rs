@@ -0,0 +1,146 @@
+use std::num::NonZeroUsize;
+
+use clap::ValueEnum;
+
+use crate::{
+    hasher::{make_hasher, traits::DynHasher},
+    input::InputSource,
+    program_options::{HashAlgorithm, IoMode},
+};
+
+use super::InputReader;
+
+fn sha256_of(
+    reader: InputReader,
+    read: impl FnOnce(&mut [Box<dyn DynHasher>], InputReader),
+) -> Vec<u8> {
+    let mut hashers = [make_hasher(
+        HashAlgorithm::Sha256,
+        1.try_into().unwrap(),
+        Default::default(),
+    )
+    .unwrap()];
+    read(&mut hashers, reader);
+    hashers[0].finalize_and_reset()
+}
+
+fn readers() -> impl Iterator<Item = InputReader> {
+    IoMode::value_variants().iter().flat_map(|mode| {
+        [1, 4096, 5000, 256 * 1024].map(|buffer_size| InputReader {
+            mode: *mode,
+            buffer_size: NonZeroUsize::new(buffer_size).unwrap(),
+        })
+    })
+}
+
+#[test]
+fn all_modes_read_the_whole_file() {
+    let dir = std::env::temp_dir().join(format!("thash-reader-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    for len in [0, 1, 4095, 4096, 4097, 3 * 4096 + 17, 1_000_003] {
+        let data = (0..len).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        let path = dir.join(format!("data-{len}.bin"));
+        std::fs::write(&path, &data).unwrap();
+
+        let expected = sha256_of(InputReader::default(), |hashers, _| hashers[0].write(&data));
+
+        for reader in readers() {
+            let from_file = sha256_of(reader, |hashers, reader| {
+                reader.read_file_into_hashers(hashers, &path).unwrap()
+            });
+            assert_eq!(from_file, expected, "{reader:?}, length {len}");
+
+            let from_source = sha256_of(reader, |hashers, reader| {
+                reader.read_into_hashers(hashers, data.as_slice()).unwrap()
+            });
+            assert_eq!(from_source, expected, "{reader:?}, length {len}");
+        }
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn read_errors_are_returned() {
+    struct FailingSource(usize);
+
+    impl std::io::Read for FailingSource {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.0 == 0 {
+                return Err(std::io::Error::other("read failed"));
+            }
+            self.0 -= 1;
+            buf.fill(1);
+            Ok(buf.len())
+        }
+    }
+
+    for reader in readers() {
+        let mut hashers = [make_hasher(
+            HashAlgorithm::Sha256,
+            1.try_into().unwrap(),
+            Default::default(),
+        )
+        .unwrap()];
+        let error = reader
+            .read_into_hashers(&mut hashers, FailingSource(3))
+            .unwrap_err();
+        assert_eq!(error.to_string(), "read failed", "{reader:?}");
+
+        let error = reader
+            .read_file_into_hashers(&mut hashers, std::path::Path::new("does-not-exist.bin"))
+            .unwrap_err();
+        assert!(
+            error.to_string().starts_with("File not found"),
+            "{reader:?}"
+        );
+    }
+}
+
+#[test]
+fn hashers_read_files_themselves_only_with_buffered_and_mmap() {
+    /// Records whether the file was read by the hasher or by the reader
+    #[derive(Default)]
+    struct FileReadingHasher {
+        read_itself: bool,
+        written: usize,
+    }
+
+    impl DynHasher for FileReadingHasher {
+        fn algorithm_name(&self) -> &'static str {
+            "FileReading"
+        }
+
+        fn write(&mut self, data: &[u8]) {
+            self.written += data.len();
+        }
+
+        fn finalize_and_reset(&mut self) -> Vec<u8> {
+            Vec::new()
+        }
+
+        fn output_size(&self) -> NonZeroUsize {
+            NonZeroUsize::new(1).unwrap()
+        }
+
+        fn write_file(&mut self, _path: &std::path::Path) -> anyhow::Result<bool> {
+            self.read_itself = true;
+            Ok(true)
+        }
+    }
+
+    let input = InputSource::File("README.md".into());
+    for reader in readers() {
+        let mut hashers = [FileReadingHasher::default()];
+        input.read_into_hashers(&mut hashers, reader).unwrap();
+
+        let [hasher] = hashers;
+        assert_eq!(
+            hasher.read_itself,
+            matches!(reader.mode, IoMode::Buffered | IoMode::Mmap),
+            "{reader:?}"
+        );
+        assert_eq!(hasher.read_itself, hasher.written == 0, "{reader:?}");
+    }
+}
@@ -0,0 +1,332 @@
+//! Hashing of whole directory trees into a single digest.
+//!
+//! Every node in the tree gets a digest, computed with the selected hasher (including iterations).
+//! The input of every node starts with its type byte ('f' file, 'd' directory, 'l' symlink), so nodes
+//! of different types never hash the same input, like a file whose content is the target of a symlink:
+//! - A regular file is hashed by `'f' || content`.
+//! - A symbolic link is never followed. It is hashed by `'l' || target`, with the bytes of its target path.
+//! - A directory is hashed by `'d'` and the encoding of its entries, sorted bytewise by name:
+//!   `u64 BE number of entries`, then for every entry:
+//!   `type byte || u64 BE name length || name || digest`.
+//!
+//! Names are the raw bytes of the file names on unix, and their UTF-8 encoding elsewhere.
+//! The path given by the user is followed if it is a symbolic link.
+
+use std::{
+    ffi::OsStr,
+    fs::FileType,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+
+use crate::{
+    hasher::traits::DynHasher, input::InputSource, reader::InputReader, worker_pool::run_in_order,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryType {
+    File,
+    Directory,
+    Symlink,
+}
+
+impl EntryType {
+    pub fn from_file_type(file_type: &FileType) -> Option<Self> {
+        if file_type.is_symlink() {
+            Some(EntryType::Symlink)
+        } else if file_type.is_dir() {
+            Some(EntryType::Directory)
+        } else if file_type.is_file() {
+            Some(EntryType::File)
+        } else {
+            None
+        }
+    }
+
+    pub fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            b'f' => Some(EntryType::File),
+            b'd' => Some(EntryType::Directory),
+            b'l' => Some(EntryType::Symlink),
+            _ => None,
+        }
+    }
+
+    pub fn tag(&self) -> u8 {
+        match self {
+            EntryType::File => b'f',
+            EntryType::Directory => b'd',
+            EntryType::Symlink => b'l',
+        }
+    }
+}
+
+/// Calculates the digest of the tree rooted at the given path. The path can also be a file.
+pub fn tree_digest(
+    hasher: &mut impl DynHasher,
+    path: &Path,
+    reader: InputReader,
+) -> anyhow::Result<Vec<u8>> {
+    node_digest(hasher, path, root_entry_type(path)?, reader)
+}
+
+/// Calculates the digests of the tree rooted at the given path with all the hashers, like [`tree_digest`],
+/// in the same order. The tree, and every file in it, is read once for all the hashers.
+pub fn tree_digests(
+    hashers: &mut [impl DynHasher],
+    path: &Path,
+    reader: InputReader,
+) -> anyhow::Result<Vec<Vec<u8>>> {
+    let tree = TreeNode::read(path, root_entry_type(path)?)?;
+
+    let mut file_digests = vec![Vec::new(); hashers.len()];
+    for file in tree.files() {
+        for (digests, digest) in file_digests
+            .iter_mut()
+            .zip(files_digests(hashers, file, reader)?)
+        {
+            digests.push(digest);
+        }
+    }
+
+    Ok(hashers
+        .iter_mut()
+        .zip(file_digests)
+        .map(|(hasher, digests)| tree.digest(hasher, &mut digests.into_iter()))
+        .collect())
+}
+
+/// Calculates the digest of the tree rooted at the given path, like [`tree_digest`], but the files in it
+/// are hashed by `jobs` workers, each with its own hasher from `make_hasher`. The tree is read before
+/// any file in it is hashed.
+pub fn tree_digest_with_jobs<H: DynHasher>(
+    hasher: &mut impl DynHasher,
+    path: &Path,
+    reader: InputReader,
+    jobs: NonZeroUsize,
+    make_hasher: impl Fn() -> anyhow::Result<H> + Sync,
+) -> anyhow::Result<Vec<u8>> {
+    let tree = TreeNode::read(path, root_entry_type(path)?)?;
+
+    let files = tree.files();
+    let mut file_digests = Vec::with_capacity(files.len());
+    run_in_order(
+        jobs,
+        &files,
+        make_hasher,
+        |file_hasher, file| file_digest(file_hasher, file, reader),
+        |_, digest| {
+            file_digests.push(digest?);
+            Ok(())
+        },
+    )?;
+
+    Ok(tree.digest(hasher, &mut file_digests.into_iter()))
+}
+
+fn node_digest(
+    hasher: &mut impl DynHasher,
+    path: &Path,
+    entry_type: EntryType,
+    reader: InputReader,
+) -> anyhow::Result<Vec<u8>> {
+    let tree = TreeNode::read(path, entry_type)?;
+
+    let file_digests = tree
+        .files()
+        .into_iter()
+        .map(|file| file_digest(hasher, file, reader))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(tree.digest(hasher, &mut file_digests.into_iter()))
+}
+
+/// The digest of a single file or symbolic link, as it's listed in a manifest. Unlike in a tree, the input
+/// isn't prefixed with the type byte, since a manifest lists the type next to the digest. So the digest
+/// of a file is the same as hashing the file directly.
+pub fn entry_digest(
+    hasher: &mut impl DynHasher,
+    path: &Path,
+    entry_type: EntryType,
+    reader: InputReader,
+) -> anyhow::Result<Vec<u8>> {
+    match TreeNode::read(path, entry_type)? {
+        TreeNode::File(path) => InputSource::File(path).read_into_hasher(hasher, reader)?,
+        TreeNode::Symlink(target) => hasher.write(&target),
+        TreeNode::Directory(_) => {
+            return Err(anyhow::anyhow!(
+                "Directories have no digest of their own: {}",
+                path.display()
+            ))
+        }
+    }
+    Ok(hasher.finalize_and_reset())
+}
+
+fn root_entry_type(path: &Path) -> anyhow::Result<EntryType> {
+    let metadata =
+        std::fs::metadata(path).context(format!("Reading metadata failed: {}", path.display()))?;
+    EntryType::from_file_type(&metadata.file_type())
+        .ok_or_else(|| anyhow::anyhow!("Path is not a file or a directory: {}", path.display()))
+}
+
+/// The digest of a file in a tree, which is prefixed with its type byte like every other node
+fn file_digest(
+    hasher: &mut impl DynHasher,
+    path: &Path,
+    reader: InputReader,
+) -> anyhow::Result<Vec<u8>> {
+    let digest = files_digests(std::slice::from_mut(hasher), path, reader)?
+        .pop()
+        .expect("There is a digest for the hasher");
+    Ok(digest)
+}
+
+/// The digests of a file in a tree with all the hashers, like [`file_digest`]. The file is read once.
+fn files_digests(
+    hashers: &mut [impl DynHasher],
+    path: &Path,
+    reader: InputReader,
+) -> anyhow::Result<Vec<Vec<u8>>> {
+    for hasher in hashers.iter_mut() {
+        hasher.write(&[EntryType::File.tag()]);
+    }
+    InputSource::File(path.to_path_buf()).read_into_hashers(hashers, reader)?;
+    Ok(hashers
+        .iter_mut()
+        .map(|hasher| hasher.finalize_and_reset())
+        .collect())
+}
+
+/// A tree as it was read from the file system, without the contents of its files
+enum TreeNode {
+    File(PathBuf),
+    /// The bytes of the target path
+    Symlink(Vec<u8>),
+    /// The entries, sorted bytewise by name
+    Directory(Vec<(Vec<u8>, TreeNode)>),
+}
+
+impl TreeNode {
+    fn read(path: &Path, entry_type: EntryType) -> anyhow::Result<Self> {
+        match entry_type {
+            EntryType::File => Ok(TreeNode::File(path.to_path_buf())),
+            EntryType::Symlink => {
+                let target = std::fs::read_link(path)
+                    .context(format!("Reading symbolic link failed: {}", path.display()))?;
+                Ok(TreeNode::Symlink(name_bytes(target.as_os_str())))
+            }
+            EntryType::Directory => {
+                let mut entries = std::fs::read_dir(path)
+                    .context(format!("Reading directory failed: {}", path.display()))?
+                    .map(|entry| -> anyhow::Result<(Vec<u8>, TreeNode)> {
+                        let entry = entry
+                            .context(format!("Reading directory failed: {}", path.display()))?;
+                        let entry_path = entry.path();
+                        let file_type = entry.file_type().context(format!(
+                            "Reading metadata failed: {}",
+                            entry_path.display()
+                        ))?;
+                        let entry_type =
+                            EntryType::from_file_type(&file_type).ok_or_else(|| {
+                                anyhow::anyhow!("Unsupported file type: {}", entry_path.display())
+                            })?;
+                        Ok((
+                            name_bytes(&entry.file_name()),
+                            TreeNode::read(&entry_path, entry_type)?,
+                        ))
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+                Ok(TreeNode::Directory(entries))
+            }
+        }
+    }
+
+    fn entry_type(&self) -> EntryType {
+        match self {
+            TreeNode::File(_) => EntryType::File,
+            TreeNode::Symlink(_) => EntryType::Symlink,
+            TreeNode::Directory(_) => EntryType::Directory,
+        }
+    }
+
+    /// All the files in the tree, in the order their digests are used
+    fn files(&self) -> Vec<&Path> {
+        let mut result = Vec::new();
+        self.collect_files(&mut result);
+        result
+    }
+
+    fn collect_files<'a>(&'a self, files: &mut Vec<&'a Path>) {
+        match self {
+            TreeNode::File(path) => files.push(path),
+            TreeNode::Symlink(_) => (),
+            TreeNode::Directory(entries) => {
+                for (_, child) in entries {
+                    child.collect_files(files);
+                }
+            }
+        }
+    }
+
+    /// The digest of the tree, given the digests of its files in the order of [`TreeNode::files`].
+    /// The digests of the files already include their type byte, see [`file_digest`].
+    fn digest(
+        &self,
+        hasher: &mut impl DynHasher,
+        file_digests: &mut impl Iterator<Item = Vec<u8>>,
+    ) -> Vec<u8> {
+        match self {
+            TreeNode::File(_) => file_digests
+                .next()
+                .expect("Every file in the tree has a digest"),
+            TreeNode::Symlink(target) => {
+                hasher.write(&[EntryType::Symlink.tag()]);
+                hasher.write(target);
+                hasher.finalize_and_reset()
+            }
+            TreeNode::Directory(entries) => {
+                // Children are hashed first, since the same hasher is used for the directory itself
+                let children = entries
+                    .iter()
+                    .map(|(name, child)| {
+                        (name, child.entry_type(), child.digest(hasher, file_digests))
+                    })
+                    .collect::<Vec<_>>();
+
+                hasher.write(&[EntryType::Directory.tag()]);
+                hasher.write(&(children.len() as u64).to_be_bytes());
+                for (name, entry_type, digest) in children {
+                    hasher.write(&[entry_type.tag()]);
+                    hasher.write(&(name.len() as u64).to_be_bytes());
+                    hasher.write(name);
+                    hasher.write(&digest);
+                }
+
+                hasher.finalize_and_reset()
+            }
+        }
+    }
+}
+
+/// The bytes of a file name, as they are used for sorting and hashing
+pub fn name_bytes(name: &OsStr) -> Vec<u8> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        name.as_bytes().to_vec()
+    }
+
+    #[cfg(not(unix))]
+    {
+        name.to_string_lossy().as_bytes().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests;
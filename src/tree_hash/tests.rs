@@ -0,0 +1,97 @@
+use crate::{hasher::make_hasher, program_options::HashAlgorithm, reader::InputReader};
+
+use super::{tree_digest, tree_digests, EntryType};
+
+fn sha256_tree(path: &std::path::Path) -> Vec<u8> {
+    let mut hasher = make_hasher(
+        HashAlgorithm::Sha256,
+        1.try_into().unwrap(),
+        Default::default(),
+    )
+    .unwrap();
+    tree_digest(&mut hasher, path, InputReader::default()).unwrap()
+}
+
+fn sha256(data: &[u8]) -> Vec<u8> {
+    let mut hasher = make_hasher(
+        HashAlgorithm::Sha256,
+        1.try_into().unwrap(),
+        Default::default(),
+    )
+    .unwrap();
+    hasher.write(data);
+    hasher.finalize_and_reset()
+}
+
+#[cfg(unix)]
+#[test]
+fn file_and_symlink_with_the_same_target_differ() {
+    let dir = std::env::temp_dir().join(format!("thash-tree-test-{}", std::process::id()));
+    let with_file = dir.join("with-file");
+    let with_symlink = dir.join("with-symlink");
+    std::fs::create_dir_all(&with_file).unwrap();
+    std::fs::create_dir_all(&with_symlink).unwrap();
+
+    std::fs::write(with_file.join("entry"), "data1.bin").unwrap();
+    std::os::unix::fs::symlink("data1.bin", with_symlink.join("entry")).unwrap();
+
+    let file_digest = sha256_tree(&with_file);
+    let symlink_digest = sha256_tree(&with_symlink);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_ne!(file_digest, symlink_digest);
+}
+
+#[test]
+fn nodes_are_prefixed_with_their_type() {
+    let dir = std::env::temp_dir().join(format!("thash-tree-prefix-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a"), "abc").unwrap();
+
+    let root_file_digest = sha256_tree(&dir.join("a"));
+    let digest = sha256_tree(&dir);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let file_digest = sha256(b"fabc");
+    assert_eq!(root_file_digest, file_digest);
+
+    let mut directory = vec![EntryType::Directory.tag()];
+    directory.extend_from_slice(&1u64.to_be_bytes());
+    directory.push(EntryType::File.tag());
+    directory.extend_from_slice(&1u64.to_be_bytes());
+    directory.push(b'a');
+    directory.extend_from_slice(&file_digest);
+    assert_eq!(digest, sha256(&directory));
+}
+
+#[test]
+fn trees_are_read_once_for_all_hashers() {
+    let dir = std::env::temp_dir().join(format!("thash-tree-all-test-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+    std::fs::write(dir.join("a"), "abc").unwrap();
+    std::fs::write(dir.join("sub").join("b"), vec![7; 100_000]).unwrap();
+
+    let algos = [
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Md5,
+        HashAlgorithm::Blake3,
+    ];
+    let mut hashers = algos
+        .iter()
+        .map(|algo| make_hasher(*algo, 1.try_into().unwrap(), Default::default()).unwrap())
+        .collect::<Vec<_>>();
+    let digests = tree_digests(&mut hashers, &dir, InputReader::default()).unwrap();
+
+    for (algo, digest) in algos.iter().zip(digests) {
+        let mut hasher = make_hasher(*algo, 1.try_into().unwrap(), Default::default()).unwrap();
+        assert_eq!(
+            digest,
+            tree_digest(&mut hasher, &dir, InputReader::default()).unwrap(),
+            "{algo}"
+        );
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,102 @@
+//! A bounded pool of worker threads, for hashing many files at once.
+//!
+//! Every worker makes its own state, like its hasher, in its own thread, so hashers are never
+//! shared or moved between threads. The results are handed back on the calling thread in the
+//! order of the items, so the output doesn't depend on which worker finishes first.
+
+use std::{
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+};
+
+/// The number of workers used when none is requested
+pub fn default_jobs() -> NonZeroUsize {
+    std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)
+}
+
+/// Runs `work` on every item with at most `jobs` workers, and passes every result to `on_result`
+/// in the order of the items. If `on_result` fails, the workers stop taking items, and the error is returned.
+/// With a single job, or a single item, everything runs on the calling thread.
+pub fn run_in_order<T, S, R>(
+    jobs: NonZeroUsize,
+    items: &[T],
+    make_state: impl Fn() -> anyhow::Result<S> + Sync,
+    work: impl Fn(&mut S, &T) -> R + Sync,
+    mut on_result: impl FnMut(&T, R) -> anyhow::Result<()>,
+) -> anyhow::Result<()>
+where
+    T: Sync,
+    R: Send,
+{
+    let workers = jobs.get().min(items.len());
+    if workers <= 1 {
+        let mut state = make_state()?;
+        for item in items {
+            on_result(item, work(&mut state, item))?;
+        }
+        return Ok(());
+    }
+
+    let next_item = AtomicUsize::new(0);
+
+    std::thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel::<(usize, R)>();
+
+        let handles = (0..workers)
+            .map(|_| {
+                let sender = sender.clone();
+                let (next_item, make_state, work) = (&next_item, &make_state, &work);
+                scope.spawn(move || -> anyhow::Result<()> {
+                    let mut state = make_state()?;
+                    loop {
+                        let index = next_item.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            return Ok(());
+                        };
+                        if sender.send((index, work(&mut state, item))).is_err() {
+                            // The results are not needed anymore
+                            return Ok(());
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(sender);
+
+        // Results that arrived before some of the items ahead of them
+        let mut pending = BTreeMap::new();
+        let mut next_result = 0;
+        let mut result = Ok(());
+
+        for (index, item_result) in receiver.iter() {
+            pending.insert(index, item_result);
+            while let Some(item_result) = pending.remove(&next_result) {
+                result = on_result(&items[next_result], item_result);
+                next_result += 1;
+                if result.is_err() {
+                    break;
+                }
+            }
+            if result.is_err() {
+                next_item.store(items.len(), Ordering::Relaxed);
+                break;
+            }
+        }
+        drop(receiver);
+
+        for handle in handles {
+            handle
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))?;
+        }
+
+        result
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,89 @@
+use std::{num::NonZeroUsize, thread::ThreadId};
+
+use super::run_in_order;
+
+#[test]
+fn results_are_in_order() {
+    let items = (0..500u64).collect::<Vec<_>>();
+
+    for jobs in [1, 2, 7] {
+        let mut results = Vec::new();
+        run_in_order(
+            NonZeroUsize::new(jobs).unwrap(),
+            &items,
+            || Ok(()),
+            |(), item| {
+                // Later items finish first
+                std::thread::sleep(std::time::Duration::from_micros(500 - item));
+                item * 2
+            },
+            |item, result| {
+                results.push((*item, result));
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            results,
+            items.iter().map(|i| (*i, i * 2)).collect::<Vec<_>>(),
+            "jobs = {jobs}"
+        );
+    }
+}
+
+#[test]
+fn every_worker_has_its_own_state() {
+    let items = (0..100).collect::<Vec<_>>();
+
+    let mut states = Vec::new();
+    run_in_order(
+        NonZeroUsize::new(4).unwrap(),
+        &items,
+        || Ok(std::thread::current().id()),
+        |state: &mut ThreadId, _| {
+            assert_eq!(*state, std::thread::current().id());
+            *state
+        },
+        |_, state| {
+            states.push(state);
+            Ok(())
+        },
+    )
+    .unwrap();
+
+    assert_eq!(states.len(), items.len());
+    assert!(!states.contains(&std::thread::current().id()));
+}
+
+#[test]
+fn errors_stop_the_pool() {
+    let items = (0..10_000).collect::<Vec<_>>();
+
+    let mut seen = 0;
+    let result = run_in_order(
+        NonZeroUsize::new(3).unwrap(),
+        &items,
+        || Ok(()),
+        |(), item| *item,
+        |_, result| {
+            seen += 1;
+            if result == 10 {
+                return Err(anyhow::anyhow!("stop"));
+            }
+            Ok(())
+        },
+    );
+
+    assert_eq!(result.unwrap_err().to_string(), "stop");
+    assert_eq!(seen, 11);
+
+    let result = run_in_order(
+        NonZeroUsize::new(3).unwrap(),
+        &items,
+        || -> anyhow::Result<()> { Err(anyhow::anyhow!("no state")) },
+        |(), item| *item,
+        |_, _| Ok(()),
+    );
+    assert_eq!(result.unwrap_err().to_string(), "no state");
+}
@@ -0,0 +1,43 @@
+#!/bin/bash
+
+# Benchmarks of the input modes (`--io`) and buffer sizes (`--buffer-size`), with hyperfine.
+# `--io buffered --buffer-size 4K` reads like thash did before the input modes, so it is the baseline.
+#
+# Usage: test-tools/bench-io.sh [FILE_SIZE_IN_MIB] [ALGORITHM]
+#
+# With COLD=1, the page cache is dropped before every run (this needs sudo), which measures reading
+# from the disk instead of from memory. Results are written to bench-io-results.md. They only hold for the
+# machine, the storage and the file system they were measured on, so none are recorded in the repository.
+
+set -e
+
+size_mib=${1:-1024}
+algo=${2:-blake3}
+file=bench-io-data.bin
+
+if ! command -v hyperfine > /dev/null; then
+    echo "hyperfine is required, see the benchmarking methodology in README.md"
+    exit 1
+fi
+
+cargo build --release
+
+if [ ! -f "$file" ] || [ "$(stat -c %s "$file")" -ne $((size_mib * 1024 * 1024)) ]; then
+    dd if=/dev/urandom of="$file" bs=1M count="$size_mib" status=none
+fi
+
+prepare="cat $file > /dev/null"
+if [ "$COLD" = "1" ]; then
+    prepare="sync; echo 3 | sudo tee /proc/sys/vm/drop_caches > /dev/null"
+fi
+
+commands=("target/release/thash -a $algo --io buffered --buffer-size 4K $file")
+for mode in buffered mmap direct threaded; do
+    for buffer_size in 64K 256K 1M 4M; do
+        commands+=("target/release/thash -a $algo --io $mode --buffer-size $buffer_size $file")
+    done
+done
+
+hyperfine --warmup 1 --prepare "$prepare" --export-markdown bench-io-results.md "${commands[@]}"
+
+rm "$file"
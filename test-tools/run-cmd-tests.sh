@@ -621,6 +621,48 @@ compare_output "$output1" "$output2"
 ######################################################################
 
 
+######################################################################
+# Tests of algos - Blake2 parameters
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a blake2b -o output-size=20)
+output2=$(echo "384264f676f39536840523f284921cdc68b6846b")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a blake2b -o blake2-key=text:key)
+output2=$(echo "5c6a9a4ae911c02fb7e71a991eb9aea371ae993d4842d206e6020d46f5e41358c6d5c277c110ef86c959ed63e6ecaaaceaaff38019a43264ae06acf73b9550b1")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+# key selects HMAC, like with the other algorithms
+output1=$(echo -n "abc" | target/release/thash -a blake2b -o key=text:key)
+output2=$(echo "05cc4815438d5cfe68fff446b8df57828cc96189de4b4e928e3f06d815d64e5bc15124a02ffd39859b3e2476da03bc0235ca86df623af2a5631779809e9fd04a")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+echo -n "abc" | target/release/thash -a blake2b -o key=text:key -o output-size=20 2>/dev/null && exit 1
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a blake2s -o blake2-key=text:key -o salt=text:salt -o personal=text:me -o output-size=16 -i3)
+output2=$(echo "d14447a87ac029b7353be4a420a8119a")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+echo -n "abc" | target/release/thash -a blake2s -o output-size=33 2>/dev/null && exit 1
+######################################################################
+
+
 ######################################################################
 # Tests of algos - Blake3
 ######################################################################
@@ -667,6 +709,50 @@ output2=$(echo "acfec95e0441b70ebce15e924c3d2edacd40d5b2495dc24eb5d3e4ad2f29ca9b
 compare_output "$output1" "$output2"
 ######################################################################
 
+######################################################################
+head -c 1000000 /dev/zero > data.bin
+output1=$(target/release/thash -a blake3 --threads 4 data.bin)
+output2=$(echo "c211bb2e5afbd0efa21659d5578ea30217d5382734be1b494faf705d9aa202a1")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(cat data.bin | target/release/thash -a blake3 -o threads=4)
+output2=$(echo "c211bb2e5afbd0efa21659d5578ea30217d5382734be1b494faf705d9aa202a1")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+######################################################################
+# Other algorithms ignore --threads, also next to BLAKE3
+output1=$(target/release/thash -a sha256 --threads 4 data.bin)
+output2=$(target/release/thash -a sha256 data.bin)
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash -a sha256,blake3 --threads 4 data.bin)
+output2=$(printf "SHA256 (data.bin) = d29751f2649b32ff572b5e0a9f541ea660a50f94ff0beedfb0b692b924cc8025\nBLAKE3 (data.bin) = c211bb2e5afbd0efa21659d5578ea30217d5382734be1b494faf705d9aa202a1")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash --all --threads 4 data.bin | grep -c " = ")
+output2=$(target/release/thash --all data.bin | grep -c " = ")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+target/release/thash -a blake3 --threads 0 data.bin 2>/dev/null && exit 1
+target/release/thash -a sha256 -o threads=4 data.bin 2>/dev/null && exit 1
+rm data.bin
+######################################################################
+
 
 ######################################################################
 # Tests of algos - MD5
@@ -810,60 +896,1140 @@ compare_output "$output1" "$output2"
 
 
 ######################################################################
-# Tests of algos - Large data with pipe
+# Tests of algos - K12 - customization
 ######################################################################
 
 ######################################################################
-output1=$(echo -n "abc" | rdgen -l100000 | target/release/thash)
-output2=$(echo "befb724c57435953a1740c0467f85ec09c212259e88afdd9a079c465ec03fd2a41ff976000d87f2f9c4eb87a0c05f5dc1be60c63650e2e8eb09b94faaff830c8")
+output1=$(echo -n "" | target/release/thash -a k12 -o customization=hex:00)
+output2=$(echo "fab658db63e94a246188bf7af69a133045f46ee984c56e3c3328caaf1aa1a583")
 
 compare_output "$output1" "$output2"
 ######################################################################
 
 ######################################################################
-output1=$(echo -n "abc" | rdgen -l100000 | target/release/thash -i10)
-output2=$(echo "95b0e2039c1e25b99c98a5d71537d3746020ccbeab017a99f9650ea912f93b8171d5035fed3c524425a9a6358bdd5615e847c90b7e57428d51ec11d2f90db1ca")
+output1=$(echo -n "abc" | target/release/thash -a k12 -o customization=my-app)
+output2=$(echo "d5a6793faab6053b07a8315e99449f015b5a3d1d5fb4c594cf86796677dbeefd")
 
 compare_output "$output1" "$output2"
 ######################################################################
 
 ######################################################################
-output1=$(echo -n "abc" | rdgen -l100000 | target/release/thash -i10 -a sha256)
-output2=$(echo "39ccf4908ef54473556e785c4eb248fe28bd229b00bb008a61c811d795966e6b")
+output1=$(echo -n "abc" | target/release/thash -a k12 -o customization=hex:6d792d617070 -o output-size=16)
+output2=$(echo "d5a6793faab6053b07a8315e99449f01")
 
 compare_output "$output1" "$output2"
 ######################################################################
 
 
 ######################################################################
-# Tests of algos - Large data with file
+# Tests of algos - SHAKE and cSHAKE
 ######################################################################
 
 ######################################################################
-echo -n "abc" | rdgen -l100000 > data.bin
-output1=$(target/release/thash -f data.bin)
-output2=$(echo "befb724c57435953a1740c0467f85ec09c212259e88afdd9a079c465ec03fd2a41ff976000d87f2f9c4eb87a0c05f5dc1be60c63650e2e8eb09b94faaff830c8")
+output1=$(echo -n "abc" | target/release/thash -a shake128)
+output2=$(echo "5881092dd818bf5cf8a3ddb793fbcba74097d5c526a6d35f97b83351940f2cc8")
 
 compare_output "$output1" "$output2"
 ######################################################################
 
 ######################################################################
-echo -n "abc" | rdgen -l100000 > data.bin
-output1=$(target/release/thash -f data.bin -i10)
-output2=$(echo "95b0e2039c1e25b99c98a5d71537d3746020ccbeab017a99f9650ea912f93b8171d5035fed3c524425a9a6358bdd5615e847c90b7e57428d51ec11d2f90db1ca")
+output1=$(echo -n "abc" | target/release/thash -a shake256 -o output-size=16)
+output2=$(echo "483366601360a8771c6863080cc4114d")
 
 compare_output "$output1" "$output2"
 ######################################################################
 
 ######################################################################
-echo -n "abc" | rdgen -l100000 > data.bin
-output1=$(target/release/thash -f data.bin -i10 -a sha256)
-output2=$(echo "39ccf4908ef54473556e785c4eb248fe28bd229b00bb008a61c811d795966e6b")
+output1=$(printf '\x00\x01\x02\x03' | target/release/thash -a cshake128 -o "customization=Email Signature")
+output2=$(echo "c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5")
 
 compare_output "$output1" "$output2"
 ######################################################################
 
-rm data.bin
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a cshake128 -o function-name=fn -o customization=cust -o output-size=20 -i2)
+output2=$(echo "5bec2996f8170649d32867742baac785d8365337")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+
+######################################################################
+# Tests of algos - KMAC, TupleHash and ParallelHash
+######################################################################
+
+######################################################################
+output1=$(printf '\x00\x01\x02\x03' | target/release/thash -a kmac128 -o key=404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5f -o "customization=My Tagged Application")
+output2=$(echo "3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+echo -n "abc" | target/release/thash -a kmac128 2>/dev/null && exit 1
+######################################################################
+
+######################################################################
+printf '\x00\x01\x02' > element1.bin
+printf '\x10\x11\x12\x13\x14\x15' > element2.bin
+output1=$(target/release/thash -a tuplehash128 --tuple -o "customization=My Tuple App" element1.bin element2.bin)
+output2=$(echo "75cdb20ff4db1154e841d758e24160c54bae86eb8c13e7f5f40eb35588e96dfb")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+# The concatenation of the elements is hashed differently
+output1=$(cat element1.bin element2.bin | target/release/thash -a tuplehash128 -o "customization=My Tuple App")
+output2=$(echo "75cdb20ff4db1154e841d758e24160c54bae86eb8c13e7f5f40eb35588e96dfb")
+
+[ "$output1" != "$output2" ] || exit 1
+target/release/thash -a sha256 --tuple element1.bin element2.bin 2>/dev/null && exit 1
+rm element1.bin element2.bin
+######################################################################
+
+######################################################################
+output1=$(printf '\x00\x01\x02\x03\x04\x05\x06\x07\x10\x11\x12\x13\x14\x15\x16\x17\x20\x21\x22\x23\x24\x25\x26\x27' | target/release/thash -a parallelhash128 -o block-size=8 -o "customization=Parallel Data")
+output2=$(echo "fc484dcb3f84dceedc353438151bee58157d6efed0445a81f165e495795b7206")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+head -c 1000000 /dev/urandom > parallel.bin
+output1=$(target/release/thash -a parallelhash256 parallel.bin)
+output2=$(target/release/thash -a parallelhash256 --threads 4 --buffer-size 1M parallel.bin)
+
+compare_output "$output1" "$output2"
+rm parallel.bin
+######################################################################
+
+
+######################################################################
+# Tests of algos - TurboSHAKE and KT256
+######################################################################
+
+######################################################################
+output1=$(echo -n "" | target/release/thash -a turboshake128)
+output2=$(echo "1e415f1c5983aff2169217277d17bb538cd945a397ddec541f1ce41af2c1b74c")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf '\xff\xff\xff' | target/release/thash -a turboshake128 -o domain-separation=0x01)
+output2=$(echo "bf323f940494e88ee1c540fe660be8a0c93f43d15ec006998462fa994eed5dab")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+echo -n "" | target/release/thash -a turboshake256 -o domain-separation=0x80 2>/dev/null && exit 1
+######################################################################
+
+######################################################################
+output1=$(echo -n "" | target/release/thash -a kt256)
+output2=$(echo "b23d2e9cea9f4904e02bec06817fc10ce38ce8e93ef4c89e6537076af8646404e3e8b68107b8833a5d30490aa33482353fd4adc7148ecb782855003aaebde4a9")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a kt128)
+output2=$(echo -n "abc" | target/release/thash -a k12)
+
+compare_output "$output1" "$output2"
+######################################################################
+
+
+######################################################################
+# Tests of algos - SHA-512/t and Keccak
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a sha512-224)
+output2=$(echo "4634270f707b6a54daae7530460842e20e37ed265ceee9a43e8924aa")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a sha512-256)
+output2=$(echo "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "" | target/release/thash -a keccak256)
+output2=$(echo "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "" | target/release/thash -a keccak256)
+output2=$(echo -n "" | target/release/thash -a sha3-256)
+
+[ "$output1" != "$output2" ] || exit 1
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a keccak512)
+output2=$(echo "18587dc2ea106b9a1563e32b3312421ca164c7f1f07bc922a9c83d77cea3a1e5d0c69910739025372dc14ac9642629379540c17e2a65b19d77aa511a9d00bb96")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+
+######################################################################
+# Tests of algos - Legacy and regional hashes
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a ripemd160)
+output2=$(echo "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a whirlpool)
+output2=$(echo "4e2448a4c6f486bb16b6562c73b4020bf3043e3a731bce721ae1b303d97e6d4c7181eebdb6c57e277d0e34957114cbd6c797fc9d95d8b582d225292076d4eef5")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a tiger)
+output2=$(echo "2aab1484e8c158f2bfb8c5ff41b57a525129131c957b5f93")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a tiger2)
+output2=$(echo "f68d7bc5af4b43a06e048d7829560d4a9415658bb0b1f3bf")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a sm3)
+output2=$(echo "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "012345678901234567890123456789012345678901234567890123456789012" | target/release/thash -a streebog256)
+output2=$(echo "9d151eefd8590b89daa6ba6cb74af9275dd051026bb149a452fd84e5e57b5500")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "012345678901234567890123456789012345678901234567890123456789012" | target/release/thash -a streebog512)
+output2=$(echo "1b54d01a4af5b9d5cc3d86d68d285462b19abc2475222f35c085122be4ba1ffa00ad30f8767b3a82384c6574f024c311e2a481332b08ef7f41797891c1646f48")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a gost94)
+output2=$(echo "f3134348c44fb1b2a277729e2285ebb5cb5e0f29c975bc753b70497c06a4d51d")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a gost94-cryptopro)
+output2=$(echo "b285056dbf18d7392d7677369524dd14747459ed8143997e163b2986f92fd42c")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "what do ya want for nothing?" | target/release/thash -a ripemd160 -o key=text:Jefe)
+output2=$(echo "dda6c0213a485a9e24f4742064a7f033b43c4069")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+
+######################################################################
+# Tests of algos - SHA-3 finalists and Ascon
+######################################################################
+
+######################################################################
+output1=$(printf "" | target/release/thash -a blake256)
+output2=$(echo "716f6e863f744b9ac22c97ec7b76ea5f5908bc5b2f67c61510bfc4751384ea7a")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf "" | target/release/thash -a blake512)
+output2=$(echo "a8cfbbd73726062df0c6864dda65defe58ef0cc52a5625090fa17601e1eecd1b628e94f396ae402a00acc9eab77b4d4c2e852aaaa25a636d80af3fc7913ef5b8")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf "" | target/release/thash -a groestl256)
+output2=$(echo "1a52d11d550039be16107f9c58db9ebcc417f16f736adb2502567119f0083467")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf "" | target/release/thash -a jh256)
+output2=$(echo "46e64619c18bb0a92a5e87185a47eef83ca747b8fcc8e1412921357e326df434")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf "" | target/release/thash -a skein256)
+output2=$(echo "c8877087da56e072870daa843f176e9453115929094c3a40c463a196c29bf7ba")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "The quick brown fox jumps over the lazy dog" | target/release/thash -a skein512 -o output-size=32)
+output2=$(echo "b3250457e05d3060b1a4bbc1428bc75a3f525ca389aeab96cfa34638d96e492a")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf "" | target/release/thash -a ascon-hash256)
+output2=$(echo "0b3be5850f2f6b98caf29f8fdea89b64a1fa70aa249b8f839bd53baa304d92b2")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+printf "" | target/release/thash -a ascon-hash256 -o key=00 2>/dev/null && exit 1
+target/release/thash -a ascon-hash256 hkdf -o key=00 2>/dev/null && exit 1
+######################################################################
+
+######################################################################
+output1=$(printf "" | target/release/thash -a ascon-xof128)
+output2=$(echo "473d5e6164f58b39dfd84aacdb8ae42ec2d91fed33388ee0d960d9b3993295c6")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+printf "" | target/release/thash -a skein512 -o output-size=0 2>/dev/null && exit 1
+######################################################################
+
+
+######################################################################
+# Tests of algos - Checksums
+######################################################################
+
+######################################################################
+output1=$(printf 123456789 | target/release/thash -a crc32)
+output2=$(echo "cbf43926")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf 123456789 | target/release/thash -a crc32c)
+output2=$(echo "e3069283")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf 123456789 | target/release/thash -a crc64)
+output2=$(echo "995dc9bbdf1939fa")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf 123456789 | target/release/thash -a crc64-ecma)
+output2=$(echo "6c40df5f0b497347")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf 123456789 | target/release/thash -a crc64-nvme)
+output2=$(echo "ae8b14860a799888")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+# The first number of: head -c 100000 /dev/zero | cksum
+output1=$(printf '%u' 0x$(head -c 100000 /dev/zero | target/release/thash -a cksum))
+output2=$(head -c 100000 /dev/zero | cksum | cut -d ' ' -f 1)
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf 123456789 | target/release/thash -a adler32)
+output2=$(echo "091e01de")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+# head -c 100000 /dev/zero | cksum, in hex
+output1=$(head -c 100000 /dev/zero | target/release/thash -a cksum)
+output2=$(echo "4b275616")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf 123456789 | target/release/thash -a crc -o width=16 -o polynomial=0x1021 -o init=0xffff)
+output2=$(echo "29b1")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+echo -n "" | target/release/thash -a crc 2>/dev/null && exit 1
+echo -n "" | target/release/thash -a crc -o width=8 -o polynomial=0x107 2>/dev/null && exit 1
+echo -n "" | target/release/thash -a crc32 -o key=00 2>/dev/null && exit 1
+######################################################################
+
+
+######################################################################
+# Tests of algos - Fast hashes
+######################################################################
+
+######################################################################
+output1=$(printf abc | target/release/thash -a xxh32)
+output2=$(echo "32d153ff")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf abc | target/release/thash -a xxh64)
+output2=$(echo "44bc2cf5ad770999")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf abc | target/release/thash -a xxh3)
+output2=$(echo "78af5f94892f3950")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf abc | target/release/thash -a xxh128)
+output2=$(echo "06b05ab6733a618578af5f94892f3950")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf abc | target/release/thash -a wyhash -o seed=2)
+output2=$(echo "32dd92e4b2915153")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf '' | target/release/thash -a siphash24 -o key=000102030405060708090a0b0c0d0e0f -o output-size=16)
+output2=$(echo "930255c71472f66de6a825ba047f81a3")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(printf '' | target/release/thash -a siphash13 -o key=000102030405060708090a0b0c0d0e0f)
+output2=$(echo "abac0158050fc4dc")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+# Secrets of XXH3 can have any size from 136 bytes
+head -c 100000 /dev/zero | target/release/thash -a xxh3 -o secret=$(printf '%0272d' 0) > /dev/null
+head -c 100000 /dev/zero | target/release/thash -a xxh128 -o secret=$(printf '%0300d' 0) > /dev/null
+echo -n "" | target/release/thash -a xxh3 -o secret=$(printf '%0270d' 0) 2>/dev/null && exit 1
+######################################################################
+
+######################################################################
+echo -n "" | target/release/thash -a xxh32 -o seed=0x100000000 2>/dev/null && exit 1
+echo -n "" | target/release/thash -a xxh3 -o secret=00 2>/dev/null && exit 1
+echo -n "" | target/release/thash -a siphash13 -o output-size=4 2>/dev/null && exit 1
+######################################################################
+
+
+######################################################################
+# Tests of HMAC
+######################################################################
+
+######################################################################
+output1=$(echo -n "Hi There" | target/release/thash -a sha256 -o key=0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b)
+output2=$(echo "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+echo -n "Jefe" > key.bin
+output1=$(echo -n "what do ya want for nothing?" | target/release/thash -a sha512 --key-file key.bin)
+output2=$(echo "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "what do ya want for nothing?" | target/release/thash -a md5 -o key=text:Jefe -F base64)
+output2=$(echo "dQx4PmqwtQPqqG4xCl23OA==")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+rm key.bin
+
+
+######################################################################
+# Tests of algos - Large data with pipe
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | rdgen -l100000 | target/release/thash)
+output2=$(echo "befb724c57435953a1740c0467f85ec09c212259e88afdd9a079c465ec03fd2a41ff976000d87f2f9c4eb87a0c05f5dc1be60c63650e2e8eb09b94faaff830c8")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | rdgen -l100000 | target/release/thash -i10)
+output2=$(echo "95b0e2039c1e25b99c98a5d71537d3746020ccbeab017a99f9650ea912f93b8171d5035fed3c524425a9a6358bdd5615e847c90b7e57428d51ec11d2f90db1ca")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | rdgen -l100000 | target/release/thash -i10 -a sha256)
+output2=$(echo "39ccf4908ef54473556e785c4eb248fe28bd229b00bb008a61c811d795966e6b")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+
+######################################################################
+# Tests of algos - Large data with file
+######################################################################
+
+######################################################################
+echo -n "abc" | rdgen -l100000 > data.bin
+output1=$(target/release/thash -f data.bin)
+output2=$(echo "befb724c57435953a1740c0467f85ec09c212259e88afdd9a079c465ec03fd2a41ff976000d87f2f9c4eb87a0c05f5dc1be60c63650e2e8eb09b94faaff830c8")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+echo -n "abc" | rdgen -l100000 > data.bin
+output1=$(target/release/thash -f data.bin -i10)
+output2=$(echo "95b0e2039c1e25b99c98a5d71537d3746020ccbeab017a99f9650ea912f93b8171d5035fed3c524425a9a6358bdd5615e847c90b7e57428d51ec11d2f90db1ca")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+echo -n "abc" | rdgen -l100000 > data.bin
+output1=$(target/release/thash -f data.bin -i10 -a sha256)
+output2=$(echo "39ccf4908ef54473556e785c4eb248fe28bd229b00bb008a61c811d795966e6b")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+rm data.bin
+
+
+######################################################################
+# Tests of input modes
+######################################################################
+
+######################################################################
+head -c 3000017 /dev/zero > data.bin
+for mode in buffered mmap direct threaded; do
+    for buffer_size in 1 4096 100K 1M; do
+        output1=$(target/release/thash -a sha256 --io $mode --buffer-size $buffer_size data.bin)
+        output2=$(echo "85ff473dfcc390acd78f495502c95617000316eb5b00695579be98b76c52085e")
+
+        compare_output "$output1" "$output2"
+
+        output1=$(cat data.bin | target/release/thash -a sha256 --io $mode --buffer-size $buffer_size)
+
+        compare_output "$output1" "$output2"
+    done
+done
+######################################################################
+
+######################################################################
+output1=$(target/release/thash -a sha256,md5 --io threaded data.bin | grep "^SHA256 ")
+output2=$(echo "SHA256 (data.bin) = 85ff473dfcc390acd78f495502c95617000316eb5b00695579be98b76c52085e")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+touch empty.bin
+output1=$(target/release/thash -a sha256 --io mmap empty.bin)
+output2=$(echo "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+target/release/thash --buffer-size 0 data.bin 2>/dev/null && exit 1
+target/release/thash --buffer-size 1X data.bin 2>/dev/null && exit 1
+target/release/thash --io mmap missing.bin 2>/dev/null && exit 1
+######################################################################
+
+rm data.bin empty.bin
+
+
+######################################################################
+# Tests of multiple files
+######################################################################
+
+######################################################################
+echo -n "abc" > data1.bin
+echo -n "abcd" > data2.bin
+output1=$(target/release/thash -a sha256 data1.bin data2.bin)
+output2=$(printf "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  data1.bin\n88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589  data2.bin")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash -a sha256 -b -f data1.bin -f data2.bin)
+output2=$(printf "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad *data1.bin\n88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589 *data2.bin")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash -a sha256 data2.bin -f data1.bin)
+output2=$(printf "88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589  data2.bin\nba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  data1.bin")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash -a sha256 -N data1.bin)
+output2=$(echo "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  data1.bin")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(cat data2.bin | target/release/thash -a sha256 data1.bin -)
+output2=$(printf "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  data1.bin\n88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589  -")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+rm data1.bin data2.bin
+
+
+######################################################################
+# Tests of multiple algorithms
+######################################################################
+
+######################################################################
+echo -n "abc" > data1.bin
+echo -n "abcd" > data2.bin
+output1=$(target/release/thash -a sha256,md5 data1.bin data2.bin)
+output2=$(printf "SHA256 (data1.bin) = ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad\nMD5 (data1.bin) = 900150983cd24fb0d6963f7d28e17f72\nSHA256 (data2.bin) = 88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589\nMD5 (data2.bin) = e2fc714c4727ee9395f324cd2e7f331f")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(cat data1.bin | target/release/thash -a sha3-256,blake3)
+output2=$(printf "SHA3-256 (-) = 3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532\nBLAKE3 (-) = 6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+target/release/thash -a sha256,blake3,md5 data1.bin data2.bin > sums.txt
+output1=$(target/release/thash -c sums.txt)
+output2=$(printf "data1.bin: OK\ndata1.bin: OK\ndata1.bin: OK\ndata2.bin: OK\ndata2.bin: OK\ndata2.bin: OK")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash --all data1.bin | grep "^SHA256 ")
+output2=$(echo "SHA256 (data1.bin) = ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+# The algorithms that cannot be used with the given options are listed
+output1=$(target/release/thash --all data1.bin 2>&1 >/dev/null)
+output2=$(echo "thash: skipped the algorithms that cannot be used with the given options: crc, kmac128, kmac256")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+# Trees are hashed with every algorithm like with a single one
+mkdir -p tree-dir/sub
+cp data1.bin tree-dir/
+cp data2.bin tree-dir/sub/
+output1=$(target/release/thash -r -a sha256,md5 tree-dir)
+output2=$(printf "SHA256 (tree-dir) = %s\nMD5 (tree-dir) = %s" $(target/release/thash -r -a sha256 tree-dir) $(target/release/thash -r -a md5 tree-dir))
+
+compare_output "$output1" "$output2"
+rm -r tree-dir
+######################################################################
+
+######################################################################
+output1=$(target/release/thash -a sha256,md5 --table data1.bin | grep -c "SHA256\|MD5")
+output2=$(echo "2")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+target/release/thash -a sha256,md5 -F binary data1.bin 2>/dev/null && exit 1
+target/release/thash -a sha256,md5 -c sums.txt 2>/dev/null && exit 1
+######################################################################
+
+rm data1.bin data2.bin sums.txt
+
+
+######################################################################
+# Tests of checking checksum lists
+######################################################################
+
+######################################################################
+echo -n "abc" > data1.bin
+echo -n "abcd" > data2.bin
+target/release/thash -a sha256 data1.bin data2.bin > sums.txt
+output1=$(target/release/thash -a sha256 -c sums.txt)
+output2=$(printf "data1.bin: OK\ndata2.bin: OK")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+echo "SHA256 (data1.bin) = ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad" > sums.txt
+echo "MD5 (data2.bin) = e2fc714c4727ee9395f324cd2e7f331f" >> sums.txt
+output1=$(target/release/thash -c sums.txt)
+output2=$(printf "data1.bin: OK\ndata2.bin: OK")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+echo -n "xyz" > data2.bin
+target/release/thash -a sha256 -c sums.txt --status && exit 1
+output1=$(target/release/thash -c sums.txt --quiet || true)
+output2=$(echo "data2.bin: FAILED")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+echo "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  missing.bin" > sums.txt
+target/release/thash -a sha256 -c sums.txt --status && exit 1
+echo "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  data1.bin" >> sums.txt
+output1=$(target/release/thash -a sha256 -c sums.txt --ignore-missing)
+output2=$(echo "data1.bin: OK")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+echo "not a checksum line" >> sums.txt
+target/release/thash -a sha256 -c sums.txt --ignore-missing --status
+target/release/thash -a sha256 -c sums.txt --ignore-missing --status --strict && exit 1
+######################################################################
+
+######################################################################
+# A line whose algorithm cannot be used with the given options is reported, and the next lines are checked
+echo "CRC (data1.bin) = 352441c2" > sums.txt
+echo "SHA256 (data1.bin) = ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad" >> sums.txt
+output1=$(target/release/thash -c sums.txt 2>&1 || true)
+output2=$(printf "thash: sums.txt: 1: Option \`polynomial\` is required for a custom CRC\ndata1.bin: OK\nthash: WARNING: 1 line could not be checked with the given options")
+
+compare_output "$output1" "$output2"
+target/release/thash -c sums.txt --status && exit 1
+######################################################################
+
+######################################################################
+# A digest of the wrong length for the algorithm is an improperly formatted line
+echo "SHA256 (data1.bin) = ba7816bf" > sums.txt
+echo "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  data1.bin" >> sums.txt
+output1=$(target/release/thash -a sha256 -c sums.txt -w 2>&1)
+output2=$(printf "thash: sums.txt: 1: improperly formatted checksum line\ndata1.bin: OK\nthash: WARNING: 1 line is improperly formatted")
+
+compare_output "$output1" "$output2"
+target/release/thash -a sha256 -c sums.txt --strict --status && exit 1
+######################################################################
+
+######################################################################
+# Checksum lists are text, so they cannot be checked with the binary output format
+target/release/thash -a sha256 data1.bin > sums.txt
+target/release/thash -a sha256 -F binary -c sums.txt 2>/dev/null && exit 1
+######################################################################
+
+rm data1.bin data2.bin sums.txt
+
+
+######################################################################
+# Tests of recursive hashing
+######################################################################
+
+######################################################################
+mkdir -p tree-dir/sub tree-dir/empty
+echo -n "abc" > tree-dir/data1.bin
+echo -n "abcd" > tree-dir/sub/data2.bin
+output1=$(target/release/thash -r -a sha256 tree-dir)
+output2=$(echo "b951bc13bd1fe8fe6f40e641f45229adb930f21add526b23bbd9bad07e5c80de")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash -r tree-dir)
+output2=$(echo "122a35297f1553951ed0466b8f7f146e3012e39d869d8e811165897a2638e7e7331b13ced87464155c4fc6cda6d3475cd551062cebc5f8087286ae5f7d124fe1")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash -r -a sha256 -i2 tree-dir)
+output2=$(echo "f7af1d3ee411f459fae062ff673ba0dae90861e145d8cd35e8c1552c5f0b0067")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash -r -a sha256 tree-dir tree-dir/data1.bin)
+output2=$(printf "b951bc13bd1fe8fe6f40e641f45229adb930f21add526b23bbd9bad07e5c80de  tree-dir\nba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  tree-dir/data1.bin")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+rm -r tree-dir
+
+
+######################################################################
+# Tests of manifests
+######################################################################
+
+######################################################################
+mkdir -p tree-dir/sub
+echo -n "abc" > tree-dir/data1.bin
+echo -n "abcd" > tree-dir/sub/data2.bin
+echo -n "abcde" > tree-dir/data3.bin
+target/release/thash -a sha256 manifest tree-dir > manifest.txt
+output1=$(head -n1 manifest.txt)
+output2=$(printf "#thash-manifest\tversion=1\talgorithm=sha256\titerations=1")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(sed -n 2p manifest.txt | cut -f 3-)
+output2=$(printf "3\tba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad\tdata1.bin")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+target/release/thash diff manifest.txt tree-dir
+mv tree-dir/sub/data2.bin tree-dir/sub/moved.bin
+echo -n "xyz" > tree-dir/data1.bin
+rm tree-dir/data3.bin
+echo -n "new" > tree-dir/new.bin
+output1=$(target/release/thash diff manifest.txt tree-dir || true)
+output2=$(printf "added\tnew.bin\nremoved\tdata3.bin\nmodified\tdata1.bin\nrenamed\tsub/data2.bin\tsub/moved.bin")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+echo -n "secret" > key.bin
+target/release/thash -a sha256 --key-file key.bin manifest tree-dir > keyed-manifest.txt
+output1=$(head -n1 keyed-manifest.txt)
+output2=$(printf "#thash-manifest\tversion=1\talgorithm=sha256\titerations=1\tsecret-option=key")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+target/release/thash --key-file key.bin diff keyed-manifest.txt tree-dir
+target/release/thash diff keyed-manifest.txt tree-dir 2>/dev/null && exit 1
+target/release/thash --key-file manifest.txt diff keyed-manifest.txt tree-dir > /dev/null && exit 1
+target/release/thash -a sha256 -o key=736563726574 manifest tree-dir | grep -q 736563726574 && exit 1
+######################################################################
+
+rm -r tree-dir manifest.txt keyed-manifest.txt key.bin
+
+
+######################################################################
+# Tests of parallel hashing
+######################################################################
+
+######################################################################
+mkdir -p tree-dir/sub tree-dir/empty
+echo -n "abc" > tree-dir/data1.bin
+echo -n "abcd" > tree-dir/sub/data2.bin
+output1=$(target/release/thash -r -a sha256 --jobs 4 tree-dir)
+output2=$(echo "b951bc13bd1fe8fe6f40e641f45229adb930f21add526b23bbd9bad07e5c80de")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+for i in $(seq 1 200); do
+    mkdir -p tree-dir/many/$((i % 7))
+    echo -n "$i" > tree-dir/many/$((i % 7))/file$i.bin
+done
+ln -s data1.bin tree-dir/link
+output1=$(target/release/thash -r -a sha256 --jobs 1 tree-dir)
+output2=$(target/release/thash -r -a sha256 --jobs 8 tree-dir)
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash -a sha256 --jobs 1 tree-dir/many/*/*.bin)
+output2=$(target/release/thash -a sha256 -j 8 tree-dir/many/*/*.bin)
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash -a sha256 --jobs 1 manifest tree-dir)
+output2=$(target/release/thash -a sha256 --jobs 8 manifest tree-dir)
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+target/release/thash -a sha256,md5 --jobs 8 tree-dir/many/*/*.bin > sums.txt
+output1=$(target/release/thash -a sha256,md5 --jobs 1 tree-dir/many/*/*.bin)
+output2=$(cat sums.txt)
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash --jobs 8 -c sums.txt | grep -c ": OK$")
+output2=$(echo "400")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+target/release/thash -a sha256 --jobs 0 tree-dir/data1.bin 2>/dev/null && exit 1
+######################################################################
+
+rm -r tree-dir sums.txt
+
+
+######################################################################
+# Tests of HKDF
+######################################################################
+
+######################################################################
+output1=$(target/release/thash -a sha256 hkdf -o key=0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b -o salt=000102030405060708090a0b0c -o info=f0f1f2f3f4f5f6f7f8f9 -o output-size=42)
+output2=$(echo "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash -a sha256 hkdf extract -o key=0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b -o salt=000102030405060708090a0b0c)
+output2=$(echo "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash -a sha256 hkdf expand -o key=077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5 -o info=f0f1f2f3f4f5f6f7f8f9 -o output-size=42)
+output2=$(echo "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+printf '\x0b\x0b\x0b\x0b\x0b\x0b\x0b\x0b\x0b\x0b\x0b' > ikm.bin
+output1=$(target/release/thash -a sha1 hkdf -f ikm.bin -o salt=000102030405060708090a0b0c -o info=f0f1f2f3f4f5f6f7f8f9 -o output-size=42)
+output2=$(echo "085a01ea1b10f36933068b56efa5ad81a4f14b822f5b091568a9cdd4f155fda2c22e422478d305f3f896")
+
+compare_output "$output1" "$output2"
+rm ikm.bin
+######################################################################
+
+######################################################################
+output1=$(echo -n "abc" | target/release/thash -a sha3-256 hkdf -o salt=text:salt -o info=text:info -F base64)
+output2=$(echo "hBKV2RGEOTIjLa/1dH1DfENPoxINlnjB+X/RIRfaqOg=")
+
+compare_output "$output1" "$output2"
+target/release/thash -a blake3 hkdf -o key=00 2>/dev/null && exit 1
+target/release/thash -a sha256 hkdf expand -o key=00 -o salt=00 2>/dev/null && exit 1
+######################################################################
+
+
+######################################################################
+# Tests of key derivation
+######################################################################
+
+######################################################################
+output1=$(echo -n "password" | target/release/thash kdf argon2id -o memory=256 -o salt=text:somesalt)
+output2=$(echo "9dfeb910e80bad0311fee20f9c0e2b12c17987b4cac90c2ef54d5b3021c68bfe")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "password" | target/release/thash kdf argon2i -o memory=256 -o salt=text:somesalt --phc)
+output2=$(echo '$argon2i$v=19$m=256,t=2,p=1$c29tZXNhbHQ$iekCn0Y3spW+sCcFanM2xBT63UP2sghkUoHLIUpWRS8')
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n "password" | target/release/thash kdf scrypt -o log-n=10 -o r=8 -o p=16 -o salt=text:NaCl -o output-size=64)
+output2=$(echo "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b3731622eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+echo -n "password" > password.txt
+output1=$(target/release/thash kdf pbkdf2-sha256 -f password.txt -o rounds=1000 -o salt=text:salt --phc)
+output2=$(echo '$pbkdf2-sha256$i=1000,l=32$c2FsdA$YywoEuRtRgQQK6dhjp1tfS+BKPYma0oDJk0qBGC33LM')
+
+compare_output "$output1" "$output2"
+rm password.txt
+######################################################################
+
+######################################################################
+output1=$(echo -n "password" | target/release/thash kdf pbkdf2-sha1 -o rounds=2 -o salt=text:salt -F hex-upper)
+output2=$(echo "EA6C014DC72D6F8CCD1ED92ACE1D41F0D8DE8957")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(echo -n 'My S3cre7 P@55w0rd!' | target/release/thash kdf bcrypt -o cost=5 -o salt=2671d48d6cd5c3a6c926140d2f286812 --phc)
+output2=$(echo '$2b$05$HlFShUxTu4ZHHfOLJwfmCeDj/kuKFKboanXtDJXxCC7aIPTUgxNDe')
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+# A random salt is only generated for PHC strings
+output1=$(echo -n "password" | target/release/thash kdf argon2id -o memory=64 --phc | cut -d '$' -f 1-4)
+output2=$(echo '$argon2id$v=19$m=64,t=2,p=1')
+
+compare_output "$output1" "$output2"
+echo -n "password" | target/release/thash kdf argon2id -o memory=64 2>/dev/null && exit 1
+echo -n "password" | target/release/thash -i 2 kdf scrypt -o salt=00 2>/dev/null && exit 1
+######################################################################
+
+
+######################################################################
+# Tests of password verification
+######################################################################
+
+######################################################################
+echo -n "password" | target/release/thash verify-password '$argon2id$v=19$m=256,t=2,p=1$c29tZXNhbHQ$nf65EOgLrQMR/uIPnA4rEsF5h7TKyQwu9U1bMCHGi/4'
+echo -n "wrong" | target/release/thash verify-password '$argon2id$v=19$m=256,t=2,p=1$c29tZXNhbHQ$nf65EOgLrQMR/uIPnA4rEsF5h7TKyQwu9U1bMCHGi/4' 2>/dev/null && exit 1
+######################################################################
+
+######################################################################
+echo -n "password" | target/release/thash verify-password '$pbkdf2-sha256$i=1000,l=32$c2FsdA$YywoEuRtRgQQK6dhjp1tfS+BKPYma0oDJk0qBGC33LM'
+# The line ending of a piped password is not part of it
+echo "password" | target/release/thash verify-password '$pbkdf2-sha256$i=1000,l=32$c2FsdA$YywoEuRtRgQQK6dhjp1tfS+BKPYma0oDJk0qBGC33LM'
+echo -n "My S3cre7 P@55w0rd!" | target/release/thash verify-password '$2b$05$HlFShUxTu4ZHHfOLJwfmCeDj/kuKFKboanXtDJXxCC7aIPTUgxNDe'
+echo -n "Hello world!" | target/release/thash verify-password '$5$saltstring$5B8vYYiY.CVt1RlTTf8KbXBH3hsxY/GNooZaBBGWEc5'
+######################################################################
+
+######################################################################
+# A mismatch exits with 1, and an error with 2
+status=0
+echo -n "wrong" | target/release/thash verify-password '$5$saltstring$5B8vYYiY.CVt1RlTTf8KbXBH3hsxY/GNooZaBBGWEc5' 2>/dev/null || status=$?
+compare_output "$status" "1"
+status=0
+echo -n "password" | target/release/thash verify-password '$5$rounds=x$saltstring$hash' 2>/dev/null || status=$?
+compare_output "$status" "2"
+status=0
+target/release/thash verify-password -f does-not-exist.txt '$5$saltstring$5B8vYYiY.CVt1RlTTf8KbXBH3hsxY/GNooZaBBGWEc5' 2>/dev/null || status=$?
+compare_output "$status" "2"
+######################################################################
+
+######################################################################
+# Like glibc, too few rounds are clamped to 1000
+echo -n "the minimum number is still observed" | target/release/thash verify-password '$5$rounds=10$roundstoolow$yfvwcWrQ8l/K0DAWyuPMDNHpIVlTQebY9l/gL972bIC'
+######################################################################
+
+######################################################################
+echo -n "Hello world!" > password.txt
+target/release/thash verify-password -f password.txt '$6$rounds=10000$saltstringsaltst$OW1/O6BYHV6BcXZu8QVeXbDWra3Oeqh0sbHbbMCVNSnCM/UrjmM0Dp8vOuZeHBy/YTBmSK6H9qs/y3RnOaw5v.'
+echo "Hello world!" > password.txt
+target/release/thash verify-password -f password.txt '$6$rounds=10000$saltstringsaltst$OW1/O6BYHV6BcXZu8QVeXbDWra3Oeqh0sbHbbMCVNSnCM/UrjmM0Dp8vOuZeHBy/YTBmSK6H9qs/y3RnOaw5v.' 2>/dev/null && exit 1
+rm password.txt
+######################################################################
+
+######################################################################
+# A password hash that was derived with kdf can be verified
+phc=$(echo -n "secret" | target/release/thash kdf scrypt -o log-n=4 --phc)
+echo -n "secret" | target/release/thash verify-password "$phc"
+######################################################################
+
+
+######################################################################
+# Tests of benchmarks
+######################################################################
+
+######################################################################
+output1=$(target/release/thash bench sha256,md5 --sizes 64,4K --min-time 10 2>/dev/null | grep -c "^| sha256 \|^| md5 ")
+output2=$(echo "2")
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+output1=$(target/release/thash bench blake3 --sizes 1K --min-time 10 --json 2>/dev/null | grep -o '"algorithm":"blake3","size":1024')
+output2=$(echo '"algorithm":"blake3","size":1024')
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+# Only BLAKE3 uses --threads, the other algorithms are measured without it
+output1=$(target/release/thash bench md5,blake3 --threads 2 --sizes 64 --min-time 10 --json 2>/dev/null | grep -o '"algorithm":"[a-z0-9]*"' | tr '\n' ' ')
+output2=$(echo '"algorithm":"md5" "algorithm":"blake3" ')
+
+compare_output "$output1" "$output2"
+######################################################################
+
+######################################################################
+target/release/thash bench kmac128 --sizes 64 --min-time 10 2>/dev/null && exit 1
+target/release/thash bench sha256 --sizes 0 2>/dev/null && exit 1
+######################################################################
 
 
 ######################################################################